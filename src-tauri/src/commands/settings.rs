@@ -90,6 +90,7 @@ mod tests {
         // Test that the command accepts valid settings structure
         let test_settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
 
         // Test that settings can be serialized (required for Tauri commands)
@@ -113,6 +114,7 @@ mod tests {
         // Test that AppSettings can be serialized/deserialized for Tauri commands
         let settings = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
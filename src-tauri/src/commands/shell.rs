@@ -1,8 +1,13 @@
 // This file contains the commands that are used to interact with the shell.
 
+use crate::services::safe_path;
+use crate::services::themes::icon_themes::{self, FALLBACK_ICON_THEME};
 use dirs;
 use std::process::{Command, Stdio};
 
+/// Where Omarchy installs its scripts, relative to the home directory
+const OMARCHY_BIN_DIR: &str = ".local/share/omarchy/bin";
+
 // Execute a bash command
 #[tauri::command]
 pub async fn execute_bash_command(command: String) -> Result<String, String> {
@@ -47,10 +52,13 @@ pub fn execute_bash_command_async(command: String) -> Result<(), String> {
 pub fn run_update_script(script_path: String) -> Result<(), String> {
     log::info!("Running script in Alacritty: {script_path}");
 
-    // Get absolute path to the script
+    // Only Omarchy's own scripts may be launched this way
+    let home_dir = dirs::home_dir().ok_or("Failed to get home directory")?;
     let absolute_script_path = std::env::current_dir()
         .map_err(|e| format!("Failed to get current directory: {e}"))?
         .join(&script_path);
+    let absolute_script_path =
+        safe_path::contained_in(&home_dir.join(OMARCHY_BIN_DIR), &absolute_script_path)?;
 
     // Convert to string
     let script_path_str = absolute_script_path
@@ -137,12 +145,16 @@ pub fn get_omarchy_version() -> Result<String, String> {
 // Apply theme using omarchy-theme-set
 #[tauri::command]
 pub async fn apply_theme(dir: String) -> Result<(), String> {
+    // omarchy-theme-set joins this onto the themes directory
+    safe_path::file_name(&dir)?;
 
+    let mut applied = false;
     let result = Command::new("omarchy-theme-set").arg(&dir).spawn()
         .and_then(|child| child.wait_with_output())
         .map(|output| {
             if output.status.success() {
                 log::info!("Successfully started omarchy-theme-set for theme {dir}");
+                applied = true;
             } else {
                 log::warn!("omarchy-theme-set fails with output:\n{output:?}");
             }
@@ -153,6 +165,14 @@ pub async fn apply_theme(dir: String) -> Result<(), String> {
             Ok(())
         });
 
+    // Only touch the desktop when `current/theme` really points at the new theme
+    if applied {
+        // Point GTK and Qt at the generated color files of the newly applied theme
+        crate::services::themes::desktop_links::sync_current_theme();
+        crate::services::themes::backgrounds::show_current_active();
+        crate::services::themes::theme_library::record_applied(&dir);
+    }
+
     // Invalidate cache after theme application to ensure fresh state
     if result.is_ok() {
         if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
@@ -166,9 +186,41 @@ pub async fn apply_theme(dir: String) -> Result<(), String> {
     result
 }
 
+/// Icon theme for themes without `icons.theme`: Omarchy's default when installed (or when no
+/// icon themes are found), otherwise Adwaita, otherwise the first installed theme
+fn fallback_icon_theme() -> String {
+    let installed = icon_themes::installed_icon_themes();
+    if installed.is_empty() || installed.iter().any(|t| t.id == FALLBACK_ICON_THEME) {
+        return FALLBACK_ICON_THEME.to_string();
+    }
+    installed
+        .iter()
+        .find(|t| t.id == "Adwaita")
+        .unwrap_or(&installed[0])
+        .id
+        .clone()
+}
+
+/// Warn when the current theme asks for an icon theme that is not installed
+fn warn_missing_icon_theme() {
+    let Some(path) = dirs::home_dir().map(|h| h.join(".config/omarchy/current/theme/icons.theme"))
+    else {
+        return;
+    };
+    if let Ok(content) = std::fs::read_to_string(path) {
+        let name = content.trim();
+        if !name.is_empty() && !icon_themes::is_installed(name) {
+            log::warn!("Icon theme '{name}' is not installed; GNOME will keep its current icons");
+        }
+    }
+}
+
 // Refresh apps and gnome
 #[tauri::command]
 pub fn refresh_theme_adjustments() -> Result<(), String> {
+    crate::services::themes::desktop_links::sync_current_theme();
+    warn_missing_icon_theme();
+
     // Run a best-effort, silent bash script (no terminal)
     let script = r#"
 THEME_DIR="$HOME/.config/omarchy/current/theme"
@@ -194,7 +246,7 @@ if [[ -f "$THEME_DIR/icons.theme" ]]; then
   fi
 else
   if command -v gsettings >/dev/null 2>&1; then
-    gsettings set org.gnome.desktop.interface icon-theme "Yaru-blue" || true
+    gsettings set org.gnome.desktop.interface icon-theme "$FALLBACK_ICON_THEME" || true
   fi
 fi
 
@@ -229,6 +281,7 @@ command -v hyprctl >/dev/null 2>&1 && hyprctl reload || true
     let status = Command::new("bash")
         .arg("-c")
         .arg(script)
+        .env("FALLBACK_ICON_THEME", fallback_icon_theme())
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -22,6 +22,10 @@ fn create_invoke_handler() -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool +
         commands::shell::execute_bash_command_async,
         // Theme system commands
         services::themes::get_themes::get_themes,
+        services::themes::catalog::install_catalog_theme,
+        services::themes::catalog::update_catalog_theme,
+        services::themes::catalog::uninstall_catalog_theme,
+        services::themes::catalog::check_catalog_updates,
         services::themes::get_sys_themes::get_sys_themes,
         services::themes::get_sys_themes::get_sys_theme_by_name,
         services::get_sys_themes::get_themes_cached,
@@ -36,25 +40,53 @@ fn create_invoke_handler() -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool +
         services::get_sys_themes::invalidate_system_themes_cache,
         services::get_sys_themes::invalidate_and_refresh_cache,
         services::themes::get_current_theme::get_system_theme_colors,
+        services::themes::theme_query::query_themes,
+        services::themes::theme_compare::compare_themes,
+        services::themes::theme_library::set_theme_tags,
+        services::themes::theme_library::set_theme_favorite,
+        services::themes::theme_library::list_theme_tags,
         // Custom theme commands
         services::themes::custom_themes::create_custom_theme,
         services::themes::custom_themes::create_custom_theme_advanced,
         services::themes::custom_themes::update_custom_theme,
         services::themes::custom_themes::update_custom_theme_advanced,
+        services::themes::custom_themes::create_light_variant,
+        services::themes::custom_themes::remove_light_variant,
+        services::themes::custom_themes::switch_theme_variant,
         services::themes::custom_themes::get_custom_theme,
         services::themes::custom_themes::list_custom_themes,
         services::themes::custom_themes::delete_custom_theme,
+        services::themes::bulk::delete_custom_themes,
+        services::themes::bulk::regenerate_custom_themes,
+        services::themes::bulk::export_custom_themes,
+        services::themes::bulk::import_theme_bundle,
+        services::themes::bulk::patch_custom_themes,
         services::themes::custom_themes::init_custom_theme,
+        services::themes::custom_themes::get_theme_manifest,
+        services::themes::custom_themes::update_theme_manifest,
         services::themes::custom_themes::get_app_schemas,
         services::themes::custom_themes::get_theme_backgrounds,
         services::themes::custom_themes::add_theme_backgrounds,
         services::themes::custom_themes::remove_theme_background,
         services::themes::custom_themes::get_background_image_data,
+        services::themes::custom_themes::get_background_playlist,
+        services::themes::custom_themes::reorder_theme_backgrounds,
+        services::themes::custom_themes::set_active_background,
+        services::themes::custom_themes::next_background,
+        services::themes::custom_themes::previous_background,
+        services::themes::custom_themes::set_background_rotation,
+        services::themes::custom_themes::list_btop_themes,
+        services::themes::custom_themes::convert_btop_theme,
+        services::themes::icon_themes::list_icon_themes,
         // Configuration commands
         commands::update_config::update_config,
         // Light mode commands
         services::config::light_mode::is_theme_light_mode,
         services::config::light_mode::set_theme_light_mode,
+        // Schedule commands
+        services::themes::schedule::get_schedule_status,
+        // Schema validation commands
+        services::config::schema_validation::validate_theme_data,
         // Cache commands
         services::cache::cache_config::get_cache_config,
         services::cache::cache_config::update_cache_config,
@@ -69,6 +101,15 @@ pub fn run() {
         log::warn!("Failed to setup NVIDIA compatibility: {}", e);
     }
 
+    // `schedule` and `compare` only read files, so they run without starting Tauri (and
+    // without being forwarded to a running instance)
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(exit_code) = services::themes::schedule::run_cli(&args)
+        .or_else(|| services::themes::theme_compare::run_cli(&args))
+    {
+        std::process::exit(exit_code);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_cli::init())
         .plugin(tauri_plugin_single_instance::init(
@@ -150,6 +191,15 @@ pub fn run() {
                 }
             });
 
+            // Load user generator plugins once so broken templates are reported at startup
+            services::config::generators::plugin::installed_plugins();
+
+            // Apply scheduled theme switches while the app is running
+            services::themes::schedule::start(app.handle().clone());
+
+            // Cycle the current theme's backgrounds when it has rotation enabled
+            services::themes::backgrounds::start_rotation();
+
             // Theme refresh is now handled via CLI commands through single instance plugin
 
             Ok(())
@@ -1,11 +1,227 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::terminal::{TerminalPalette, ANSI_NAMES};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use serde_json::{json, Map, Value};
+use toml::Table;
 
 pub struct AlacrittyGenerator;
 
 unsafe impl Send for AlacrittyGenerator {}
 unsafe impl Sync for AlacrittyGenerator {}
 
+const HEADER: &str = r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for Alacritty
+# Generated with Omarchist
+# ────────────────────────────────────────────────────────────
+"#;
+
+/// Keys of the optional color pairs such as `search.matches` and `footer_bar`
+const FOREGROUND_BACKGROUND: [&str; 2] = ["foreground", "background"];
+
+fn color_table<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> Table {
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), toml::Value::String(value.to_string())))
+        .collect()
+}
+
+/// Non-empty string values of `keys` in `source`, or `None` when nothing is set
+fn optional_colors(source: Option<&Value>, keys: &[&str]) -> Option<Table> {
+    let table = color_table(keys.iter().filter_map(|key| {
+        source
+            .and_then(|s| s.get(*key))
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| (*key, v))
+    }));
+    (!table.is_empty()).then_some(table)
+}
+
+/// Group optional sub-sections such as `search.matches` under one parent table
+fn optional_group(source: Option<&Value>, sections: &[&str], keys: &[&str]) -> Option<Table> {
+    let table: Table = sections
+        .iter()
+        .filter_map(|section| {
+            optional_colors(source.and_then(|s| s.get(*section)), keys)
+                .map(|t| (section.to_string(), toml::Value::Table(t)))
+        })
+        .collect();
+    (!table.is_empty()).then_some(table)
+}
+
+fn indexed_colors(source: Option<&Value>) -> Vec<toml::Value> {
+    source
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let index = entry.get("index")?.as_i64()?;
+            let color = entry.get("color")?.as_str()?.trim();
+            if !(16..=255).contains(&index) || color.is_empty() {
+                return None;
+            }
+            let mut table = Table::new();
+            table.insert("index".to_string(), toml::Value::Integer(index));
+            table.insert("color".to_string(), toml::Value::String(color.to_string()));
+            Some(toml::Value::Table(table))
+        })
+        .collect()
+}
+
+/// Alacritty's YAML-era configs wrote colors as `0xRRGGBB`
+fn normalize_legacy_color(value: &str) -> String {
+    match value.trim().strip_prefix("0x") {
+        Some(hex) => format!("#{hex}"),
+        None => value.trim().to_string(),
+    }
+}
+
+/// Convert a parsed `colors` table into theme data, keeping only strings and indexed colors
+fn colors_to_theme_data(value: &Value) -> Option<Value> {
+    match value {
+        Value::String(s) => Some(json!(normalize_legacy_color(s))),
+        Value::Number(_) => Some(value.clone()),
+        Value::Array(items) => Some(Value::Array(
+            items.iter().filter_map(colors_to_theme_data).collect(),
+        )),
+        Value::Object(map) => {
+            let converted: Map<String, Value> = map
+                .iter()
+                .filter_map(|(k, v)| colors_to_theme_data(v).map(|v| (k.clone(), v)))
+                .collect();
+            Some(Value::Object(converted))
+        },
+        _ => None,
+    }
+}
+
+/// Map a parsed Alacritty config (TOML or YAML) onto the shape of the Alacritty theme data
+pub fn theme_data_from_config(config: &Value) -> Value {
+    match config.get("colors").and_then(colors_to_theme_data) {
+        Some(colors) => json!({ "colors": colors }),
+        None => json!({}),
+    }
+}
+
+/// Parse the block-style YAML subset used by pre-0.13 `alacritty.yml` configs: nested
+/// mappings of scalars and lists of scalars (enough for `colors` and `import`).
+pub fn parse_legacy_yaml(content: &str) -> Result<Value, String> {
+    let lines: Vec<(usize, usize, &str)> = content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, raw)| {
+            let text = strip_yaml_comment(raw).trim_end();
+            let trimmed = text.trim_start();
+            if trimmed.is_empty() || trimmed == "---" {
+                return None;
+            }
+            Some((index + 1, text.len() - trimmed.len(), trimmed))
+        })
+        .collect();
+
+    let mut pos = 0;
+    let indent = lines.first().map_or(0, |(_, indent, _)| *indent);
+    let value = parse_yaml_block(&lines, &mut pos, indent)?;
+    if let Some((line_no, _, text)) = lines.get(pos) {
+        return Err(format!("line {line_no}: unexpected '{text}'"));
+    }
+    Ok(value)
+}
+
+fn parse_yaml_block(
+    lines: &[(usize, usize, &str)],
+    pos: &mut usize,
+    indent: usize,
+) -> Result<Value, String> {
+    let is_list = lines
+        .get(*pos)
+        .is_some_and(|(_, _, text)| text.starts_with("- ") || *text == "-");
+
+    if is_list {
+        let mut items = Vec::new();
+        while let Some((_, line_indent, text)) = lines.get(*pos) {
+            if *line_indent != indent || !(text.starts_with("- ") || *text == "-") {
+                break;
+            }
+            items.push(yaml_scalar(text.trim_start_matches('-').trim()));
+            *pos += 1;
+        }
+        return Ok(Value::Array(items));
+    }
+
+    let mut map = Map::new();
+    while let Some((line_no, line_indent, text)) = lines.get(*pos) {
+        if *line_indent < indent {
+            break;
+        }
+        if *line_indent > indent {
+            return Err(format!("line {line_no}: unexpected indentation"));
+        }
+        let (key, rest) = match text.split_once(": ") {
+            Some((key, rest)) => (key, rest.trim()),
+            None => match text.strip_suffix(':') {
+                Some(key) => (key, ""),
+                None => return Err(format!("line {line_no}: expected 'key: value'")),
+            },
+        };
+        *pos += 1;
+
+        let value = if !rest.is_empty() {
+            yaml_scalar(rest)
+        } else {
+            match lines.get(*pos) {
+                Some((_, child_indent, _)) if *child_indent > indent => {
+                    parse_yaml_block(lines, pos, *child_indent)?
+                },
+                // Lists are allowed at the same indentation as their key
+                Some((_, child_indent, child))
+                    if *child_indent == indent && child.starts_with("- ") =>
+                {
+                    parse_yaml_block(lines, pos, indent)?
+                },
+                _ => Value::Null,
+            }
+        };
+        map.insert(unquote(key.trim()).to_string(), value);
+    }
+    Ok(Value::Object(map))
+}
+
+fn strip_yaml_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) if i == 0 || line[..i].ends_with(char::is_whitespace) => {
+                return &line[..i];
+            },
+            _ => {},
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+fn yaml_scalar(value: &str) -> Value {
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "~" | "null" => Value::Null,
+        _ => Value::String(unquote(value).to_string()),
+    }
+}
+
 impl ConfigGenerator for AlacrittyGenerator {
     fn get_app_name(&self) -> &'static str {
         "alacritty"
@@ -16,217 +232,141 @@ impl ConfigGenerator for AlacrittyGenerator {
     }
 
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
-        let empty_obj = json!({});
-        let alacritty = theme_data.get("alacritty").unwrap_or(&empty_obj);
-
-        // Primary colors
-        let primary_bg = alacritty
-            .get("colors")
-            .and_then(|c| c.get("primary"))
-            .and_then(|p| p.get("background"))
-            .and_then(|b| b.as_str())
-            .unwrap_or("#121212");
-        let primary_fg = alacritty
-            .get("colors")
-            .and_then(|c| c.get("primary"))
-            .and_then(|p| p.get("foreground"))
-            .and_then(|f| f.as_str())
-            .unwrap_or("#bebebe");
-        let dim_fg = alacritty
-            .get("colors")
-            .and_then(|c| c.get("primary"))
-            .and_then(|p| p.get("dim_foreground"))
-            .and_then(|d| d.as_str())
-            .unwrap_or("#8a8a8d");
-
-        // Normal colors
-        let empty_normal = json!({});
-        let normal = alacritty
-            .get("colors")
-            .and_then(|c| c.get("normal"))
-            .unwrap_or(&empty_normal);
-        let normal_black = normal
-            .get("black")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#333333");
-        let normal_red = normal
-            .get("red")
-            .and_then(|r| r.as_str())
-            .unwrap_or("#D35F5F");
-        let normal_green = normal
-            .get("green")
-            .and_then(|g| g.as_str())
-            .unwrap_or("#FFC107");
-        let normal_yellow = normal
-            .get("yellow")
-            .and_then(|y| y.as_str())
-            .unwrap_or("#b91c1c");
-        let normal_blue = normal
-            .get("blue")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#e68e0d");
-        let normal_magenta = normal
-            .get("magenta")
-            .and_then(|m| m.as_str())
-            .unwrap_or("#D35F5F");
-        let normal_cyan = normal
-            .get("cyan")
-            .and_then(|c| c.as_str())
-            .unwrap_or("#bebebe");
-        let normal_white = normal
-            .get("white")
-            .and_then(|w| w.as_str())
-            .unwrap_or("#bebebe");
-
-        // Bright colors
-        let empty_bright = json!({});
-        let bright = alacritty
-            .get("colors")
-            .and_then(|c| c.get("bright"))
-            .unwrap_or(&empty_bright);
-        let bright_black = bright
-            .get("black")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#8a8a8d");
-        let bright_red = bright
-            .get("red")
-            .and_then(|r| r.as_str())
-            .unwrap_or("#B91C1C");
-        let bright_green = bright
-            .get("green")
-            .and_then(|g| g.as_str())
-            .unwrap_or("#FFC107");
-        let bright_yellow = bright
-            .get("yellow")
-            .and_then(|y| y.as_str())
-            .unwrap_or("#b90a0a");
-        let bright_blue = bright
-            .get("blue")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#f59e0b");
-        let bright_magenta = bright
-            .get("magenta")
-            .and_then(|m| m.as_str())
-            .unwrap_or("#b91c1c");
-        let bright_cyan = bright
-            .get("cyan")
-            .and_then(|c| c.as_str())
-            .unwrap_or("#eaeaea");
-        let bright_white = bright
-            .get("white")
-            .and_then(|w| w.as_str())
-            .unwrap_or("#eaeaea");
-
-        // Extract font settings
-        // let font_size = alacritty
-        //     .get("font")
-        //     .and_then(|f| f.get("size"))
-        //     .and_then(|s| s.as_f64())
-        //     .unwrap_or(12.0);
-
-        // Extract window settings
-        // let empty_window = json!({});
-        // let window = alacritty.get("window").unwrap_or(&empty_window);
-        // let padding_x = window
-        //     .get("padding")
-        //     .and_then(|p| p.get("x"))
-        //     .and_then(|x| x.as_i64())
-        //     .unwrap_or(12);
-        // let padding_y = window
-        //     .get("padding")
-        //     .and_then(|p| p.get("y"))
-        //     .and_then(|y| y.as_i64())
-        //     .unwrap_or(12);
-
-        // Cursor colors
-        let empty_cursor = json!({});
-        let cursor_obj = alacritty
-            .get("colors")
-            .and_then(|c| c.get("cursor"))
-            .unwrap_or(&empty_cursor);
-        let cursor_text = cursor_obj
-            .get("text")
-            .and_then(|v| v.as_str())
-            .unwrap_or("#121212");
-        let cursor_cursor = cursor_obj
-            .get("cursor")
-            .and_then(|v| v.as_str())
-            .unwrap_or("#eaeaea");
-
-        // Vi mode cursor colors
-        // let empty_vi_cursor = json!({});
-        // let vi_cursor_obj = alacritty
-        //     .get("colors")
-        //     .and_then(|c| c.get("vi_mode_cursor"))
-        //     .unwrap_or(&empty_vi_cursor);
-        // let vi_cursor_text = vi_cursor_obj
-        //     .get("text")
-        //     .and_then(|v| v.as_str())
-        //     .unwrap_or("#121212");
-        // let vi_cursor_cursor = vi_cursor_obj
-        //     .get("cursor")
-        //     .and_then(|v| v.as_str())
-        //     .unwrap_or("#eaeaea");
-
-        // Selection
-        let empty_selection = json!({});
-        let selection_obj = alacritty
-            .get("colors")
-            .and_then(|c| c.get("selection"))
-            .unwrap_or(&empty_selection);
-        let selection_background = selection_obj
-            .get("background")
+        let palette = TerminalPalette::resolve(theme_data, "alacritty");
+        let colors_data = theme_data.get("alacritty").and_then(|a| a.get("colors"));
+        let section = |name: &str| colors_data.and_then(|c| c.get(name));
+        let mut colors = Table::new();
+        let mut insert = |name: &str, table: Table| {
+            colors.insert(name.to_string(), toml::Value::Table(table));
+        };
+
+        let mut primary = color_table([
+            ("background", palette.background.as_str()),
+            ("foreground", palette.foreground.as_str()),
+            ("dim_foreground", palette.dim_foreground.as_str()),
+        ]);
+        if let Some(extra) = optional_colors(section("primary"), &["bright_foreground"]) {
+            primary.extend(extra);
+        }
+        insert("primary", primary);
+
+        insert(
+            "cursor",
+            color_table([
+                ("text", palette.cursor_text.as_str()),
+                ("cursor", palette.cursor.as_str()),
+            ]),
+        );
+        if let Some(table) = optional_colors(section("vi_mode_cursor"), &["text", "cursor"]) {
+            insert("vi_mode_cursor", table);
+        }
+
+        let selection_text = section("selection")
+            .and_then(|s| s.get("text"))
             .and_then(|v| v.as_str())
-            .unwrap_or("#333333");
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or("CellForeground");
+        insert(
+            "selection",
+            color_table([
+                ("text", selection_text),
+                ("background", palette.selection_background.as_str()),
+            ]),
+        );
 
-        Ok(format!(
-            r#"# ────────────────────────────────────────────────────────────
-# Omarchy Custom Theme for Alacritty
-# Generated with Omarchist
-# ────────────────────────────────────────────────────────────
+        if let Some(table) = optional_group(
+            section("search"),
+            &["matches", "focused_match"],
+            &FOREGROUND_BACKGROUND,
+        ) {
+            insert("search", table);
+        }
+        if let Some(table) =
+            optional_group(section("hints"), &["start", "end"], &FOREGROUND_BACKGROUND)
+        {
+            insert("hints", table);
+        }
+        if let Some(table) = optional_colors(section("footer_bar"), &FOREGROUND_BACKGROUND) {
+            insert("footer_bar", table);
+        }
+
+        insert(
+            "normal",
+            color_table(
+                ANSI_NAMES
+                    .iter()
+                    .copied()
+                    .zip(palette.normal.iter().map(String::as_str)),
+            ),
+        );
+        insert(
+            "bright",
+            color_table(
+                ANSI_NAMES
+                    .iter()
+                    .copied()
+                    .zip(palette.bright.iter().map(String::as_str)),
+            ),
+        );
+        if let Some(table) = optional_colors(section("dim"), &ANSI_NAMES) {
+            insert("dim", table);
+        }
+
+        let indexed = indexed_colors(section("indexed_colors"));
+        if !indexed.is_empty() {
+            colors.insert("indexed_colors".to_string(), toml::Value::Array(indexed));
+        }
 
-[colors]
-[colors.primary]
-background = "{primary_bg}"
-foreground = "{primary_fg}"
-dim_foreground = "{dim_fg}"
-
-[colors.cursor]
-text = "{cursor_text}"
-cursor = "{cursor_cursor}"
-
-[colors.selection]
-text = "CellForeground"
-background = "{selection_background}"
-
-[colors.normal]
-black = "{normal_black}"
-red = "{normal_red}"
-green = "{normal_green}"
-yellow = "{normal_yellow}"
-blue = "{normal_blue}"
-magenta = "{normal_magenta}"
-cyan = "{normal_cyan}"
-white = "{normal_white}"
-
-[colors.bright]
-black = "{bright_black}"
-red = "{bright_red}"
-green = "{bright_green}"
-yellow = "{bright_yellow}"
-blue = "{bright_blue}"
-magenta = "{bright_magenta}"
-cyan = "{bright_cyan}"
-white = "{bright_white}"
-"#
-        ))
+        let mut root = Table::new();
+        root.insert("colors".to_string(), toml::Value::Table(colors));
+        let body = toml::to_string(&root)
+            .map_err(|e| format!("Failed to serialize Alacritty config: {e}"))?;
+
+        Ok(format!("{HEADER}\n{body}"))
     }
 
     fn get_config_schema(&self) -> Value {
+        let color = |title: &str| json!({"type": "string", "format": "color", "title": title});
+        let optional = |title: &str| {
+            json!({
+                "type": "string",
+                "format": "color",
+                "title": title,
+                "default": "",
+                "description": "Leave empty to use Alacritty's default"
+            })
+        };
+        let foreground_background = |title: &str| {
+            json!({
+                "type": "object",
+                "title": title,
+                "x-order": FOREGROUND_BACKGROUND,
+                "properties": {
+                    "foreground": optional(&format!("{title} Foreground")),
+                    "background": optional(&format!("{title} Background"))
+                }
+            })
+        };
+        let ansi_section = |prefix: &str, make: &dyn Fn(&str) -> Value| {
+            let properties: Map<String, Value> = ANSI_NAMES
+                .iter()
+                .map(|name| {
+                    let mut title = name.to_string();
+                    title[..1].make_ascii_uppercase();
+                    (name.to_string(), make(&format!("{prefix}{title}")))
+                })
+                .collect();
+            json!({
+                "type": "object",
+                "x-order": ANSI_NAMES,
+                "properties": properties
+            })
+        };
+
         json!({
             "type": "object",
             // UI ordering for top-level properties
-            "x-order": ["colors", "font", "window"],
+            "x-order": ["colors"],
             "properties": {
                 "colors": {
                     "type": "object",
@@ -236,17 +376,23 @@ white = "{bright_white}"
                         "cursor",
                         "vi_mode_cursor",
                         "selection",
+                        "search",
+                        "hints",
+                        "footer_bar",
                         "normal",
-                        "bright"
+                        "bright",
+                        "dim",
+                        "indexed_colors"
                     ],
                     "properties": {
                         "primary": {
                             "type": "object",
-                            "x-order": ["background", "foreground", "dim_foreground"],
+                            "x-order": ["background", "foreground", "dim_foreground", "bright_foreground"],
                             "properties": {
-                                "background": {"type": "string", "format": "color", "title": "Background Color"},
-                                "foreground": {"type": "string", "format": "color", "title": "Foreground Color"},
-                                "dim_foreground": {"type": "string", "format": "color", "title": "Dim Foreground Color"}
+                                "background": color("Background Color"),
+                                "foreground": color("Foreground Color"),
+                                "dim_foreground": color("Dim Foreground Color"),
+                                "bright_foreground": optional("Bright Foreground Color")
                             }
                         },
                         "cursor": {
@@ -256,38 +402,57 @@ white = "{bright_white}"
                                 "cursor": {"type": "string", "format": "color", "title": "Cursor Color", "default": "#EAEAEA"}
                             }
                         },
+                        "vi_mode_cursor": {
+                            "type": "object",
+                            "properties": {
+                                "text": optional("Vi Mode Cursor Text"),
+                                "cursor": optional("Vi Mode Cursor Color")
+                            }
+                        },
                         "selection": {
                             "type": "object",
+                            "x-order": ["background", "text"],
                             "properties": {
-                                "background": {"type": "string", "format": "color", "title": "Selection Background", "default": "#333333"}
+                                "background": {"type": "string", "format": "color", "title": "Selection Background", "default": "#333333"},
+                                "text": {
+                                    "type": "string",
+                                    "title": "Selection Text",
+                                    "description": "A color, CellForeground or CellBackground",
+                                    "default": "CellForeground"
+                                }
                             }
                         },
-                        "normal": {
+                        "search": {
                             "type": "object",
-                            "x-order": ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"],
+                            "x-order": ["matches", "focused_match"],
                             "properties": {
-                                "black": {"type": "string", "format": "color", "title": "Black"},
-                                "red": {"type": "string", "format": "color", "title": "Red"},
-                                "green": {"type": "string", "format": "color", "title": "Green"},
-                                "yellow": {"type": "string", "format": "color", "title": "Yellow"},
-                                "blue": {"type": "string", "format": "color", "title": "Blue"},
-                                "magenta": {"type": "string", "format": "color", "title": "Magenta"},
-                                "cyan": {"type": "string", "format": "color", "title": "Cyan"},
-                                "white": {"type": "string", "format": "color", "title": "White"}
+                                "matches": foreground_background("Search Match"),
+                                "focused_match": foreground_background("Focused Match")
                             }
                         },
-                        "bright": {
+                        "hints": {
                             "type": "object",
-                            "x-order": ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"],
+                            "x-order": ["start", "end"],
                             "properties": {
-                                "black": {"type": "string", "format": "color", "title": "Bright Black"},
-                                "red": {"type": "string", "format": "color", "title": "Bright Red"},
-                                "green": {"type": "string", "format": "color", "title": "Bright Green"},
-                                "yellow": {"type": "string", "format": "color", "title": "Bright Yellow"},
-                                "blue": {"type": "string", "format": "color", "title": "Bright Blue"},
-                                "magenta": {"type": "string", "format": "color", "title": "Bright Magenta"},
-                                "cyan": {"type": "string", "format": "color", "title": "Bright Cyan"},
-                                "white": {"type": "string", "format": "color", "title": "Bright White"}
+                                "start": foreground_background("Hint Start"),
+                                "end": foreground_background("Hint End")
+                            }
+                        },
+                        "footer_bar": foreground_background("Footer Bar"),
+                        "normal": ansi_section("", &color),
+                        "bright": ansi_section("Bright ", &color),
+                        "dim": ansi_section("Dim ", &optional),
+                        "indexed_colors": {
+                            "type": "array",
+                            "title": "Indexed Colors",
+                            "description": "Overrides for palette entries 16-255",
+                            "default": [],
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "index": {"type": "integer", "title": "Index", "minimum": 16, "maximum": 255},
+                                    "color": color("Color")
+                                }
                             }
                         }
                     }
@@ -296,8 +461,119 @@ white = "{bright_white}"
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement TOML parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let config: Value = toml::from_str(content)
+            .or_else(|_| parse_legacy_yaml(content))
+            .map_err(|e| format!("Failed to parse Alacritty config: {e}"))?;
+
+        Ok(theme_data_from_config(&config))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_toml(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_produce_valid_toml() {
+        let config = AlacrittyGenerator.generate_config(&json!({})).unwrap();
+        assert!(config.starts_with(HEADER));
+        assert!(AlacrittyGenerator.validate_output(&config).is_ok());
+
+        let parsed: Table = toml::from_str(&config).unwrap();
+        let colors = parsed["colors"].as_table().unwrap();
+        let sections: Vec<&str> = colors.keys().map(String::as_str).collect();
+        assert_eq!(
+            sections,
+            vec!["primary", "cursor", "selection", "normal", "bright"]
+        );
+        assert_eq!(colors["selection"]["text"].as_str(), Some("CellForeground"));
+    }
+
+    #[test]
+    fn test_optional_sections_are_written_when_set() {
+        let theme_data = json!({
+            "alacritty": { "colors": {
+                "vi_mode_cursor": { "text": "", "cursor": "#ff00ff" },
+                "search": {
+                    "matches": { "foreground": "#000000", "background": "#ffff00" },
+                    "focused_match": { "foreground": "", "background": "" }
+                },
+                "hints": { "start": { "background": "#00ff00" } },
+                "footer_bar": { "foreground": "", "background": "" },
+                "dim": { "red": "#550000" },
+                "indexed_colors": [
+                    { "index": 16, "color": "#ff9e64" },
+                    { "index": 3, "color": "#000000" }
+                ]
+            }}
+        });
+        let config = AlacrittyGenerator.generate_config(&theme_data).unwrap();
+        let parsed: Table = toml::from_str(&config).unwrap();
+        let colors = parsed["colors"].as_table().unwrap();
+
+        assert_eq!(colors["vi_mode_cursor"].as_table().unwrap().len(), 1);
+        assert_eq!(
+            colors["search"]["matches"]["background"].as_str(),
+            Some("#ffff00")
+        );
+        assert!(colors["search"].get("focused_match").is_none());
+        assert_eq!(
+            colors["hints"]["start"]["background"].as_str(),
+            Some("#00ff00")
+        );
+        assert!(colors.get("footer_bar").is_none());
+        assert_eq!(colors["dim"]["red"].as_str(), Some("#550000"));
+        let indexed = colors["indexed_colors"].as_array().unwrap();
+        assert_eq!(indexed.len(), 1);
+        assert_eq!(indexed[0]["index"].as_integer(), Some(16));
+    }
+
+    #[test]
+    fn test_round_trip_through_parser() {
+        let theme_data = json!({
+            "alacritty": { "colors": {
+                "primary": { "background": "#1a1b26", "foreground": "#c0caf5" },
+                "search": { "matches": { "foreground": "#1a1b26", "background": "#ff9e64" } },
+                "indexed_colors": [{ "index": 17, "color": "#db4b4b" }]
+            }}
+        });
+        let config = AlacrittyGenerator.generate_config(&theme_data).unwrap();
+        let parsed = AlacrittyGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["primary"]["background"], "#1a1b26");
+        assert_eq!(
+            parsed["colors"]["search"]["matches"]["background"],
+            "#ff9e64"
+        );
+        assert_eq!(parsed["colors"]["indexed_colors"][0]["index"], 17);
+    }
+
+    #[test]
+    fn test_parse_legacy_yaml() {
+        let content = r##"
+# Colors (Tomorrow Night)
+colors:
+  primary:
+    background: '0x1d1f21'
+    foreground: "#c5c8c6" # inline comment
+  normal:
+    red:   '0xcc6666'
+import:
+- ~/.config/alacritty/base.yml
+"##;
+        let parsed = parse_legacy_yaml(content).unwrap();
+        assert_eq!(parsed["colors"]["primary"]["background"], "0x1d1f21");
+        assert_eq!(parsed["colors"]["primary"]["foreground"], "#c5c8c6");
+        assert_eq!(parsed["import"][0], "~/.config/alacritty/base.yml");
+
+        let theme = AlacrittyGenerator.parse_existing_config(content).unwrap();
+        assert_eq!(theme["colors"]["primary"]["background"], "#1d1f21");
+        assert_eq!(theme["colors"]["normal"]["red"], "#cc6666");
+
+        assert!(parse_legacy_yaml("colors:\n  primary:\n      bad\n").is_err());
     }
 }
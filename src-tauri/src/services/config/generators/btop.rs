@@ -1,5 +1,10 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::oklch;
+use crate::services::settings::app_settings;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[allow(unused_macros)]
 macro_rules! big_json {
@@ -13,6 +18,114 @@ pub struct BtopGenerator;
 unsafe impl Send for BtopGenerator {}
 unsafe impl Sync for BtopGenerator {}
 
+/// Color sections of the btop schema, in display order
+const SECTIONS: [&str; 6] = ["basic", "boxes", "temperature", "cpu", "memory", "network"];
+
+/// Read `theme[key]=value` entries the way btop does: the value may be double-quoted,
+/// single-quoted or bare, and an empty value means the terminal default.
+/// Anything that isn't a `theme[...]` line is ignored.
+pub fn parse_btop_theme(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("theme[")?;
+            let (key, rest) = rest.split_once(']')?;
+            let value = rest.trim_start().strip_prefix('=')?.trim_start();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+                _ => value.split_whitespace().next().unwrap_or_default(),
+            };
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Convert a btop color to `#rrggbb`. btop also accepts `#GG` greyscale and decimal `R G B`;
+/// empty stays empty.
+pub fn normalize_btop_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(String::new());
+    }
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        return match hex.len() {
+            6 => Some(format!("#{}", hex.to_lowercase())),
+            2 => Some(format!("#{}", hex.to_lowercase().repeat(3))),
+            _ => None,
+        };
+    }
+
+    let channels: Vec<u8> = value
+        .split_whitespace()
+        .map(|c| c.parse().ok())
+        .collect::<Option<_>>()?;
+    match channels.as_slice() {
+        [r, g, b] => Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        _ => None,
+    }
+}
+
+/// A `.theme` file shipped with btop or installed in the user's btop config
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BundledBtopTheme {
+    pub name: String,
+    pub path: String,
+}
+
+/// Where btop looks for themes: the system install locations, then `~/.config/btop/themes`
+pub fn bundled_theme_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/share/btop/themes"),
+        PathBuf::from("/usr/local/share/btop/themes"),
+    ];
+    if let Some(config_dir) = app_settings::config_home() {
+        dirs.push(config_dir.join("btop").join("themes"));
+    }
+    dirs
+}
+
+/// Every `.theme` file in `dirs`, sorted by name. Earlier directories win on name clashes.
+pub fn list_bundled_themes(dirs: &[PathBuf]) -> Vec<BundledBtopTheme> {
+    let mut themes: Vec<BundledBtopTheme> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().map_or(true, |ext| ext != "theme") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if themes.iter().any(|t| t.name == name) {
+                continue;
+            }
+            themes.push(BundledBtopTheme {
+                name: name.to_string(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Convert a btop `.theme` file into custom-theme `apps` JSON (`{ "btop": { "colors": ... } }`)
+pub fn convert_theme_file(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read btop theme {}: {e}", path.display()))?;
+    let btop = BtopGenerator.parse_existing_config(&content)?;
+    if btop["colors"].as_object().map_or(true, |c| c.is_empty()) {
+        return Err(format!("{} has no btop colors", path.display()));
+    }
+    Ok(json!({ "btop": btop }))
+}
+
 impl ConfigGenerator for BtopGenerator {
     fn get_app_name(&self) -> &'static str {
         "btop"
@@ -29,13 +142,23 @@ impl ConfigGenerator for BtopGenerator {
 
         // gets nested color values from sections
         let get_color = |section: &str, field: &str, default: &str| -> String {
-            colors.get(section)
+            colors
+                .get(section)
                 .and_then(|s| s.get(field))
                 .and_then(|v| v.as_str())
                 .unwrap_or(default)
                 .to_string()
         };
 
+        // An unset mid blends start and end so two-color gradients stay smooth
+        let gradient_mid = |section: &str, field: &str, start: &str, end: &str| -> String {
+            let mid = get_color(section, field, "");
+            if !mid.trim().is_empty() {
+                return mid;
+            }
+            oklch::midpoint(start, end).unwrap_or_else(|| "#f59e0b".to_string())
+        };
+
         // Extract colors with defaults from template
         let main_bg = get_color("basic", "main_bg", "");
         let main_fg = get_color("basic", "main_fg", "#EAEAEA");
@@ -51,29 +174,30 @@ impl ConfigGenerator for BtopGenerator {
         let proc_box = get_color("boxes", "proc_box", "#8a8a8d");
         let div_line = get_color("boxes", "div_line", "#8a8a8d");
         let temp_start = get_color("temperature", "temp_start", "#8a8a8d");
-        let temp_mid = get_color("temperature", "temp_mid", "#f59e0b");
         let temp_end = get_color("temperature", "temp_end", "#b91c1c");
+        let temp_mid = gradient_mid("temperature", "temp_mid", &temp_start, &temp_end);
         let cpu_start = get_color("cpu", "cpu_start", "#8a8a8d");
-        let cpu_mid = get_color("cpu", "cpu_mid", "#f59e0b");
         let cpu_end = get_color("cpu", "cpu_end", "#b91c1c");
+        let cpu_mid = gradient_mid("cpu", "cpu_mid", &cpu_start, &cpu_end);
         let free_start = get_color("memory", "free_start", "#8a8a8d");
-        let free_mid = get_color("memory", "free_mid", "#f59e0b");
         let free_end = get_color("memory", "free_end", "#b91c1c");
+        let free_mid = gradient_mid("memory", "free_mid", &free_start, &free_end);
         let cached_start = get_color("memory", "cached_start", "#8a8a8d");
-        let cached_mid = get_color("memory", "cached_mid", "#f59e0b");
         let cached_end = get_color("memory", "cached_end", "#b91c1c");
+        let cached_mid = gradient_mid("memory", "cached_mid", &cached_start, &cached_end);
         let available_start = get_color("memory", "available_start", "#8a8a8d");
-        let available_mid = get_color("memory", "available_mid", "#f59e0b");
         let available_end = get_color("memory", "available_end", "#b91c1c");
+        let available_mid =
+            gradient_mid("memory", "available_mid", &available_start, &available_end);
         let used_start = get_color("memory", "used_start", "#8a8a8d");
-        let used_mid = get_color("memory", "used_mid", "#f59e0b");
         let used_end = get_color("memory", "used_end", "#b91c1c");
+        let used_mid = gradient_mid("memory", "used_mid", &used_start, &used_end);
         let download_start = get_color("network", "download_start", "#8a8a8d");
-        let download_mid = get_color("network", "download_mid", "#f59e0b");
         let download_end = get_color("network", "download_end", "#b91c1c");
+        let download_mid = gradient_mid("network", "download_mid", &download_start, &download_end);
         let upload_start = get_color("network", "upload_start", "#8a8a8d");
-        let upload_mid = get_color("network", "upload_mid", "#f59e0b");
         let upload_end = get_color("network", "upload_end", "#b91c1c");
+        let upload_mid = gradient_mid("network", "upload_mid", &upload_start, &upload_end);
 
         Ok(format!(
             r#"# ────────────────────────────────────────────────────────────
@@ -182,7 +306,7 @@ theme[upload_end]="{upload_end}"
         basic_properties.insert("proc_misc".to_string(), json!({"type": "string", "format": "color", "title": "Process Misc", "default": "#8a8a8d"}));
         properties.insert(
             "basic".to_string(),
-            json!({"type": "object", "title": "Basic Colors", "properties": basic_properties}),
+            json!({"type": "object", "title": "Basic Colors", "x-order": ["main_bg", "main_fg", "title", "hi_fg", "selected_bg", "selected_fg", "inactive_fg", "proc_misc"], "properties": basic_properties}),
         );
 
         // Box Outlines Section
@@ -194,27 +318,27 @@ theme[upload_end]="{upload_end}"
         box_properties.insert("div_line".to_string(), json!({"type": "string", "format": "color", "title": "Divider Line", "default": "#8a8a8d"}));
         properties.insert(
             "boxes".to_string(),
-            json!({"type": "object", "title": "Box Outlines", "properties": box_properties}),
+            json!({"type": "object", "title": "Box Outlines", "x-order": ["cpu_box", "mem_box", "net_box", "proc_box", "div_line"], "properties": box_properties}),
         );
 
         // Temperature Graph Section
         let mut temp_properties = serde_json::Map::new();
         temp_properties.insert("temp_start".to_string(), json!({"type": "string", "format": "color", "title": "Start Color", "default": "#8a8a8d"}));
-        temp_properties.insert("temp_mid".to_string(), json!({"type": "string", "format": "color", "title": "Mid Color", "default": "#f59e0b"}));
+        temp_properties.insert("temp_mid".to_string(), json!({"type": "string", "format": "color", "title": "Mid Color", "default": "", "description": "Leave empty to blend start and end"}));
         temp_properties.insert("temp_end".to_string(), json!({"type": "string", "format": "color", "title": "End Color", "default": "#b91c1c"}));
         properties.insert(
             "temperature".to_string(),
-            json!({"type": "object", "title": "Temperature Graph", "properties": temp_properties}),
+            json!({"type": "object", "title": "Temperature Graph", "x-order": ["temp_start", "temp_mid", "temp_end"], "properties": temp_properties}),
         );
 
         // CPU Graph Section
         let mut cpu_properties = serde_json::Map::new();
         cpu_properties.insert("cpu_start".to_string(), json!({"type": "string", "format": "color", "title": "Start Color", "default": "#8a8a8d"}));
-        cpu_properties.insert("cpu_mid".to_string(), json!({"type": "string", "format": "color", "title": "Mid Color", "default": "#f59e0b"}));
+        cpu_properties.insert("cpu_mid".to_string(), json!({"type": "string", "format": "color", "title": "Mid Color", "default": "", "description": "Leave empty to blend start and end"}));
         cpu_properties.insert("cpu_end".to_string(), json!({"type": "string", "format": "color", "title": "End Color", "default": "#b91c1c"}));
         properties.insert(
             "cpu".to_string(),
-            json!({"type": "object", "title": "CPU Graph", "properties": cpu_properties}),
+            json!({"type": "object", "title": "CPU Graph", "x-order": ["cpu_start", "cpu_mid", "cpu_end"], "properties": cpu_properties}),
         );
 
         // Memory Meters Section
@@ -222,22 +346,22 @@ theme[upload_end]="{upload_end}"
         memory_properties.insert("free_start".to_string(), json!({"type": "string", "format": "color", "title": "Free Start", "default": "#8a8a8d"}));
         memory_properties.insert(
             "free_mid".to_string(),
-            json!({"type": "string", "format": "color", "title": "Free Mid", "default": "#f59e0b"}),
+            json!({"type": "string", "format": "color", "title": "Free Mid", "default": "", "description": "Leave empty to blend start and end"}),
         );
         memory_properties.insert(
             "free_end".to_string(),
             json!({"type": "string", "format": "color", "title": "Free End", "default": "#b91c1c"}),
         );
         memory_properties.insert("cached_start".to_string(), json!({"type": "string", "format": "color", "title": "Cached Start", "default": "#8a8a8d"}));
-        memory_properties.insert("cached_mid".to_string(), json!({"type": "string", "format": "color", "title": "Cached Mid", "default": "#f59e0b"}));
+        memory_properties.insert("cached_mid".to_string(), json!({"type": "string", "format": "color", "title": "Cached Mid", "default": "", "description": "Leave empty to blend start and end"}));
         memory_properties.insert("cached_end".to_string(), json!({"type": "string", "format": "color", "title": "Cached End", "default": "#b91c1c"}));
         memory_properties.insert("available_start".to_string(), json!({"type": "string", "format": "color", "title": "Available Start", "default": "#8a8a8d"}));
-        memory_properties.insert("available_mid".to_string(), json!({"type": "string", "format": "color", "title": "Available Mid", "default": "#f59e0b"}));
+        memory_properties.insert("available_mid".to_string(), json!({"type": "string", "format": "color", "title": "Available Mid", "default": "", "description": "Leave empty to blend start and end"}));
         memory_properties.insert("available_end".to_string(), json!({"type": "string", "format": "color", "title": "Available End", "default": "#b91c1c"}));
         memory_properties.insert("used_start".to_string(), json!({"type": "string", "format": "color", "title": "Used Start", "default": "#8a8a8d"}));
         memory_properties.insert(
             "used_mid".to_string(),
-            json!({"type": "string", "format": "color", "title": "Used Mid", "default": "#f59e0b"}),
+            json!({"type": "string", "format": "color", "title": "Used Mid", "default": "", "description": "Leave empty to blend start and end"}),
         );
         memory_properties.insert(
             "used_end".to_string(),
@@ -245,20 +369,20 @@ theme[upload_end]="{upload_end}"
         );
         properties.insert(
             "memory".to_string(),
-            json!({"type": "object", "title": "Memory Meters", "properties": memory_properties}),
+            json!({"type": "object", "title": "Memory Meters", "x-order": ["free_start", "free_mid", "free_end", "cached_start", "cached_mid", "cached_end", "available_start", "available_mid", "available_end", "used_start", "used_mid", "used_end"], "properties": memory_properties}),
         );
 
         // Network Meters Section
         let mut network_properties = serde_json::Map::new();
         network_properties.insert("download_start".to_string(), json!({"type": "string", "format": "color", "title": "Download Start", "default": "#8a8a8d"}));
-        network_properties.insert("download_mid".to_string(), json!({"type": "string", "format": "color", "title": "Download Mid", "default": "#f59e0b"}));
+        network_properties.insert("download_mid".to_string(), json!({"type": "string", "format": "color", "title": "Download Mid", "default": "", "description": "Leave empty to blend start and end"}));
         network_properties.insert("download_end".to_string(), json!({"type": "string", "format": "color", "title": "Download End", "default": "#b91c1c"}));
         network_properties.insert("upload_start".to_string(), json!({"type": "string", "format": "color", "title": "Upload Start", "default": "#8a8a8d"}));
-        network_properties.insert("upload_mid".to_string(), json!({"type": "string", "format": "color", "title": "Upload Mid", "default": "#f59e0b"}));
+        network_properties.insert("upload_mid".to_string(), json!({"type": "string", "format": "color", "title": "Upload Mid", "default": "", "description": "Leave empty to blend start and end"}));
         network_properties.insert("upload_end".to_string(), json!({"type": "string", "format": "color", "title": "Upload End", "default": "#b91c1c"}));
         properties.insert(
             "network".to_string(),
-            json!({"type": "object", "title": "Network Meters", "properties": network_properties}),
+            json!({"type": "object", "title": "Network Meters", "x-order": ["download_start", "download_mid", "download_end", "upload_start", "upload_mid", "upload_end"], "properties": network_properties}),
         );
 
         json!({
@@ -266,14 +390,132 @@ theme[upload_end]="{upload_end}"
             "properties": {
                 "colors": {
                     "type": "object",
+                    "x-order": SECTIONS,
                     "properties": properties
                 }
             }
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement theme file parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let schema = self.get_config_schema();
+        let sections = &schema["properties"]["colors"]["properties"];
+        let mut colors = Map::new();
+
+        for (key, value) in parse_btop_theme(content) {
+            let Some(section) = SECTIONS
+                .iter()
+                .find(|section| sections[**section]["properties"].get(&key).is_some())
+            else {
+                continue;
+            };
+            let Some(color) = normalize_btop_color(&value) else {
+                log::warn!("Skipping btop color theme[{key}]=\"{value}\"");
+                continue;
+            };
+            colors
+                .entry(section.to_string())
+                .or_insert_with(|| json!({}))[&key] = json!(color);
+        }
+
+        Ok(json!({ "colors": colors }))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_btop_theme(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const BUNDLED_THEME: &str = r##"#Bashtop theme with nord palette (https://www.nordtheme.com)
+theme[main_bg]=""
+theme[main_fg]='#D8DEE9'
+theme[title]="#8FBCBB"
+theme[hi_fg] = "#5E81AC" # trailing comment
+theme[selected_bg]="#4C566A"
+theme[inactive_fg]="#4C"
+theme[proc_misc]="94 129 172"
+theme[graph_text]="#D8DEE9"
+theme[cpu_box]=#4C566A
+theme[temp_start]="#81A1C1"
+theme[temp_end]="#BF616A"
+"##;
+
+    #[test]
+    fn test_parse_quoting_rules() {
+        let entries = parse_btop_theme(BUNDLED_THEME);
+        assert_eq!(entries[0], ("main_bg".to_string(), String::new()));
+        assert_eq!(entries[1].1, "#D8DEE9");
+        assert_eq!(entries[3], ("hi_fg".to_string(), "#5E81AC".to_string()));
+        assert_eq!(entries[8], ("cpu_box".to_string(), "#4C566A".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_btop_color() {
+        assert_eq!(normalize_btop_color("#D8DEE9").as_deref(), Some("#d8dee9"));
+        assert_eq!(normalize_btop_color("#4C").as_deref(), Some("#4c4c4c"));
+        assert_eq!(
+            normalize_btop_color("94 129 172").as_deref(),
+            Some("#5e81ac")
+        );
+        assert_eq!(normalize_btop_color("").as_deref(), Some(""));
+        assert_eq!(normalize_btop_color("#12345"), None);
+        assert_eq!(normalize_btop_color("300 0 0"), None);
+    }
+
+    #[test]
+    fn test_parse_existing_config_maps_sections() {
+        let parsed = BtopGenerator.parse_existing_config(BUNDLED_THEME).unwrap();
+        let colors = &parsed["colors"];
+        assert_eq!(colors["basic"]["main_bg"], "");
+        assert_eq!(colors["basic"]["proc_misc"], "#5e81ac");
+        assert_eq!(colors["boxes"]["cpu_box"], "#4c566a");
+        assert_eq!(colors["temperature"]["temp_end"], "#bf616a");
+        assert!(colors["temperature"].get("temp_mid").is_none());
+        assert!(colors["basic"].get("graph_text").is_none());
+    }
+
+    #[test]
+    fn test_unset_mid_is_blended() {
+        let theme_data = json!({ "btop": { "colors": {
+            "temperature": { "temp_start": "#000000", "temp_mid": "", "temp_end": "#ffffff" },
+            "cpu": { "cpu_start": "#000000", "cpu_mid": "#123456", "cpu_end": "#ffffff" }
+        }}});
+        let config = BtopGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.contains("theme[temp_mid]=\"#636363\""));
+        assert!(config.contains("theme[cpu_mid]=\"#123456\""));
+        assert!(BtopGenerator.validate_output(&config).is_ok());
+
+        let parsed = BtopGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["cpu"]["cpu_mid"], "#123456");
+    }
+
+    #[test]
+    fn test_bundled_themes() {
+        let system = TempDir::new().unwrap();
+        let user = TempDir::new().unwrap();
+        fs::write(system.path().join("nord.theme"), BUNDLED_THEME).unwrap();
+        fs::write(system.path().join("README.md"), "").unwrap();
+        fs::write(user.path().join("nord.theme"), "theme[main_fg]=\"#ffffff\"").unwrap();
+        fs::write(user.path().join("empty.theme"), "# nothing\n").unwrap();
+
+        let themes = list_bundled_themes(&[
+            system.path().to_path_buf(),
+            user.path().to_path_buf(),
+            PathBuf::from("/nonexistent"),
+        ]);
+        let names: Vec<&str> = themes.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["empty", "nord"]);
+        assert!(themes[1]
+            .path
+            .starts_with(&*system.path().to_string_lossy()));
+
+        let converted = convert_theme_file(Path::new(&themes[1].path)).unwrap();
+        assert_eq!(converted["btop"]["colors"]["basic"]["main_fg"], "#d8dee9");
+        assert!(convert_theme_file(Path::new(&themes[0].path)).is_err());
     }
 }
@@ -1,4 +1,5 @@
 use super::ConfigGenerator;
+use crate::services::themes::chromium_extension::COLOR_KEYS;
 use serde_json::{json, Value};
 
 pub struct ChromiumGenerator;
@@ -32,8 +33,42 @@ impl ConfigGenerator for ChromiumGenerator {
     }
 
     fn get_config_schema(&self) -> Value {
+        let variant = |title: &str| {
+            let properties: serde_json::Map<String, Value> = COLOR_KEYS
+                .iter()
+                .map(|key| {
+                    let label = key
+                        .split('_')
+                        .map(|word| {
+                            let mut chars = word.chars();
+                            chars
+                                .next()
+                                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                                .unwrap_or_default()
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let schema = json!({
+                        "type": "string",
+                        "format": "color",
+                        "title": label,
+                        "description": "Leave empty to derive it from the theme color",
+                        "default": ""
+                    });
+                    (key.to_string(), schema)
+                })
+                .collect();
+            json!({
+                "type": "object",
+                "title": title,
+                "x-order": COLOR_KEYS,
+                "properties": properties
+            })
+        };
+
         json!({
             "type": "object",
+            "x-order": ["theme_color", "extension"],
             "properties": {
                 "theme_color": {
                     "type": "string",
@@ -41,6 +76,24 @@ impl ConfigGenerator for ChromiumGenerator {
                     "title": "Theme Color",
                     "description": "Base color for Chromium theme",
                     "default": "#1e1e1e"
+                },
+                "extension": {
+                    "type": "object",
+                    "title": "Theme Extension",
+                    "description": "Writes a chromium-theme folder to load via chrome://extensions (Load unpacked)",
+                    "x-order": ["enabled", "use_background", "background", "dark", "light"],
+                    "properties": {
+                        "enabled": {"type": "boolean", "title": "Enabled", "default": false},
+                        "use_background": {"type": "boolean", "title": "Background on New Tab Page", "default": true},
+                        "background": {
+                            "type": "string",
+                            "title": "Background",
+                            "description": "PNG file from the theme's backgrounds; empty picks the first PNG",
+                            "default": ""
+                        },
+                        "dark": variant("Dark Variant"),
+                        "light": variant("Light Variant")
+                    }
                 }
             }
         })
@@ -0,0 +1,128 @@
+use super::terminal::{bare_hex, inherited_colors_schema, ParsedPalette, TerminalPalette};
+use super::ConfigGenerator;
+use serde_json::Value;
+
+pub struct FootGenerator;
+
+unsafe impl Send for FootGenerator {}
+unsafe impl Sync for FootGenerator {}
+
+impl ConfigGenerator for FootGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "foot"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "foot.ini"
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let palette = TerminalPalette::resolve(theme_data, "foot");
+
+        // Foot expects bare RRGGBB values
+        let ansi: String = palette
+            .ansi()
+            .map(|(index, color)| {
+                let key = if index < 8 { "regular" } else { "bright" };
+                format!("{key}{}={}\n", index % 8, bare_hex(color))
+            })
+            .collect();
+
+        Ok(format!(
+            r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for Foot
+# Generated with Omarchist
+# ────────────────────────────────────────────────────────────
+
+[cursor]
+color={cursor_text} {cursor}
+
+[colors]
+foreground={foreground}
+background={background}
+selection-foreground={foreground}
+selection-background={selection_background}
+{ansi}"#,
+            cursor_text = bare_hex(&palette.cursor_text),
+            cursor = bare_hex(&palette.cursor),
+            foreground = bare_hex(&palette.foreground),
+            background = bare_hex(&palette.background),
+            selection_background = bare_hex(&palette.selection_background),
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        inherited_colors_schema()
+    }
+
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let mut parsed = ParsedPalette::default();
+        let mut section = String::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim().to_string());
+
+            match (section.as_str(), key) {
+                ("cursor", "color") => {
+                    let mut parts = value.split_whitespace();
+                    parsed.cursor_text = parts.next().map(str::to_string);
+                    parsed.cursor = parts.next().map(str::to_string);
+                },
+                ("colors", "background") => parsed.background = Some(value),
+                ("colors", "foreground") => parsed.foreground = Some(value),
+                ("colors", "selection-background") => parsed.selection_background = Some(value),
+                ("colors", _) => {
+                    if let Some(index) = key.strip_prefix("regular").and_then(|i| i.parse().ok()) {
+                        parsed.set_ansi(index, &value);
+                    } else if let Some(index) = key
+                        .strip_prefix("bright")
+                        .and_then(|i| i.parse::<usize>().ok())
+                    {
+                        parsed.set_ansi(index + 8, &value);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(parsed.into_theme_data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_foot_writes_bare_hex() {
+        let theme_data = json!({
+            "foot": { "colors": { "normal": { "red": "#ff0000" } } }
+        });
+        let config = FootGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.contains("regular1=ff0000\n"));
+        assert!(config.contains("bright7=eaeaea\n"));
+        assert!(config.contains("color=121212 eaeaea\n"));
+        assert!(!config.contains("=#"));
+    }
+
+    #[test]
+    fn test_foot_round_trip() {
+        let config = FootGenerator.generate_config(&json!({})).unwrap();
+        let parsed = FootGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["primary"]["foreground"], "#bebebe");
+        assert_eq!(parsed["colors"]["cursor"]["cursor"], "#eaeaea");
+        assert_eq!(parsed["colors"]["bright"]["black"], "#8a8a8d");
+    }
+}
@@ -0,0 +1,109 @@
+use super::terminal::{inherited_colors_schema, ParsedPalette, TerminalPalette};
+use super::ConfigGenerator;
+use serde_json::Value;
+
+pub struct GhosttyGenerator;
+
+unsafe impl Send for GhosttyGenerator {}
+unsafe impl Sync for GhosttyGenerator {}
+
+impl ConfigGenerator for GhosttyGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "ghostty"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "ghostty.conf"
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let palette = TerminalPalette::resolve(theme_data, "ghostty");
+
+        let ansi: String = palette
+            .ansi()
+            .map(|(index, color)| format!("palette = {index}={color}\n"))
+            .collect();
+
+        Ok(format!(
+            r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for Ghostty
+# Generated with Omarchist
+# ────────────────────────────────────────────────────────────
+
+background = {background}
+foreground = {foreground}
+cursor-color = {cursor}
+cursor-text = {cursor_text}
+selection-background = {selection_background}
+selection-foreground = {foreground}
+
+{ansi}"#,
+            background = palette.background,
+            foreground = palette.foreground,
+            cursor = palette.cursor,
+            cursor_text = palette.cursor_text,
+            selection_background = palette.selection_background,
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        inherited_colors_schema()
+    }
+
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let mut parsed = ParsedPalette::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim().to_string());
+
+            match key {
+                "background" => parsed.background = Some(value),
+                "foreground" => parsed.foreground = Some(value),
+                "cursor-color" => parsed.cursor = Some(value),
+                "cursor-text" => parsed.cursor_text = Some(value),
+                "selection-background" => parsed.selection_background = Some(value),
+                "palette" => {
+                    if let Some((index, color)) = value.split_once('=') {
+                        if let Ok(index) = index.trim().parse() {
+                            parsed.set_ansi(index, color);
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(parsed.into_theme_data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ghostty_palette_entries() {
+        let config = GhosttyGenerator.generate_config(&json!({})).unwrap();
+        assert!(config.contains("palette = 0=#333333\n"));
+        assert!(config.contains("palette = 15=#eaeaea\n"));
+    }
+
+    #[test]
+    fn test_ghostty_round_trip() {
+        let theme_data = json!({
+            "ghostty": { "colors": { "primary": { "background": "#000000" } } }
+        });
+        let config = GhosttyGenerator.generate_config(&theme_data).unwrap();
+        let parsed = GhosttyGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["primary"]["background"], "#000000");
+        assert_eq!(parsed["colors"]["normal"]["green"], "#FFC107");
+    }
+}
@@ -0,0 +1,296 @@
+use super::terminal::TerminalPalette;
+use super::{validation, ConfigGenerator, OutputValidationError};
+use serde_json::{json, Value};
+
+pub struct GtkGenerator;
+
+unsafe impl Send for GtkGenerator {}
+unsafe impl Sync for GtkGenerator {}
+
+/// Named desktop colors shared by the GTK and Qt generators.
+///
+/// Values come from the `gtk.colors` data when set, otherwise they are derived from the
+/// terminal palette so every theme gets matching toolkit colors without extra setup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopPalette {
+    pub accent_bg: String,
+    pub accent_fg: String,
+    pub window_bg: String,
+    pub window_fg: String,
+    pub view_bg: String,
+    pub view_fg: String,
+    pub headerbar_bg: String,
+    pub headerbar_fg: String,
+    pub card_bg: String,
+    pub popover_bg: String,
+    pub muted_fg: String,
+    pub destructive_bg: String,
+    pub success: String,
+    pub warning: String,
+    pub error: String,
+}
+
+impl DesktopPalette {
+    pub fn resolve(theme_data: &Value) -> Self {
+        let terminal = TerminalPalette::resolve(theme_data, "alacritty");
+        let colors = theme_data.get("gtk").and_then(|g| g.get("colors"));
+        let pick = |key: &str, derived: &str| -> String {
+            colors
+                .and_then(|c| c.get(key))
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or(derived)
+                .to_string()
+        };
+
+        let window_bg = pick("window_bg_color", &terminal.background);
+        let window_fg = pick("window_fg_color", &terminal.foreground);
+        let accent_bg = pick("accent_bg_color", &terminal.normal[4]);
+        let accent_fg = pick("accent_fg_color", contrast_fg(&accent_bg));
+        let destructive_bg = pick("destructive_bg_color", &terminal.normal[1]);
+
+        Self {
+            view_bg: pick("view_bg_color", &window_bg),
+            view_fg: pick("view_fg_color", &window_fg),
+            headerbar_bg: pick("headerbar_bg_color", &window_bg),
+            headerbar_fg: pick("headerbar_fg_color", &window_fg),
+            card_bg: pick("card_bg_color", &terminal.selection_background),
+            popover_bg: pick("popover_bg_color", &terminal.selection_background),
+            muted_fg: terminal.dim_foreground.clone(),
+            success: pick("success_color", &terminal.normal[2]),
+            warning: pick("warning_color", &terminal.normal[3]),
+            error: pick("error_color", &destructive_bg),
+            accent_bg,
+            accent_fg,
+            window_bg,
+            window_fg,
+            destructive_bg,
+        }
+    }
+}
+
+/// Black or white, whichever reads better on top of `background`
+pub fn contrast_fg(background: &str) -> &'static str {
+    let hex = background.trim().trim_start_matches('#');
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .map(|c| {
+                let c = f64::from(c) / 255.0;
+                if c <= 0.03928 {
+                    c / 12.92
+                } else {
+                    ((c + 0.055) / 1.055).powf(2.4)
+                }
+            })
+            .unwrap_or(0.0)
+    };
+    let luminance = 0.2126 * channel(0) + 0.7152 * channel(2) + 0.0722 * channel(4);
+
+    if luminance > 0.179 {
+        "#000000"
+    } else {
+        "#ffffff"
+    }
+}
+
+impl ConfigGenerator for GtkGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "gtk"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "gtk.css"
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let DesktopPalette {
+            accent_bg,
+            accent_fg,
+            window_bg,
+            window_fg,
+            view_bg,
+            view_fg,
+            headerbar_bg,
+            headerbar_fg,
+            card_bg,
+            popover_bg,
+            muted_fg,
+            destructive_bg,
+            success,
+            warning,
+            error,
+        } = DesktopPalette::resolve(theme_data);
+        let destructive_fg = contrast_fg(&destructive_bg);
+
+        Ok(format!(
+            r#"/* ────────────────────────────────────────────────────────────
+ * Omarchy Custom Theme for GTK 3/4
+ * Generated with Omarchist
+ * ────────────────────────────────────────────────────────────
+ */
+
+/* libadwaita (GTK 4) */
+@define-color accent_color {accent_bg};
+@define-color accent_bg_color {accent_bg};
+@define-color accent_fg_color {accent_fg};
+@define-color destructive_color {destructive_bg};
+@define-color destructive_bg_color {destructive_bg};
+@define-color destructive_fg_color {destructive_fg};
+@define-color success_color {success};
+@define-color warning_color {warning};
+@define-color error_color {error};
+@define-color window_bg_color {window_bg};
+@define-color window_fg_color {window_fg};
+@define-color view_bg_color {view_bg};
+@define-color view_fg_color {view_fg};
+@define-color headerbar_bg_color {headerbar_bg};
+@define-color headerbar_fg_color {headerbar_fg};
+@define-color headerbar_backdrop_color {window_bg};
+@define-color sidebar_bg_color {headerbar_bg};
+@define-color sidebar_fg_color {headerbar_fg};
+@define-color card_bg_color {card_bg};
+@define-color card_fg_color {window_fg};
+@define-color popover_bg_color {popover_bg};
+@define-color popover_fg_color {window_fg};
+@define-color dialog_bg_color {popover_bg};
+@define-color dialog_fg_color {window_fg};
+
+/* Adwaita (GTK 3) */
+@define-color theme_bg_color {window_bg};
+@define-color theme_fg_color {window_fg};
+@define-color theme_base_color {view_bg};
+@define-color theme_text_color {view_fg};
+@define-color theme_selected_bg_color {accent_bg};
+@define-color theme_selected_fg_color {accent_fg};
+@define-color insensitive_fg_color {muted_fg};
+@define-color borders {card_bg};
+"#
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        let color = |title: &str| {
+            json!({
+                "type": "string",
+                "format": "color",
+                "title": title,
+                "default": "",
+                "description": "Leave empty to derive it from the terminal palette"
+            })
+        };
+
+        json!({
+            "type": "object",
+            "x-order": ["colors"],
+            "properties": {
+                "colors": {
+                    "type": "object",
+                    "x-order": [
+                        "accent_bg_color",
+                        "accent_fg_color",
+                        "window_bg_color",
+                        "window_fg_color",
+                        "view_bg_color",
+                        "view_fg_color",
+                        "headerbar_bg_color",
+                        "headerbar_fg_color",
+                        "card_bg_color",
+                        "popover_bg_color",
+                        "destructive_bg_color",
+                        "success_color",
+                        "warning_color",
+                        "error_color"
+                    ],
+                    "properties": {
+                        "accent_bg_color": color("Accent Background"),
+                        "accent_fg_color": color("Accent Foreground"),
+                        "window_bg_color": color("Window Background"),
+                        "window_fg_color": color("Window Foreground"),
+                        "view_bg_color": color("View Background"),
+                        "view_fg_color": color("View Foreground"),
+                        "headerbar_bg_color": color("Header Bar Background"),
+                        "headerbar_fg_color": color("Header Bar Foreground"),
+                        "card_bg_color": color("Card Background"),
+                        "popover_bg_color": color("Popover Background"),
+                        "destructive_bg_color": color("Destructive"),
+                        "success_color": color("Success"),
+                        "warning_color": color("Warning"),
+                        "error_color": color("Error")
+                    }
+                }
+            }
+        })
+    }
+
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let schema = self.get_config_schema();
+        let known = &schema["properties"]["colors"]["properties"];
+        let mut colors = serde_json::Map::new();
+
+        for line in content.lines() {
+            let Some(rest) = line.trim().strip_prefix("@define-color") else {
+                continue;
+            };
+            let mut parts = rest
+                .trim()
+                .trim_end_matches(';')
+                .splitn(2, char::is_whitespace);
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                if known.get(name).is_some() {
+                    colors.insert(name.to_string(), json!(value.trim()));
+                }
+            }
+        }
+
+        Ok(json!({ "colors": colors }))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_css(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gtk_derives_from_terminal_palette() {
+        let theme_data = json!({
+            "alacritty": { "colors": {
+                "primary": { "background": "#101010", "foreground": "#f0f0f0" },
+                "normal": { "blue": "#3366ff" }
+            }},
+            "gtk": { "colors": { "view_bg_color": "#000000", "card_bg_color": "" } }
+        });
+
+        let palette = DesktopPalette::resolve(&theme_data);
+        assert_eq!(palette.window_bg, "#101010");
+        assert_eq!(palette.view_bg, "#000000");
+        assert_eq!(palette.headerbar_bg, "#101010");
+        assert_eq!(palette.accent_bg, "#3366ff");
+        assert_eq!(palette.card_bg, "#333333");
+
+        let css = GtkGenerator.generate_config(&theme_data).unwrap();
+        assert!(css.contains("@define-color window_bg_color #101010;"));
+        assert!(css.contains("@define-color theme_selected_bg_color #3366ff;"));
+    }
+
+    #[test]
+    fn test_contrast_fg() {
+        assert_eq!(contrast_fg("#ffc107"), "#000000");
+        assert_eq!(contrast_fg("#121212"), "#ffffff");
+        assert_eq!(contrast_fg("#3366ff"), "#ffffff");
+    }
+
+    #[test]
+    fn test_gtk_round_trip() {
+        let theme_data = json!({ "gtk": { "colors": { "accent_bg_color": "#abcdef" } } });
+        let css = GtkGenerator.generate_config(&theme_data).unwrap();
+        let parsed = GtkGenerator.parse_existing_config(&css).unwrap();
+        assert_eq!(parsed["colors"]["accent_bg_color"], "#abcdef");
+        assert_eq!(parsed["colors"]["window_bg_color"], "#121212");
+        assert!(parsed["colors"].get("theme_bg_color").is_none());
+    }
+}
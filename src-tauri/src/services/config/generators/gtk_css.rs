@@ -0,0 +1,383 @@
+//! Reading and writing the GTK-flavoured CSS used by Waybar, Walker and SwayOSD themes.
+//!
+//! Generators own a known set of `@define-color` variables and rules; everything else in an
+//! existing file (user rules and colors, comments, imports) is kept as `custom_css` and
+//! written back after the generated part.
+
+/// A top-level item of a stylesheet, keeping its source text
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssItem {
+    Comment(String),
+    DefineColor {
+        name: String,
+        value: String,
+    },
+    Rule {
+        selector: String,
+        body: String,
+    },
+    /// Any other statement, e.g. `@import "...";`
+    Other(String),
+}
+
+impl CssItem {
+    fn to_css(&self) -> String {
+        match self {
+            CssItem::Comment(text) | CssItem::Other(text) => text.clone(),
+            CssItem::DefineColor { name, value } => define_color(name, value),
+            CssItem::Rule { selector, body } => format!("{selector} {{{body}}}"),
+        }
+    }
+
+    /// The banner comment Omarchist writes at the top of every generated file
+    fn is_generated_header(&self) -> bool {
+        matches!(self, CssItem::Comment(text) if text.contains("Generated with Omarchist"))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GtkCss {
+    pub items: Vec<CssItem>,
+}
+
+impl GtkCss {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let chars: Vec<char> = content.chars().collect();
+        let mut items = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i].is_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                let end = find_comment_end(&chars, i + 2).ok_or("unterminated comment")?;
+                items.push(CssItem::Comment(chars[i..end].iter().collect()));
+                i = end;
+                continue;
+            }
+
+            // A statement ends at `;` unless a block opens first
+            let start = i;
+            let mut depth = 0usize;
+            let mut quote: Option<char> = None;
+            let mut end = None;
+            while i < chars.len() {
+                let c = chars[i];
+                match quote {
+                    Some(q) if c == q => quote = None,
+                    Some(_) if c == '\\' => i += 1,
+                    Some(_) => {},
+                    None => match c {
+                        '"' | '\'' => quote = Some(c),
+                        '/' if chars.get(i + 1) == Some(&'*') => {
+                            i = find_comment_end(&chars, i + 2).ok_or("unterminated comment")? - 1;
+                        },
+                        '{' => depth += 1,
+                        '}' if depth == 0 => return Err("unexpected '}'".to_string()),
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                end = Some(i + 1);
+                            }
+                        },
+                        ';' if depth == 0 => end = Some(i + 1),
+                        _ => {},
+                    },
+                }
+                i += 1;
+                if end.is_some() {
+                    break;
+                }
+            }
+            let Some(end) = end else {
+                return Err(if depth > 0 {
+                    "unclosed block".to_string()
+                } else {
+                    "statement is missing ';'".to_string()
+                });
+            };
+
+            let text: String = chars[start..end].iter().collect();
+            items.push(classify(text.trim()));
+        }
+
+        Ok(Self { items })
+    }
+
+    /// Value of `@define-color name`; later definitions win like they do in GTK
+    pub fn color(&self, name: &str) -> Option<&str> {
+        self.items.iter().rev().find_map(|item| match item {
+            CssItem::DefineColor { name: n, value } if n == name => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Declarations of the first rule whose selector is exactly `selector`
+    pub fn rule(&self, selector: &str) -> Option<Vec<(String, String)>> {
+        self.items.iter().find_map(|item| match item {
+            CssItem::Rule { selector: s, body } if s == selector => Some(declarations(body)),
+            _ => None,
+        })
+    }
+
+    /// Remove the first rule whose selector is exactly `selector`, returning its declarations
+    pub fn take_rule(&mut self, selector: &str) -> Option<Vec<(String, String)>> {
+        let index = self
+            .items
+            .iter()
+            .position(|item| matches!(item, CssItem::Rule { selector: s, .. } if s == selector))?;
+        match self.items.remove(index) {
+            CssItem::Rule { body, .. } => Some(declarations(&body)),
+            _ => None,
+        }
+    }
+
+    /// Remove a rule that only sets `property` on exactly `selector`, as generators write
+    /// them, returning the value. Rules the user extended are left in place.
+    pub fn take_single(&mut self, selector: &str, property: &str) -> Option<String> {
+        match self.rule(selector)?.as_slice() {
+            [(p, _)] if p == property => self.take_rule(selector)?.pop().map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Everything except the `owned_colors` definitions and the generated banner, ready to
+    /// append to generated output. Call after taking the rules a generator owns.
+    pub fn custom_css(&self, owned_colors: &[&str]) -> String {
+        self.items
+            .iter()
+            .filter(|item| {
+                !item.is_generated_header()
+                    && !matches!(item, CssItem::DefineColor { name, .. }
+                        if owned_colors.contains(&name.as_str()))
+            })
+            .map(CssItem::to_css)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn find_comment_end(chars: &[char], from: usize) -> Option<usize> {
+    (from..chars.len().saturating_sub(1))
+        .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+        .map(|j| j + 2)
+}
+
+fn classify(text: &str) -> CssItem {
+    if let Some(rest) = text.strip_prefix("@define-color") {
+        let rest = rest.trim().trim_end_matches(';').trim();
+        if let Some((name, value)) = rest.split_once(char::is_whitespace) {
+            return CssItem::DefineColor {
+                name: name.to_string(),
+                value: value.trim().to_string(),
+            };
+        }
+    }
+    if text.ends_with('}') {
+        if let Some((selector, body)) = text.split_once('{') {
+            return CssItem::Rule {
+                selector: selector.trim().to_string(),
+                body: body[..body.len() - 1].to_string(),
+            };
+        }
+    }
+    CssItem::Other(text.to_string())
+}
+
+/// Split a rule body into `property: value` pairs
+pub fn declarations(body: &str) -> Vec<(String, String)> {
+    let mut without_comments = String::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("/*") {
+        without_comments.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("*/")
+            .map_or("", |end| &rest[start + end + 2..]);
+    }
+    without_comments.push_str(rest);
+
+    split_outside_quotes(&without_comments, ';')
+        .into_iter()
+        .filter_map(|decl| {
+            let (property, value) = decl.split_once(':')?;
+            Some((property.trim().to_string(), value.trim().to_string()))
+        })
+        .filter(|(property, _)| !property.is_empty())
+        .collect()
+}
+
+/// Split `text` at every `separator` that is not inside a quoted string
+fn split_outside_quotes(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match quote {
+            _ if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {},
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == separator => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            },
+            None => {},
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// The banner comment at the top of generated files
+pub fn header(app: &str) -> String {
+    format!(
+        r#"/* ────────────────────────────────────────────────────────────
+ * Omarchy Custom Theme for {app}
+ * Generated with Omarchist
+ * ────────────────────────────────────────────────────────────
+ */
+"#
+    )
+}
+
+pub fn define_color(name: &str, value: &str) -> String {
+    format!("@define-color {name} {value};")
+}
+
+pub fn rule(selector: &str, declarations: &[(&str, String)]) -> String {
+    let body: String = declarations
+        .iter()
+        .map(|(property, value)| format!("  {property}: {value};\n"))
+        .collect();
+    format!("{selector} {{\n{body}}}")
+}
+
+/// Assemble a generated stylesheet: banner, color definitions, owned rules, then the user's
+/// own CSS
+pub fn render(app: &str, colors: &[(&str, String)], rules: &[String], custom_css: &str) -> String {
+    let mut out = header(app);
+    out.push('\n');
+    for (name, value) in colors {
+        out.push_str(&define_color(name, value));
+        out.push('\n');
+    }
+    for rule in rules {
+        out.push('\n');
+        out.push_str(rule);
+        out.push('\n');
+    }
+    let custom_css = custom_css.trim();
+    if !custom_css.is_empty() {
+        out.push('\n');
+        out.push_str(custom_css);
+        out.push('\n');
+    }
+    out
+}
+
+/// Schema for the free-form CSS carried over from an existing file
+pub fn custom_css_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "format": "textarea",
+        "title": "Custom CSS",
+        "description": "Extra rules and comments appended after the generated colors",
+        "default": ""
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"/* ────
+ * Omarchy Custom Theme for Waybar
+ * Generated with Omarchist
+ */
+
+@define-color background #1e1e1e;
+@import url("extra.css");
+
+/* my tweaks; keep these */
+#clock {
+  color: @background; /* inline */
+  font-family: "Mono;Sans";
+}
+
+@define-color background #000000;
+"#;
+
+    #[test]
+    fn test_parse_items() {
+        let css = GtkCss::parse(SAMPLE).unwrap();
+        assert_eq!(css.color("background"), Some("#000000"));
+        assert_eq!(css.items.len(), 6);
+        assert_eq!(
+            css.items[2],
+            CssItem::Other("@import url(\"extra.css\");".to_string())
+        );
+    }
+
+    #[test]
+    fn test_custom_css_keeps_user_content() {
+        let css = GtkCss::parse(SAMPLE).unwrap();
+        let custom = css.custom_css(&["background"]);
+        assert!(!custom.contains("Generated with Omarchist"));
+        assert!(!custom.contains("@define-color"));
+        assert!(custom.contains("/* my tweaks; keep these */"));
+        assert!(custom.contains("font-family: \"Mono;Sans\";"));
+
+        // Round trip through render is stable
+        let rendered = render("Waybar", &[("background", "#000000".into())], &[], &custom);
+        let reparsed = GtkCss::parse(&rendered).unwrap();
+        assert_eq!(reparsed.custom_css(&["background"]), custom);
+    }
+
+    #[test]
+    fn test_custom_css_keeps_user_colors() {
+        let css =
+            GtkCss::parse("@define-color background #000000;\n@define-color my-red #ff0000;\n")
+                .unwrap();
+        assert_eq!(
+            css.custom_css(&["background"]),
+            "@define-color my-red #ff0000;"
+        );
+        assert_eq!(css.custom_css(&["background", "my-red"]), "");
+    }
+
+    #[test]
+    fn test_declarations_keep_quoted_semicolons() {
+        let decls = declarations(r#" font-family: "Mono;Sans"; content: 'a;b'; color: red "#);
+        assert_eq!(
+            decls,
+            vec![
+                ("font-family".to_string(), "\"Mono;Sans\"".to_string()),
+                ("content".to_string(), "'a;b'".to_string()),
+                ("color".to_string(), "red".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_take_rule() {
+        let mut css = GtkCss::parse(SAMPLE).unwrap();
+        let clock = css.take_rule("#clock").unwrap();
+        assert_eq!(clock[0], ("color".to_string(), "@background".to_string()));
+        assert_eq!(clock[1].0, "font-family");
+        assert!(!css.custom_css(&[]).contains("#clock"));
+        assert!(css.take_rule("#clock").is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(GtkCss::parse("/* open").is_err());
+        assert!(GtkCss::parse("#clock { color: red;").is_err());
+        assert!(GtkCss::parse("@define-color x #fff").is_err());
+        assert!(GtkCss::parse("}").is_err());
+    }
+}
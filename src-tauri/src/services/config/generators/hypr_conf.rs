@@ -0,0 +1,372 @@
+//! Parsing and formatting helpers for the hyprlang syntax shared by `hyprland.conf`
+//! and `hyprlock.conf`.
+
+/// A `name { ... }` block. The document root is a section with an empty name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HyprSection {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+    pub children: Vec<HyprSection>,
+    /// `$name = value` declarations in file order, with earlier variables substituted.
+    /// Only the root section has any.
+    pub variables: Vec<(String, String)>,
+}
+
+impl HyprSection {
+    /// Value of `key` in this section; later assignments win like they do in Hyprland
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// First child section called `name`
+    pub fn section(&self, name: &str) -> Option<&HyprSection> {
+        self.children.iter().find(|s| s.name == name)
+    }
+
+    /// Every child section called `name`, in file order
+    pub fn sections<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a HyprSection> {
+        self.children.iter().filter(move |s| s.name == name)
+    }
+
+    /// Value of the root-level `$name` variable
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Follow a dotted path of section names, e.g. `decoration.blur`
+    pub fn path(&self, path: &str) -> Option<&HyprSection> {
+        path.split('.')
+            .try_fold(self, |section, name| section.section(name))
+    }
+}
+
+/// Parse hyprlang content into a section tree.
+///
+/// `$variables` are substituted, `source =` lines and unknown keywords are kept as plain
+/// entries, and `##` is an escaped `#` rather than a comment.
+pub fn parse(content: &str) -> Result<HyprSection, String> {
+    let mut stack = vec![HyprSection::default()];
+    let mut variables: Vec<(String, String)> = Vec::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = strip_comment(raw_line);
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line == "}" {
+            if stack.len() == 1 {
+                return Err(format!("line {line_no}: unexpected '}}'"));
+            }
+            let section = stack.pop().expect("stack has a parent");
+            stack
+                .last_mut()
+                .expect("root section")
+                .children
+                .push(section);
+            continue;
+        }
+
+        if let Some(name) = line.strip_suffix('{') {
+            let name = name.trim();
+            if name.is_empty() || name.contains('=') {
+                return Err(format!("line {line_no}: invalid section header '{line}'"));
+            }
+            stack.push(HyprSection {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!(
+                "line {line_no}: expected 'key = value', got '{line}'"
+            ));
+        };
+        let key = key.trim().to_string();
+        let value = substitute(value.trim(), &variables);
+
+        if let Some(name) = key.strip_prefix('$') {
+            stack[0].variables.push((name.to_string(), value.clone()));
+            variables.push((name.to_string(), value));
+            // Longest names first so `$accent_dim` is not replaced as `$accent`
+            variables.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+            continue;
+        }
+
+        stack
+            .last_mut()
+            .expect("at least the root section")
+            .entries
+            .push((key, value));
+    }
+
+    if stack.len() != 1 {
+        return Err(format!(
+            "section '{}' is never closed",
+            stack.last().map(|s| s.name.as_str()).unwrap_or_default()
+        ));
+    }
+
+    Ok(stack.pop().expect("root section"))
+}
+
+fn strip_comment(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '#' {
+            if chars.peek() == Some(&'#') {
+                chars.next();
+                result.push('#');
+                continue;
+            }
+            break;
+        }
+        result.push(c);
+    }
+    result
+}
+
+fn substitute(value: &str, variables: &[(String, String)]) -> String {
+    let mut value = value.to_string();
+    for (name, replacement) in variables {
+        value = value.replace(&format!("${name}"), replacement);
+    }
+    value
+}
+
+/// Parse a single hyprlang color into `RRGGBB` or `RRGGBBAA` (no `#`).
+///
+/// Accepts `rgb(RRGGBB)`, `rgba(RRGGBBAA)`, `rgb(r, g, b)`, `rgba(r, g, b, a)` and the
+/// legacy `0xAARRGGBB` form.
+pub fn parse_color(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix("0x") {
+        if hex.len() == 8 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            let (alpha, rgb) = hex.split_at(2);
+            return Some(with_alpha(rgb, alpha));
+        }
+        return None;
+    }
+
+    let (is_rgba, inner) = if let Some(rest) = value.strip_prefix("rgba(") {
+        (true, rest.strip_suffix(')')?)
+    } else if let Some(rest) = value.strip_prefix("rgb(") {
+        (false, rest.strip_suffix(')')?)
+    } else {
+        return None;
+    };
+    let inner = inner.trim();
+
+    if !inner.contains(',') {
+        let valid_len = if is_rgba {
+            inner.len() == 8
+        } else {
+            inner.len() == 6
+        };
+        if valid_len && inner.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some(inner.to_uppercase());
+        }
+        return None;
+    }
+
+    let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    if parts.len() != if is_rgba { 4 } else { 3 } {
+        return None;
+    }
+    let mut hex = String::new();
+    for part in &parts[..3] {
+        hex.push_str(&format!("{:02X}", part.parse::<u8>().ok()?));
+    }
+    match parts.get(3) {
+        Some(alpha) => {
+            let alpha = alpha.parse::<f64>().ok()?;
+            if !(0.0..=1.0).contains(&alpha) {
+                return None;
+            }
+            Some(with_alpha(
+                &hex,
+                &format!("{:02X}", (alpha * 255.0).round() as u8),
+            ))
+        },
+        None => Some(hex),
+    }
+}
+
+fn with_alpha(rgb: &str, alpha: &str) -> String {
+    if alpha.eq_ignore_ascii_case("ff") {
+        rgb.to_uppercase()
+    } else {
+        format!("{rgb}{alpha}").to_uppercase()
+    }
+}
+
+/// A color or gradient value: one or more colors and an optional angle in degrees
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyprGradient {
+    pub colors: Vec<String>,
+    pub angle: Option<f64>,
+}
+
+/// Parse `rgba(..) rgba(..) 45deg` style values
+pub fn parse_gradient(value: &str) -> Option<HyprGradient> {
+    let mut colors = Vec::new();
+    let mut angle = None;
+
+    // Colors may contain spaces inside parentheses, so split on top-level whitespace only
+    let mut depth = 0;
+    let mut token = String::new();
+    let mut tokens = Vec::new();
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {},
+        }
+        if c.is_whitespace() && depth == 0 {
+            if !token.is_empty() {
+                tokens.push(std::mem::take(&mut token));
+            }
+        } else {
+            token.push(c);
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    for token in tokens {
+        if let Some(deg) = token.strip_suffix("deg") {
+            angle = Some(deg.parse().ok()?);
+        } else {
+            colors.push(parse_color(&token)?);
+        }
+    }
+
+    if colors.is_empty() {
+        None
+    } else {
+        Some(HyprGradient { colors, angle })
+    }
+}
+
+/// Format a `RRGGBB`/`RRGGBBAA` hex (with or without `#`) as a hyprlang color
+pub fn format_color(hex: &str) -> String {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() == 8 {
+        format!("rgba({hex})")
+    } else {
+        format!("rgb({hex})")
+    }
+}
+
+/// Format a border value: a single color, or a gradient when `end` is non-empty
+pub fn format_gradient(start: &str, end: &str, angle: f64) -> String {
+    if end.trim().is_empty() {
+        format_color(start)
+    } else {
+        format!(
+            "{} {} {}deg",
+            format_color(start),
+            format_color(end),
+            format_number(angle)
+        )
+    }
+}
+
+/// Print whole numbers without a trailing `.0`
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{value}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_sections_and_variables() {
+        let content = r#"
+$accent = rgb(ff0000)
+general {
+    col.active_border = $accent rgba(00ff00aa) 45deg # trailing comment
+    border_size = 2
+}
+decoration {
+    blur {
+        size = 8
+    }
+}
+label {
+    text = ##1 fan
+}
+label {
+    text = second
+}
+"#;
+        let root = parse(content).unwrap();
+        assert_eq!(
+            root.path("general").unwrap().get("col.active_border"),
+            Some("rgb(ff0000) rgba(00ff00aa) 45deg")
+        );
+        assert_eq!(root.path("decoration.blur").unwrap().get("size"), Some("8"));
+        assert_eq!(root.variable("accent"), Some("rgb(ff0000)"));
+        let labels: Vec<_> = root
+            .sections("label")
+            .filter_map(|l| l.get("text"))
+            .collect();
+        assert_eq!(labels, vec!["#1 fan", "second"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_unbalanced() {
+        assert!(parse("general {\n border_size = 1\n").is_err());
+        assert!(parse("}\n").is_err());
+        assert!(parse("general {\n nonsense\n}\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_color_forms() {
+        assert_eq!(parse_color("rgb(8a8a8d)"), Some("8A8A8D".to_string()));
+        assert_eq!(parse_color("rgba(33ccffee)"), Some("33CCFFEE".to_string()));
+        assert_eq!(
+            parse_color("rgba(255, 0, 0, 0.5)"),
+            Some("FF000080".to_string())
+        );
+        assert_eq!(parse_color("rgb(0,128,255)"), Some("0080FF".to_string()));
+        assert_eq!(parse_color("0xff8a8a8d"), Some("8A8A8D".to_string()));
+        assert_eq!(parse_color("0x80112233"), Some("11223380".to_string()));
+        assert_eq!(parse_color("rgb(12345)"), None);
+        assert_eq!(parse_color("red"), None);
+    }
+
+    #[test]
+    fn test_gradient_round_trip() {
+        let value = format_gradient("33ccffee", "#00ff99ee", 45.0);
+        assert_eq!(value, "rgba(33ccffee) rgba(00ff99ee) 45deg");
+        let gradient = parse_gradient(&value).unwrap();
+        assert_eq!(gradient.colors, vec!["33CCFFEE", "00FF99EE"]);
+        assert_eq!(gradient.angle, Some(45.0));
+
+        assert_eq!(format_gradient("8A8A8D", "", 45.0), "rgb(8A8A8D)");
+        let single = parse_gradient("rgba(255, 255, 255, 1.0)").unwrap();
+        assert_eq!(single.colors, vec!["FFFFFF"]);
+        assert_eq!(single.angle, None);
+    }
+}
@@ -1,11 +1,136 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::hypr_conf::{self, format_color, format_gradient, format_number, HyprSection};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use serde_json::{json, Map, Value};
 
 pub struct HyprlandGenerator;
 
 unsafe impl Send for HyprlandGenerator {}
 unsafe impl Sync for HyprlandGenerator {}
 
+/// Animation presets offered in the editor. `inherit` leaves Omarchy's animations alone.
+const ANIMATION_PRESETS: [&str; 5] = ["inherit", "snappy", "smooth", "minimal", "none"];
+/// Choices for on/off look-and-feel settings; `inherit` leaves Omarchy's value alone
+const TOGGLE: [&str; 3] = ["inherit", "true", "false"];
+
+fn str_at<'a>(value: &'a Value, path: &[&str], default: &'a str) -> &'a str {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(|v| v.as_str())
+        .unwrap_or(default)
+}
+
+fn num_at(value: &Value, path: &[&str], default: f64) -> f64 {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(default)
+}
+
+/// A number the user set; unset look-and-feel settings are left to Omarchy
+fn set_number(value: &Value, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(|v| v.as_f64())
+        .map(format_number)
+}
+
+/// A non-empty color the user set
+fn set_color(value: &Value, path: &[&str]) -> Option<String> {
+    Some(str_at(value, path, "").trim())
+        .filter(|color| !color.is_empty())
+        .map(format_color)
+}
+
+/// An on/off setting the user set; themes saved before the `inherit` choice hold booleans
+fn set_toggle(value: &Value, path: &[&str]) -> Option<String> {
+    match path.iter().try_fold(value, |v, key| v.get(key))? {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::String(s) if s == "true" || s == "false" => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// `key = value` lines at `depth` for the settings that are set
+fn set_lines(depth: usize, entries: &[(&str, Option<String>)]) -> Vec<String> {
+    let indent = "    ".repeat(depth);
+    entries
+        .iter()
+        .filter_map(|(key, value)| Some(format!("{indent}{key} = {}", value.as_ref()?)))
+        .collect()
+}
+
+/// A nested `name { ... }` block, or nothing when none of its lines are set
+fn nested_block(depth: usize, name: &str, lines: Vec<String>) -> Vec<String> {
+    if lines.is_empty() {
+        return lines;
+    }
+    let indent = "    ".repeat(depth);
+    let mut block = vec![String::new(), format!("{indent}{name} {{")];
+    block.extend(lines);
+    block.push(format!("{indent}}}"));
+    block
+}
+
+/// Empty strings fall back to another color so groups follow the window borders by default
+fn or_color<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    if value.trim().is_empty() {
+        fallback
+    } else {
+        value
+    }
+}
+
+fn animations_block(preset: &str) -> &'static str {
+    match preset {
+        "snappy" => {
+            r#"
+animations {
+    enabled = true
+    bezier = snappy, 0.05, 0.9, 0.1, 1.0
+    animation = windows, 1, 3, snappy, popin 80%
+    animation = windowsOut, 1, 3, snappy, popin 80%
+    animation = border, 1, 5, default
+    animation = fade, 1, 3, default
+    animation = workspaces, 1, 3, snappy, slide
+}
+"#
+        },
+        "smooth" => {
+            r#"
+animations {
+    enabled = true
+    bezier = smooth, 0.23, 1, 0.32, 1
+    animation = windows, 1, 6, smooth, slide
+    animation = windowsOut, 1, 6, smooth, slide
+    animation = border, 1, 8, smooth
+    animation = fade, 1, 6, smooth
+    animation = workspaces, 1, 6, smooth, slidefade 20%
+}
+"#
+        },
+        "minimal" => {
+            r#"
+animations {
+    enabled = true
+    bezier = minimal, 0.2, 0, 0, 1
+    animation = windows, 1, 2, minimal, popin 90%
+    animation = fade, 1, 2, minimal
+    animation = workspaces, 1, 2, minimal, fade
+    animation = borderangle, 0
+}
+"#
+        },
+        "none" => {
+            r#"
+animations {
+    enabled = false
+}
+"#
+        },
+        _ => "",
+    }
+}
+
 impl ConfigGenerator for HyprlandGenerator {
     fn get_app_name(&self) -> &'static str {
         "hyprland"
@@ -19,48 +144,115 @@ impl ConfigGenerator for HyprlandGenerator {
         let empty_obj = json!({});
         let hyprland = theme_data.get("hyprland").unwrap_or(&empty_obj);
 
-        // Extract color values with defaults from template
-        let general = hyprland.get("general").unwrap_or(&empty_obj);
-        let mut active_border = general
-            .get("active_border")
-            .and_then(|a| a.as_str())
-            .unwrap_or("8A8A8D")
-            .to_string();
-        // normalize to hex without leading '#'
-        if active_border.starts_with('#') {
-            active_border = active_border.trim_start_matches('#').to_string();
-        }
-        let mut inactive_border = general
-            .get("inactive_border")
-            .and_then(|a| a.as_str())
-            .unwrap_or("5C5C5E")
-            .to_string();
-        // normalize to hex without leading '#'
-        if inactive_border.starts_with('#') {
-            inactive_border = inactive_border.trim_start_matches('#').to_string();
+        // Borders, optionally as gradients
+        let active_border = str_at(hyprland, &["general", "active_border"], "8A8A8D");
+        let inactive_border = str_at(hyprland, &["general", "inactive_border"], "5C5C5E");
+        let angle = num_at(hyprland, &["general", "border_angle"], 45.0);
+        let active = format_gradient(
+            active_border,
+            str_at(hyprland, &["general", "active_border_end"], ""),
+            angle,
+        );
+        let inactive = format_gradient(
+            inactive_border,
+            str_at(hyprland, &["general", "inactive_border_end"], ""),
+            angle,
+        );
+        let border_size = format_number(num_at(hyprland, &["general", "border_size"], 1.0));
+        let gaps_in = format_number(num_at(hyprland, &["general", "gaps_in"], 5.0));
+        let gaps_out = format_number(num_at(hyprland, &["general", "gaps_out"], 20.0));
+
+        // Groups default to the window border colors
+        let group_color = |key: &str, fallback: &str| {
+            format_color(or_color(str_at(hyprland, &["group", key], ""), fallback))
+        };
+        let groupbar_color = |key: &str, fallback: &str| {
+            format_color(or_color(
+                str_at(hyprland, &["group", "groupbar", key], ""),
+                fallback,
+            ))
+        };
+        let mut group = vec![
+            format!(
+                "    col.border_active = {}",
+                group_color("border_active", active_border)
+            ),
+            format!(
+                "    col.border_inactive = {}",
+                group_color("border_inactive", inactive_border)
+            ),
+            format!(
+                "    col.border_locked_active = {}",
+                group_color("border_locked_active", active_border)
+            ),
+            format!(
+                "    col.border_locked_inactive = {}",
+                group_color("border_locked_inactive", inactive_border)
+            ),
+            String::new(),
+            "    groupbar {".to_string(),
+            format!(
+                "        col.active = {}",
+                groupbar_color("active", active_border)
+            ),
+            format!(
+                "        col.inactive = {}",
+                groupbar_color("inactive", inactive_border)
+            ),
+        ];
+        group.extend(set_lines(
+            2,
+            &[
+                (
+                    "text_color",
+                    set_color(hyprland, &["group", "groupbar", "text_color"]),
+                ),
+                (
+                    "font_size",
+                    set_number(hyprland, &["group", "groupbar", "font_size"]),
+                ),
+            ],
+        ));
+        group.push("    }".to_string());
+
+        // Decoration: only rounding is always written, the rest only when the user set it
+        let rounding = format_number(num_at(hyprland, &["decoration", "rounding"], 0.0));
+        let decoration_at = |key: &str| set_number(hyprland, &["decoration", key]);
+        let mut decoration = vec![format!("    rounding = {rounding}")];
+        decoration.extend(set_lines(
+            1,
+            &[
+                ("active_opacity", decoration_at("active_opacity")),
+                ("inactive_opacity", decoration_at("inactive_opacity")),
+                (
+                    "dim_inactive",
+                    set_toggle(hyprland, &["decoration", "dim_inactive"]),
+                ),
+                ("dim_strength", decoration_at("dim_strength")),
+            ],
+        ));
+        for (name, keys) in [
+            ("shadow", ["enabled", "range", "render_power", "color"]),
+            ("blur", ["enabled", "size", "passes", "vibrancy"]),
+        ] {
+            let entries: Vec<(&str, Option<String>)> = keys
+                .iter()
+                .map(|&key| {
+                    let path = ["decoration", name, key];
+                    let value = match key {
+                        "enabled" => set_toggle(hyprland, &path),
+                        "color" => set_color(hyprland, &path),
+                        _ => set_number(hyprland, &path),
+                    };
+                    (key, value)
+                })
+                .collect();
+            decoration.extend(nested_block(1, name, set_lines(2, &entries)));
         }
-        let border_size = general
-            .get("border_size")
-            .and_then(|a| a.as_u64())
-            .unwrap_or(1)
-            .to_string();
-        let gaps_in = general
-            .get("gaps_in")
-            .and_then(|a| a.as_u64())
-            .unwrap_or(5)
-            .to_string();
-        let gaps_out = general
-            .get("gaps_out")
-            .and_then(|a| a.as_u64())
-            .unwrap_or(20)
-            .to_string();
-
-        let decoration = hyprland.get("decoration").unwrap_or(&empty_obj);
-        let rounding = decoration
-            .get("rounding")
-            .and_then(|a| a.as_u64())
-            .unwrap_or(0)
-            .to_string();
+
+        let group = group.join("\n");
+        let decoration = decoration.join("\n");
+        let animations = animations_block(str_at(hyprland, &["animations", "preset"], "inherit"));
 
         Ok(format!(
             r#"# ────────────────────────────────────────────────────────────
@@ -69,73 +261,166 @@ impl ConfigGenerator for HyprlandGenerator {
 # ────────────────────────────────────────────────────────────
 
 general {{
-    col.active_border = rgb({active_border})
-    col.inactive_border = rgb({inactive_border})
+    col.active_border = {active}
+    col.inactive_border = {inactive}
     border_size = {border_size}
     gaps_in = {gaps_in}
     gaps_out = {gaps_out}
 }}
 
+group {{
+{group}
+}}
+
 decoration {{
-    rounding = {rounding}
+{decoration}
 }}
-"#
+{animations}"#
         ))
     }
 
     fn get_config_schema(&self) -> Value {
+        let color = |title: &str, description: &str, default: &str| {
+            json!({
+                "type": "string",
+                "title": title,
+                "format": "color",
+                "description": description,
+                "output_format": "hex-no-hash",
+                "default": default
+            })
+        };
+        let number = |title: &str, description: &str, default: f64, min: f64, max: f64| {
+            json!({
+                "type": "number",
+                "title": title,
+                "description": description,
+                "default": default,
+                "minimum": min,
+                "maximum": max
+            })
+        };
+        // Look-and-feel settings have no default: unset ones keep Omarchy's values
+        let optional_number = |title: &str, description: &str, min: f64, max: f64| {
+            json!({
+                "type": "number",
+                "title": title,
+                "description": format!("{description}; leave unset to keep Omarchy's value"),
+                "minimum": min,
+                "maximum": max
+            })
+        };
+        let toggle = |title: &str, description: &str| {
+            json!({
+                "type": "string",
+                "title": title,
+                "description": format!("{description}; inherit keeps Omarchy's value"),
+                "enum": TOGGLE,
+                "default": "inherit"
+            })
+        };
+
         json!({
             "type": "object",
-            "x-order": ["general", "decoration"],
+            "x-order": ["general", "group", "decoration", "animations"],
             "properties": {
                 "general": {
                     "type": "object",
-                    "x-order": ["active_border", "inactive_border", "border_size", "gaps_in", "gaps_out"],
+                    "x-order": [
+                        "active_border",
+                        "active_border_end",
+                        "inactive_border",
+                        "inactive_border_end",
+                        "border_angle",
+                        "border_size",
+                        "gaps_in",
+                        "gaps_out"
+                    ],
                     "properties": {
-                        "active_border": {
-                            "type": "string",
-                            "title": "Active Border",
-                            "format": "color",
-                            "description": "border color for the active window",
-                            "output_format": "hex-no-hash",
-                            "default": "8A8A8D",
-                        },
-                        "inactive_border": {
-                            "type": "string",
-                            "title": "Inactive Border",
-                            "format": "color",
-                            "description": "border color for inactive windows",
-                            "output_format": "hex-no-hash",
-                            "default": "5C5C5E",
-                        },
-                        "border_size": {
-                            "type": "number",
-                            "title": "Border Size",
-                            "description": "size of the border around windows",
-                            "default": 1
-                        },
-                        "gaps_in": {
-                            "type": "number",
-                            "title": "Gaps In",
-                            "description": "gaps between windows",
-                            "default": 5
-                        },
-                        "gaps_out": {
-                            "type": "number",
-                            "title": "Gaps Out",
-                            "description": "gaps between windows and monitor edges",
-                            "default": 20
+                        "active_border": color("Active Border", "border color for the active window", "8A8A8D"),
+                        "active_border_end": color("Active Border Gradient End", "second gradient color; leave empty for a solid border", ""),
+                        "inactive_border": color("Inactive Border", "border color for inactive windows", "5C5C5E"),
+                        "inactive_border_end": color("Inactive Border Gradient End", "second gradient color; leave empty for a solid border", ""),
+                        "border_angle": number("Gradient Angle", "angle of border gradients in degrees", 45.0, 0.0, 360.0),
+                        "border_size": number("Border Size", "size of the border around windows", 1.0, 0.0, 20.0),
+                        "gaps_in": number("Gaps In", "gaps between windows", 5.0, 0.0, 100.0),
+                        "gaps_out": number("Gaps Out", "gaps between windows and monitor edges", 20.0, 0.0, 200.0)
+                    }
+                },
+                "group": {
+                    "type": "object",
+                    "x-order": [
+                        "border_active",
+                        "border_inactive",
+                        "border_locked_active",
+                        "border_locked_inactive",
+                        "groupbar"
+                    ],
+                    "properties": {
+                        "border_active": color("Group Border Active", "leave empty to use the active border", ""),
+                        "border_inactive": color("Group Border Inactive", "leave empty to use the inactive border", ""),
+                        "border_locked_active": color("Locked Group Active", "leave empty to use the active border", ""),
+                        "border_locked_inactive": color("Locked Group Inactive", "leave empty to use the inactive border", ""),
+                        "groupbar": {
+                            "type": "object",
+                            "x-order": ["active", "inactive", "text_color", "font_size"],
+                            "properties": {
+                                "active": color("Groupbar Active", "leave empty to use the active border", ""),
+                                "inactive": color("Groupbar Inactive", "leave empty to use the inactive border", ""),
+                                "text_color": color("Groupbar Text", "title color in the groupbar; leave empty to keep Omarchy's", ""),
+                                "font_size": optional_number("Groupbar Font Size", "title font size in the groupbar", 4.0, 32.0)
+                            }
                         }
                     }
                 },
                 "decoration": {
                     "type": "object",
+                    "x-order": [
+                        "rounding",
+                        "active_opacity",
+                        "inactive_opacity",
+                        "dim_inactive",
+                        "dim_strength",
+                        "shadow",
+                        "blur"
+                    ],
                     "properties": {
-                        "rounding": {
-                            "type": "number",
-                            "title": "Rounding",
-                            "description": "rounded corners' radius",
-                            "default": 0
+                        "rounding": number("Rounding", "rounded corners' radius", 0.0, 0.0, 50.0),
+                        "active_opacity": optional_number("Active Opacity", "opacity of the focused window", 0.0, 1.0),
+                        "inactive_opacity": optional_number("Inactive Opacity", "opacity of unfocused windows", 0.0, 1.0),
+                        "dim_inactive": toggle("Dim Inactive", "dim unfocused windows"),
+                        "dim_strength": optional_number("Dim Strength", "how much unfocused windows are dimmed", 0.0, 1.0),
+                        "shadow": {
+                            "type": "object",
+                            "x-order": ["enabled", "range", "render_power", "color"],
+                            "properties": {
+                                "enabled": toggle("Shadow", "draw shadows behind windows"),
+                                "range": optional_number("Shadow Range", "shadow size in pixels", 0.0, 100.0),
+                                "render_power": optional_number("Shadow Power", "shadow falloff (1-4)", 1.0, 4.0),
+                                "color": color("Shadow Color", "shadow color, alpha included; leave empty to keep Omarchy's", "")
+                            }
+                        },
+                        "blur": {
+                            "type": "object",
+                            "x-order": ["enabled", "size", "passes", "vibrancy"],
+                            "properties": {
+                                "enabled": toggle("Blur", "blur transparent windows"),
+                                "size": optional_number("Blur Size", "blur radius", 1.0, 20.0),
+                                "passes": optional_number("Blur Passes", "number of blur passes", 1.0, 4.0),
+                                "vibrancy": optional_number("Blur Vibrancy", "color saturation boost", 0.0, 1.0)
+                            }
+                        }
+                    }
+                },
+                "animations": {
+                    "type": "object",
+                    "properties": {
+                        "preset": {
+                            "type": "string",
+                            "title": "Animation Preset",
+                            "description": "inherit keeps the animations from your Hyprland config",
+                            "enum": ANIMATION_PRESETS,
+                            "default": "inherit"
                         }
                     }
                 }
@@ -143,8 +428,284 @@ decoration {{
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement conf file parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let root = hypr_conf::parse(content)?;
+        let mut result = Map::new();
+
+        if let Some(general) = root.section("general") {
+            let mut out = Map::new();
+            for (key, prefix) in [
+                ("col.active_border", "active_border"),
+                ("col.inactive_border", "inactive_border"),
+            ] {
+                let Some(gradient) = general.get(key).and_then(hypr_conf::parse_gradient) else {
+                    continue;
+                };
+                out.insert(prefix.to_string(), json!(gradient.colors[0]));
+                if let Some(end) = gradient.colors.get(1) {
+                    out.insert(format!("{prefix}_end"), json!(end));
+                }
+                if let Some(angle) = gradient.angle {
+                    out.insert("border_angle".to_string(), number(angle));
+                }
+            }
+            insert_numbers(&mut out, general, &["border_size", "gaps_in", "gaps_out"]);
+            insert_object(&mut result, "general", out);
+        }
+
+        if let Some(group) = root.section("group") {
+            let mut out = Map::new();
+            for key in [
+                "border_active",
+                "border_inactive",
+                "border_locked_active",
+                "border_locked_inactive",
+            ] {
+                insert_color(&mut out, key, group.get(&format!("col.{key}")));
+            }
+            if let Some(groupbar) = group.section("groupbar") {
+                let mut bar = Map::new();
+                insert_color(&mut bar, "active", groupbar.get("col.active"));
+                insert_color(&mut bar, "inactive", groupbar.get("col.inactive"));
+                insert_color(&mut bar, "text_color", groupbar.get("text_color"));
+                insert_numbers(&mut bar, groupbar, &["font_size"]);
+                insert_object(&mut out, "groupbar", bar);
+            }
+            insert_object(&mut result, "group", out);
+        }
+
+        if let Some(decoration) = root.section("decoration") {
+            let mut out = Map::new();
+            insert_numbers(
+                &mut out,
+                decoration,
+                &[
+                    "rounding",
+                    "active_opacity",
+                    "inactive_opacity",
+                    "dim_strength",
+                ],
+            );
+            insert_toggle(&mut out, "dim_inactive", decoration.get("dim_inactive"));
+
+            let mut shadow = Map::new();
+            // Hyprland < 0.45 kept shadow settings flat in `decoration`
+            insert_toggle(&mut shadow, "enabled", decoration.get("drop_shadow"));
+            insert_number(&mut shadow, "range", decoration.get("shadow_range"));
+            insert_number(
+                &mut shadow,
+                "render_power",
+                decoration.get("shadow_render_power"),
+            );
+            insert_color(&mut shadow, "color", decoration.get("col.shadow"));
+            if let Some(section) = decoration.section("shadow") {
+                insert_toggle(&mut shadow, "enabled", section.get("enabled"));
+                insert_numbers(&mut shadow, section, &["range", "render_power"]);
+                insert_color(&mut shadow, "color", section.get("color"));
+            }
+            insert_object(&mut out, "shadow", shadow);
+
+            if let Some(section) = decoration.section("blur") {
+                let mut blur = Map::new();
+                insert_toggle(&mut blur, "enabled", section.get("enabled"));
+                insert_numbers(&mut blur, section, &["size", "passes", "vibrancy"]);
+                insert_object(&mut out, "blur", blur);
+            }
+            insert_object(&mut result, "decoration", out);
+        }
+
+        if let Some(animations) = root.section("animations") {
+            let preset = if animations.get("enabled").and_then(parse_bool) == Some(false) {
+                Some("none")
+            } else {
+                animations
+                    .entries
+                    .iter()
+                    .filter(|(key, _)| key == "bezier")
+                    .filter_map(|(_, value)| value.split(',').next().map(str::trim))
+                    .find(|name| ANIMATION_PRESETS.contains(name))
+            };
+            if let Some(preset) = preset {
+                result.insert("animations".to_string(), json!({ "preset": preset }));
+            }
+        }
+
+        Ok(Value::Object(result))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_brace_balance(content)
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Store whole numbers as integers, matching what schema validation produces
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+fn insert_number(out: &mut Map<String, Value>, key: &str, value: Option<&str>) {
+    // Gaps may be given per side ("5,10,5,10"); the editor only supports one value
+    if let Some(n) = value
+        .and_then(|v| v.split(',').next())
+        .and_then(|v| v.trim().parse::<f64>().ok())
+    {
+        out.insert(key.to_string(), number(n));
+    }
+}
+
+fn insert_numbers(out: &mut Map<String, Value>, section: &HyprSection, keys: &[&str]) {
+    for key in keys {
+        insert_number(out, key, section.get(key));
+    }
+}
+
+/// On/off settings are stored as the `true`/`false` choices of [`TOGGLE`]
+fn insert_toggle(out: &mut Map<String, Value>, key: &str, value: Option<&str>) {
+    if let Some(b) = value.and_then(parse_bool) {
+        out.insert(key.to_string(), json!(b.to_string()));
+    }
+}
+
+fn insert_color(out: &mut Map<String, Value>, key: &str, value: Option<&str>) {
+    if let Some(color) = value
+        .and_then(hypr_conf::parse_gradient)
+        .map(|g| g.colors[0].clone())
+    {
+        out.insert(key.to_string(), json!(color));
+    }
+}
+
+fn insert_object(out: &mut Map<String, Value>, key: &str, value: Map<String, Value>) {
+    if !value.is_empty() {
+        out.insert(key.to_string(), Value::Object(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_output_keeps_solid_borders() {
+        let config = HyprlandGenerator.generate_config(&json!({})).unwrap();
+        assert!(config.contains("col.active_border = rgb(8A8A8D)\n"));
+        assert!(config.contains("col.border_locked_inactive = rgb(5C5C5E)\n"));
+        assert!(config.contains("rounding = 0\n"));
+        // Look and feel is left to Omarchy unless the user changed it
+        for key in [
+            "opacity",
+            "dim_",
+            "shadow",
+            "blur",
+            "font_size",
+            "text_color",
+        ] {
+            assert!(!config.contains(key), "{key} written by default");
+        }
+        assert!(!config.contains("animations {"));
+        assert!(HyprlandGenerator.validate_output(&config).is_ok());
+    }
+
+    #[test]
+    fn test_gradient_borders_and_presets() {
+        let theme_data = json!({
+            "hyprland": {
+                "general": {
+                    "active_border": "33ccffee",
+                    "active_border_end": "00ff99ee",
+                    "border_angle": 90
+                },
+                "decoration": {
+                    "dim_inactive": true,
+                    "shadow": { "enabled": "false" },
+                    "blur": { "enabled": "inherit", "size": 4 }
+                },
+                "animations": { "preset": "snappy" }
+            }
+        });
+        let config = HyprlandGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.contains("col.active_border = rgba(33ccffee) rgba(00ff99ee) 90deg\n"));
+        assert!(config.contains("col.inactive_border = rgb(5C5C5E)\n"));
+        assert!(config.contains("dim_inactive = true\n"));
+        assert!(config.contains("    shadow {\n        enabled = false\n    }\n"));
+        assert!(config.contains("    blur {\n        size = 4\n    }\n"));
+        assert!(!config.contains("dim_strength"));
+        assert!(config.contains("bezier = snappy,"));
+        assert!(HyprlandGenerator.validate_output(&config).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip_through_parser() {
+        let theme_data = json!({
+            "hyprland": {
+                "general": {
+                    "active_border": "33CCFFEE",
+                    "active_border_end": "00FF99EE",
+                    "inactive_border": "595959AA",
+                    "border_angle": 45,
+                    "border_size": 2,
+                    "gaps_in": 4,
+                    "gaps_out": 8
+                },
+                "group": { "groupbar": { "font_size": 12 } },
+                "decoration": {
+                    "rounding": 8,
+                    "inactive_opacity": 0.9,
+                    "blur": { "size": 6, "passes": 3 }
+                },
+                "animations": { "preset": "none" }
+            }
+        });
+        let config = HyprlandGenerator.generate_config(&theme_data).unwrap();
+        let parsed = HyprlandGenerator.parse_existing_config(&config).unwrap();
+
+        assert_eq!(parsed["general"]["active_border"], "33CCFFEE");
+        assert_eq!(parsed["general"]["active_border_end"], "00FF99EE");
+        assert_eq!(parsed["general"]["inactive_border"], "595959AA");
+        assert_eq!(parsed["general"]["border_size"], 2);
+        assert_eq!(parsed["group"]["border_active"], "33CCFFEE");
+        assert_eq!(parsed["group"]["groupbar"]["font_size"], 12);
+        assert_eq!(parsed["decoration"]["inactive_opacity"], 0.9);
+        assert_eq!(parsed["decoration"]["blur"]["passes"], 3);
+        assert!(parsed["decoration"].get("shadow").is_none());
+        assert!(parsed["group"]["groupbar"].get("text_color").is_none());
+        assert_eq!(parsed["animations"]["preset"], "none");
+    }
+
+    #[test]
+    fn test_parse_omarchy_style_snippet() {
+        let content = r#"
+$activeBorderColor = rgb(c4a7e7)
+
+general {
+    col.active_border = $activeBorderColor
+    gaps_out = 10,20,10,20
+}
+
+decoration {
+    drop_shadow = yes
+    col.shadow = 0xee1a1a1a
+}
+"#;
+        let parsed = HyprlandGenerator.parse_existing_config(content).unwrap();
+        assert_eq!(
+            parsed,
+            json!({
+                "general": { "active_border": "C4A7E7", "gaps_out": 10 },
+                "decoration": { "shadow": { "enabled": "true", "color": "1A1A1AEE" } }
+            })
+        );
     }
 }
@@ -1,11 +1,148 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::{hypr_conf, validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::schema_validation::default_for;
+use serde_json::{json, Map, Value};
 
 pub struct HyprlockGenerator;
 
 unsafe impl Send for HyprlockGenerator {}
 unsafe impl Sync for HyprlockGenerator {}
 
+const COLOR_KEYS: [&str; 5] = [
+    "color",
+    "inner_color",
+    "outer_color",
+    "font_color",
+    "check_color",
+];
+
+/// Where Omarchy links the active theme and the wallpaper it is showing
+const THEME_BACKGROUNDS_DIR: &str = "~/.config/omarchy/current/theme/backgrounds/";
+const CURRENT_BACKGROUND: &str = "~/.config/omarchy/current/background";
+
+const HALIGN: [&str; 3] = ["left", "center", "right"];
+const VALIGN: [&str; 3] = ["top", "center", "bottom"];
+
+/// `cmd[update:N] date +"FORMAT"`, the label text used for the clock and date
+fn date_label(format: &str, update_ms: u32) -> String {
+    format!("cmd[update:{update_ms}] date +\"{format}\"")
+}
+
+fn parse_date_label(text: &str) -> Option<String> {
+    let rest = text.strip_prefix("cmd[")?;
+    let (_, command) = rest.split_once(']')?;
+    let format = command.trim().strip_prefix("date +")?;
+    Some(format.trim_matches(|c| c == '"' || c == '\'').to_string())
+}
+
+/// Reject string values that would end their line or block and inject config of their own
+fn check_strings(path: &str, value: &Value) -> Result<(), String> {
+    match value {
+        Value::String(s) if s.contains(['\n', '\r', '{', '}']) => {
+            Err(format!("Invalid hyprlock value for {path}: '{s}'"))
+        },
+        Value::Object(map) => map
+            .iter()
+            .try_for_each(|(key, child)| check_strings(&format!("{path}.{key}"), child)),
+        _ => Ok(()),
+    }
+}
+
+/// Convert any hyprlang color into the `r,g,b,a` form the schema stores
+fn rgba_comma(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Some(inner) = value
+        .strip_prefix("rgba(")
+        .and_then(|v| v.strip_suffix(')'))
+        .filter(|v| v.contains(','))
+    {
+        return Some(
+            inner
+                .split(',')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    let hex = hypr_conf::parse_color(value)?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = match hex.len() {
+        8 => (f64::from(channel(6)?) / 255.0 * 100.0).round() / 100.0,
+        _ => 1.0,
+    };
+    Some(format!(
+        "{},{},{},{alpha:?}",
+        channel(0)?,
+        channel(2)?,
+        channel(4)?
+    ))
+}
+
+fn parse_pair(value: &str) -> Option<(i64, i64)> {
+    let (x, y) = value.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Copy integer, number, boolean and string values from a widget block into `target`
+fn read_fields(section: &hypr_conf::HyprSection, keys: &[&str], target: &mut Map<String, Value>) {
+    for key in keys {
+        let Some(value) = section.get(key) else {
+            continue;
+        };
+        let parsed = if let Ok(n) = value.parse::<i64>() {
+            json!(n)
+        } else if let Ok(n) = value.parse::<f64>() {
+            json!(n)
+        } else if let Ok(b) = value.parse::<bool>() {
+            json!(b)
+        } else {
+            json!(value)
+        };
+        target.insert(key.to_string(), parsed);
+    }
+}
+
+fn read_placement(section: &hypr_conf::HyprSection, target: &mut Map<String, Value>) {
+    if let Some((x, y)) = section.get("position").and_then(parse_pair) {
+        target.insert("position_x".into(), json!(x));
+        target.insert("position_y".into(), json!(y));
+    }
+    read_fields(section, &["halign", "valign"], target);
+}
+
+fn placement_schema(x: i64, y: i64) -> Value {
+    json!({
+        "position_x": {"type": "number", "title": "Position X", "default": x},
+        "position_y": {"type": "number", "title": "Position Y", "default": y},
+        "halign": {"type": "string", "title": "Horizontal Align", "enum": HALIGN, "default": "center"},
+        "valign": {"type": "string", "title": "Vertical Align", "enum": VALIGN, "default": "center"}
+    })
+}
+
+/// Build a widget's schema from its own properties plus `enabled` and placement
+fn widget_schema(title: &str, description: &str, properties: Value, x: i64, y: i64) -> Value {
+    let mut all = Map::new();
+    all.insert(
+        "enabled".into(),
+        json!({"type": "boolean", "title": "Enabled", "default": false}),
+    );
+    all.extend(properties.as_object().cloned().unwrap_or_default());
+    all.extend(
+        placement_schema(x, y)
+            .as_object()
+            .cloned()
+            .unwrap_or_default(),
+    );
+    let order: Vec<String> = all.keys().cloned().collect();
+    json!({
+        "type": "object",
+        "title": title,
+        "description": description,
+        "x-order": order,
+        "properties": all
+    })
+}
+
 impl ConfigGenerator for HyprlockGenerator {
     fn get_app_name(&self) -> &'static str {
         "hyprlock"
@@ -18,94 +155,398 @@ impl ConfigGenerator for HyprlockGenerator {
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let hyprlock = theme_data.get("hyprlock").unwrap_or(&empty_obj);
+        check_strings("hyprlock", hyprlock)?;
+        let defaults = default_for(&self.get_config_schema()).unwrap_or_else(|| json!({}));
+        let get = |section: &str, key: &str| -> Value {
+            [hyprlock, &defaults]
+                .iter()
+                .find_map(|root| root.get(section)?.get(key).filter(|v| !v.is_null()))
+                .cloned()
+                .unwrap_or(Value::Null)
+        };
+        let text = |section: &str, key: &str| -> String {
+            match get(section, key) {
+                Value::String(s) => s,
+                Value::Null => String::new(),
+                other => other.to_string(),
+            }
+        };
+        let enabled = |section: &str| get(section, "enabled").as_bool().unwrap_or(false);
+        let placement = |section: &str| {
+            format!(
+                "    position = {}, {}\n    halign = {}\n    valign = {}\n",
+                text(section, "position_x"),
+                text(section, "position_y"),
+                text(section, "halign"),
+                text(section, "valign")
+            )
+        };
 
-        // Extract color values with defaults from template
-        let colors = hyprlock.get("colors").unwrap_or(&empty_obj);
-        let color = colors
-            .get("color")
-            .and_then(|c| c.as_str())
-            .unwrap_or("12,12,12,1.0");
-        let inner_color = colors
-            .get("inner_color")
-            .and_then(|i| i.as_str())
-            .unwrap_or("138,138,141,0.3");
-        let outer_color = colors
-            .get("outer_color")
-            .and_then(|o| o.as_str())
-            .unwrap_or("234,234,234,0.5");
-        let font_color = colors
-            .get("font_color")
-            .and_then(|f| f.as_str())
-            .unwrap_or("234,234,234,1.0");
-        let check_color = colors
-            .get("check_color")
-            .and_then(|c| c.as_str())
-            .unwrap_or("245,158,11,1.0");
-
-        Ok(format!(
+        let mut out = String::from(
             r#"# ────────────────────────────────────────────────────────────
 # Omarchy Custom Theme for Hyprlock
 # Generated with Omarchist
 # ────────────────────────────────────────────────────────────
 
-$color = rgba({color})
-$inner_color = rgba({inner_color})
-$outer_color = rgba({outer_color})
-$font_color = rgba({font_color})
-$check_color = rgba({check_color})
-"#
-        ))
+"#,
+        );
+        for key in COLOR_KEYS {
+            out.push_str(&format!("${key} = rgba({})\n", text("colors", key)));
+        }
+
+        if enabled("background") {
+            let image = text("background", "image");
+            let path = match image.trim() {
+                "" => CURRENT_BACKGROUND.to_string(),
+                image if image.contains('/') => image.to_string(),
+                image => format!("{THEME_BACKGROUNDS_DIR}{image}"),
+            };
+            out.push_str(&format!(
+                "\nbackground {{\n    monitor =\n    color = $color\n    path = {path}\n    blur_passes = {}\n    blur_size = {}\n    brightness = {}\n}}\n",
+                text("background", "blur_passes"),
+                text("background", "blur_size"),
+                text("background", "brightness")
+            ));
+        }
+
+        if enabled("input_field") {
+            out.push_str(&format!(
+                "\ninput-field {{\n    monitor =\n    size = {}, {}\n{}\n    inner_color = $inner_color\n    outer_color = $outer_color\n    outline_thickness = {}\n\n    font_family = {}\n    font_color = $font_color\n\n    placeholder_text = {}\n    check_color = $check_color\n    fail_text = <i>$FAIL ($ATTEMPTS)</i>\n\n    rounding = {}\n    shadow_passes = 0\n    fade_on_empty = {}\n}}\n",
+                text("input_field", "width"),
+                text("input_field", "height"),
+                placement("input_field"),
+                text("input_field", "outline_thickness"),
+                text("input_field", "font_family"),
+                text("input_field", "placeholder_text"),
+                text("input_field", "rounding"),
+                text("input_field", "fade_on_empty")
+            ));
+        }
+
+        for (section, update_ms) in [("clock", 1000), ("date", 60000)] {
+            if !enabled(section) {
+                continue;
+            }
+            let format = text(section, "format");
+            if format.contains('"') {
+                return Err(format!("Invalid {section} format '{format}'"));
+            }
+            out.push_str(&format!(
+                "\nlabel {{\n    monitor =\n    text = {}\n    color = $font_color\n    font_size = {}\n    font_family = {}\n{}}}\n",
+                date_label(&format, update_ms),
+                text(section, "font_size"),
+                text(section, "font_family"),
+                placement(section)
+            ));
+        }
+
+        if enabled("image") {
+            out.push_str(&format!(
+                "\nimage {{\n    monitor =\n    path = {}\n    size = {}\n    rounding = {}\n    border_size = {}\n    border_color = $outer_color\n{}}}\n",
+                text("image", "path"),
+                text("image", "size"),
+                text("image", "rounding"),
+                text("image", "border_size"),
+                placement("image")
+            ));
+        }
+
+        Ok(out)
     }
 
     fn get_config_schema(&self) -> Value {
+        let color = |title: &str, default: &str, description: &str| {
+            json!({
+                "type": "string",
+                "output_format": "rgba-comma",
+                "default": default,
+                "title": title,
+                "description": description
+            })
+        };
+        let font =
+            json!({"type": "string", "title": "Font Family", "default": "CaskaydiaMono Nerd Font"});
+
         json!({
             "type": "object",
+            "x-order": ["colors", "background", "input_field", "clock", "date", "image"],
             "properties": {
                 "colors": {
                     "type": "object",
+                    "x-order": COLOR_KEYS,
                     "properties": {
-                        "color": {
-                            "type": "string",
-                            "output_format": "rgba-comma",
-                            "default": "12,12,12,1.0",
-                            "title": "Background Color",
-                            "description": "Main background color (RGBA format)"
-                        },
-                        "inner_color": {
-                            "type": "string",
-                            "output_format": "rgba-comma",
-                            "default": "138,138,141,0.3",
-                            "title": "Inner Color",
-                            "description": "Inner element color (RGBA format)"
-                        },
-                        "outer_color": {
-                            "type": "string",
-                            "output_format": "rgba-comma",
-                            "default": "234,234,234,0.5",
-                            "title": "Outer Color",
-                            "description": "Outer element color (RGBA format)"
-                        },
-                        "font_color": {
+                        "color": color("Background Color", "12,12,12,1.0", "Main background color (RGBA format)"),
+                        "inner_color": color("Inner Color", "138,138,141,0.3", "Inner element color (RGBA format)"),
+                        "outer_color": color("Outer Color", "234,234,234,0.5", "Outer element color (RGBA format)"),
+                        "font_color": color("Font Color", "234,234,234,1.0", "Text color (RGBA format)"),
+                        "check_color": color("Check Color", "245,158,11,1.0", "Check/accent color (RGBA format)")
+                    }
+                },
+                "background": {
+                    "type": "object",
+                    "title": "Background",
+                    "description": "Omarchy's hyprlock.conf already draws the wallpaper; enable to replace it",
+                    "x-order": ["enabled", "image", "blur_passes", "blur_size", "brightness"],
+                    "properties": {
+                        "enabled": {"type": "boolean", "title": "Enabled", "default": false},
+                        "image": {
                             "type": "string",
-                            "output_format": "rgba-comma",
-                            "default": "234,234,234,1.0",
-                            "title": "Font Color",
-                            "description": "Text color (RGBA format)"
+                            "title": "Image",
+                            "description": "A file in the theme's backgrounds folder or a full path; empty uses the current wallpaper",
+                            "default": ""
                         },
-                        "check_color": {
-                            "type": "string",
-                            "output_format": "rgba-comma",
-                            "default": "245,158,11,1.0",
-                            "title": "Check Color",
-                            "description": "Check/accent color (RGBA format)"
-                        }
+                        "blur_passes": {"type": "number", "title": "Blur Passes", "default": 3, "minimum": 0, "maximum": 10},
+                        "blur_size": {"type": "number", "title": "Blur Size", "default": 7, "minimum": 0},
+                        "brightness": {"type": "number", "title": "Brightness", "default": 0.8172, "minimum": 0, "maximum": 2}
                     }
-                }
+                },
+                "input_field": widget_schema(
+                    "Input Field",
+                    "Omarchy's hyprlock.conf already draws one; enable only when it is not sourced",
+                    json!({
+                        "width": {"type": "number", "title": "Width", "default": 650, "minimum": 0},
+                        "height": {"type": "number", "title": "Height", "default": 100, "minimum": 0},
+                        "outline_thickness": {"type": "number", "title": "Outline Thickness", "default": 4, "minimum": 0},
+                        "rounding": {"type": "number", "title": "Rounding", "description": "-1 for fully rounded", "default": 0, "minimum": -1},
+                        "font_family": font,
+                        "placeholder_text": {"type": "string", "title": "Placeholder Text", "default": "Enter Password 󰈷"},
+                        "fade_on_empty": {"type": "boolean", "title": "Fade When Empty", "default": false}
+                    }),
+                    0,
+                    0
+                ),
+                "clock": widget_schema(
+                    "Clock",
+                    "A label showing the time",
+                    json!({
+                        "format": {"type": "string", "title": "Format", "description": "date(1) format string", "default": "%H:%M"},
+                        "font_size": {"type": "number", "title": "Font Size", "default": 90, "minimum": 1},
+                        "font_family": font
+                    }),
+                    0,
+                    250
+                ),
+                "date": widget_schema(
+                    "Date",
+                    "A label showing the date",
+                    json!({
+                        "format": {"type": "string", "title": "Format", "description": "date(1) format string", "default": "%A, %B %d"},
+                        "font_size": {"type": "number", "title": "Font Size", "default": 20, "minimum": 1},
+                        "font_family": font
+                    }),
+                    0,
+                    160
+                ),
+                "image": widget_schema(
+                    "Image",
+                    "A picture such as the user's avatar",
+                    json!({
+                        "path": {"type": "string", "title": "Path", "default": "~/.face"},
+                        "size": {"type": "number", "title": "Size", "default": 150, "minimum": 1},
+                        "rounding": {"type": "number", "title": "Rounding", "description": "-1 for a circle", "default": -1, "minimum": -1},
+                        "border_size": {"type": "number", "title": "Border Size", "default": 4, "minimum": 0}
+                    }),
+                    0,
+                    -200
+                )
             }
         })
     }
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement conf file parsing if needed
-        Ok(json!({}))
+
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let root = hypr_conf::parse(content)?;
+        let mut result = Map::new();
+
+        let colors: Map<String, Value> = COLOR_KEYS
+            .iter()
+            .filter_map(|key| {
+                let value = rgba_comma(root.variable(key)?)?;
+                Some((key.to_string(), json!(value)))
+            })
+            .collect();
+        if !colors.is_empty() {
+            result.insert("colors".into(), Value::Object(colors));
+        }
+
+        if let Some(section) = root.section("background") {
+            let mut background = Map::new();
+            background.insert("enabled".into(), json!(true));
+            if let Some(path) = section.get("path") {
+                let image = match path {
+                    CURRENT_BACKGROUND => "",
+                    path => path.strip_prefix(THEME_BACKGROUNDS_DIR).unwrap_or(path),
+                };
+                background.insert("image".into(), json!(image));
+            }
+            read_fields(
+                section,
+                &["blur_passes", "blur_size", "brightness"],
+                &mut background,
+            );
+            result.insert("background".into(), Value::Object(background));
+        }
+
+        if let Some(section) = root.section("input-field") {
+            let mut input = Map::new();
+            input.insert("enabled".into(), json!(true));
+            if let Some((width, height)) = section.get("size").and_then(parse_pair) {
+                input.insert("width".into(), json!(width));
+                input.insert("height".into(), json!(height));
+            }
+            read_fields(
+                section,
+                &[
+                    "outline_thickness",
+                    "rounding",
+                    "font_family",
+                    "placeholder_text",
+                    "fade_on_empty",
+                ],
+                &mut input,
+            );
+            read_placement(section, &mut input);
+            result.insert("input_field".into(), Value::Object(input));
+        }
+
+        for label in root.sections("label") {
+            let Some(format) = label.get("text").and_then(parse_date_label) else {
+                continue;
+            };
+            // Time-of-day formats make the clock, anything else the date
+            let is_clock = ["%H", "%I", "%M", "%S", "%R", "%T"]
+                .iter()
+                .any(|spec| format.contains(spec));
+            let name = if is_clock { "clock" } else { "date" };
+            if result.contains_key(name) {
+                continue;
+            }
+            let mut widget = Map::new();
+            widget.insert("enabled".into(), json!(true));
+            widget.insert("format".into(), json!(format));
+            read_fields(label, &["font_size", "font_family"], &mut widget);
+            read_placement(label, &mut widget);
+            result.insert(name.into(), Value::Object(widget));
+        }
+
+        if let Some(section) = root.section("image") {
+            let mut image = Map::new();
+            image.insert("enabled".into(), json!(true));
+            read_fields(
+                section,
+                &["path", "size", "rounding", "border_size"],
+                &mut image,
+            );
+            read_placement(section, &mut image);
+            result.insert("image".into(), Value::Object(image));
+        }
+
+        Ok(Value::Object(result))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_brace_balance(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_omarchy_theme() {
+        let config = HyprlockGenerator.generate_config(&json!({})).unwrap();
+        let body: Vec<&str> = config
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.is_empty())
+            .collect();
+        let template = include_str!("../../../../resources/template/hyprlock.conf");
+        assert_eq!(body, template.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_widgets_round_trip() {
+        let theme = json!({
+            "hyprlock": {
+                "colors": { "check_color": "68,157,171,1.0" },
+                "background": { "enabled": true, "image": "1-wall.jpg", "blur_passes": 2 },
+                "input_field": { "enabled": true, "width": 400, "rounding": -1 },
+                "clock": { "enabled": true, "format": "%I:%M %p", "position_y": 300 },
+                "date": { "enabled": true },
+                "image": { "enabled": true, "size": 120 }
+            }
+        });
+        let config = HyprlockGenerator.generate_config(&theme).unwrap();
+        assert!(HyprlockGenerator.validate_output(&config).is_ok());
+        assert!(config.contains("path = ~/.config/omarchy/current/theme/backgrounds/1-wall.jpg"));
+        assert!(config.contains("size = 400, 100"));
+        assert!(config.contains("text = cmd[update:1000] date +\"%I:%M %p\""));
+
+        let parsed = HyprlockGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["check_color"], "68,157,171,1.0");
+        assert_eq!(parsed["background"]["image"], "1-wall.jpg");
+        assert_eq!(parsed["background"]["blur_passes"], 2);
+        assert_eq!(parsed["input_field"]["width"], 400);
+        assert_eq!(parsed["input_field"]["rounding"], -1);
+        assert_eq!(parsed["input_field"]["fade_on_empty"], false);
+        assert_eq!(parsed["clock"]["format"], "%I:%M %p");
+        assert_eq!(parsed["clock"]["position_y"], 300);
+        assert_eq!(parsed["date"]["format"], "%A, %B %d");
+        assert_eq!(parsed["image"]["size"], 120);
+
+        let regenerated = HyprlockGenerator
+            .generate_config(&json!({ "hyprlock": parsed }))
+            .unwrap();
+        assert_eq!(regenerated, config);
+    }
+
+    #[test]
+    fn test_string_fields_cannot_inject_config() {
+        for (section, key) in [
+            ("background", "image"),
+            ("input_field", "placeholder_text"),
+            ("input_field", "font_family"),
+            ("clock", "font_family"),
+            ("image", "path"),
+            ("colors", "color"),
+        ] {
+            for bad in ["x\n}\nexec-once = sh", "x } label {", "x\r"] {
+                let theme = json!({ "hyprlock": { section: { "enabled": true, key: bad } } });
+                assert!(
+                    HyprlockGenerator.generate_config(&theme).is_err(),
+                    "{section}.{key} accepted {bad:?}"
+                );
+            }
+        }
+        let theme = json!({ "hyprlock": { "clock": { "enabled": true, "format": "%H\"" } } });
+        assert!(HyprlockGenerator.generate_config(&theme).is_err());
+    }
+
+    #[test]
+    fn test_parse_omarchy_hyprlock() {
+        let content = r#"
+source = ~/.config/omarchy/current/theme/hyprlock.conf
+
+background {
+    monitor =
+    color = $color
+    path = ~/.config/omarchy/current/background
+    blur_passes = 3
+}
+
+input-field {
+    size = 650, 100
+    inner_color = $inner_color
+    placeholder_text = Enter Password 󰈷
+}
+"#;
+        let theme = "$color = rgba(26,27,38,1.0)\n$inner_color = rgba(cdd6f480)\n";
+        let parsed = HyprlockGenerator
+            .parse_existing_config(&format!("{theme}{content}"))
+            .unwrap();
+        assert_eq!(parsed["colors"]["color"], "26,27,38,1.0");
+        assert_eq!(parsed["colors"]["inner_color"], "205,214,244,0.5");
+        assert_eq!(parsed["background"]["image"], "");
+        assert_eq!(parsed["input_field"]["height"], 100);
+        assert!(parsed.get("clock").is_none());
     }
 }
@@ -1,4 +1,5 @@
 use super::ConfigGenerator;
+use crate::services::themes::icon_themes;
 use serde_json::{json, Value};
 
 pub struct IconsGenerator;
@@ -6,6 +7,17 @@ pub struct IconsGenerator;
 unsafe impl Send for IconsGenerator {}
 unsafe impl Sync for IconsGenerator {}
 
+/// Offered when no icon themes can be found, e.g. outside a desktop session
+const YARU_VARIANTS: [&str; 7] = [
+    "Yaru-red",
+    "Yaru-blue",
+    "Yaru-olive",
+    "Yaru-yellow",
+    "Yaru-purple",
+    "Yaru-magenta",
+    "Yaru-sage",
+];
+
 impl ConfigGenerator for IconsGenerator {
     fn get_app_name(&self) -> &'static str {
         "icons"
@@ -32,6 +44,15 @@ impl ConfigGenerator for IconsGenerator {
     }
 
     fn get_config_schema(&self) -> Value {
+        // Offer what is installed; themes copied from another machine still save, with a warning
+        let mut installed: Vec<String> = icon_themes::installed_icon_themes()
+            .into_iter()
+            .map(|theme| theme.id)
+            .collect();
+        if installed.is_empty() {
+            installed = YARU_VARIANTS.iter().map(|v| v.to_string()).collect();
+        }
+
         json!({
             "type": "object",
             "properties": {
@@ -39,15 +60,8 @@ impl ConfigGenerator for IconsGenerator {
                     "type": "string",
                     "title": "Icon Theme Name",
                     "description": "Name of the icon theme to use",
-                    "enum": [
-                        "Yaru-red",
-                        "Yaru-blue",
-                        "Yaru-olive",
-                        "Yaru-yellow",
-                        "Yaru-purple",
-                        "Yaru-magenta",
-                        "Yaru-sage",
-                    ]
+                    "enum": installed,
+                    "x-allow-unlisted": true
                 }
             }
         })
@@ -0,0 +1,116 @@
+use super::terminal::{inherited_colors_schema, ParsedPalette, TerminalPalette};
+use super::ConfigGenerator;
+use serde_json::Value;
+
+pub struct KittyGenerator;
+
+unsafe impl Send for KittyGenerator {}
+unsafe impl Sync for KittyGenerator {}
+
+impl ConfigGenerator for KittyGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "kitty"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "kitty.conf"
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let palette = TerminalPalette::resolve(theme_data, "kitty");
+
+        let ansi: String = palette
+            .ansi()
+            .map(|(index, color)| format!("color{index} {color}\n"))
+            .collect();
+
+        Ok(format!(
+            r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for Kitty
+# Generated with Omarchist
+# ────────────────────────────────────────────────────────────
+
+foreground {foreground}
+background {background}
+selection_foreground {foreground}
+selection_background {selection_background}
+
+cursor {cursor}
+cursor_text_color {cursor_text}
+
+url_color {url}
+
+active_tab_foreground {background}
+active_tab_background {foreground}
+inactive_tab_foreground {foreground}
+inactive_tab_background {background}
+
+{ansi}"#,
+            foreground = palette.foreground,
+            background = palette.background,
+            selection_background = palette.selection_background,
+            cursor = palette.cursor,
+            cursor_text = palette.cursor_text,
+            url = palette.normal[4],
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        inherited_colors_schema()
+    }
+
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let mut parsed = ParsedPalette::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            let value = value.trim().to_string();
+
+            match key {
+                "background" => parsed.background = Some(value),
+                "foreground" => parsed.foreground = Some(value),
+                "cursor" => parsed.cursor = Some(value),
+                "cursor_text_color" => parsed.cursor_text = Some(value),
+                "selection_background" => parsed.selection_background = Some(value),
+                _ => {
+                    if let Some(index) = key.strip_prefix("color").and_then(|i| i.parse().ok()) {
+                        parsed.set_ansi(index, &value);
+                    }
+                },
+            }
+        }
+
+        Ok(parsed.into_theme_data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_kitty_uses_alacritty_palette() {
+        let theme_data = json!({
+            "alacritty": { "colors": { "primary": { "background": "#101010" } } }
+        });
+        let config = KittyGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.contains("background #101010\n"));
+        assert!(config.contains("color15 #eaeaea\n"));
+    }
+
+    #[test]
+    fn test_kitty_round_trip() {
+        let config = KittyGenerator.generate_config(&json!({})).unwrap();
+        let parsed = KittyGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["primary"]["background"], "#121212");
+        assert_eq!(parsed["colors"]["normal"]["red"], "#D35F5F");
+        assert_eq!(parsed["colors"]["bright"]["blue"], "#f59e0b");
+    }
+}
@@ -1,11 +1,165 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::schema_validation::default_for;
+use serde_json::{json, Map, Value};
 
 pub struct MakoGenerator;
 
 unsafe impl Send for MakoGenerator {}
 unsafe impl Sync for MakoGenerator {}
 
+/// Color options, as schema keys; mako spells them with dashes (`text-color`)
+const COLOR_KEYS: [&str; 4] = [
+    "text_color",
+    "border_color",
+    "background_color",
+    "progress_color",
+];
+
+/// Global style options in output order. `padding` and `outer_margin` accept CSS-style
+/// lists (`5,10`), so they are strings.
+const STYLE_KEYS: [&str; 10] = [
+    "width",
+    "height",
+    "padding",
+    "border_size",
+    "border_radius",
+    "font",
+    "anchor",
+    "outer_margin",
+    "default_timeout",
+    "max_icon_size",
+];
+const NUMBER_STYLE_KEYS: [&str; 6] = [
+    "width",
+    "height",
+    "border_size",
+    "border_radius",
+    "default_timeout",
+    "max_icon_size",
+];
+
+const URGENCY_LEVELS: [&str; 2] = ["low", "critical"];
+
+/// How mako composites the progress bar; `over` is its default
+const PROGRESS_OPERATORS: [&str; 2] = ["over", "source"];
+
+/// A `[criteria]` block, or the global options before the first block (`criteria: None`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IniSection {
+    pub criteria: Option<String>,
+    pub entries: Vec<(String, String)>,
+}
+
+/// Parse mako's INI dialect, keeping every key and section in file order
+pub fn parse_ini(content: &str) -> Result<Vec<IniSection>, String> {
+    let mut sections = vec![IniSection {
+        criteria: None,
+        entries: Vec::new(),
+    }];
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let criteria = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {}: section header is missing ']'", index + 1))?;
+            sections.push(IniSection {
+                criteria: Some(criteria.trim().to_string()),
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected 'key=value'", index + 1))?;
+        sections
+            .last_mut()
+            .expect("global section")
+            .entries
+            .push((key.trim().to_string(), value.trim().to_string()));
+    }
+
+    Ok(sections)
+}
+
+fn mako_key(schema_key: &str) -> String {
+    schema_key.replace('_', "-")
+}
+
+fn schema_key(mako_key: &str) -> String {
+    mako_key.replace('-', "_")
+}
+
+/// Render a JSON scalar the way mako expects it; objects and arrays are not valid values
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn write_entries<'a>(out: &mut String, entries: impl IntoIterator<Item = (&'a String, &'a Value)>) {
+    for (key, value) in entries {
+        if let Some(value) = scalar(value).filter(|v| !v.is_empty()) {
+            out.push_str(&format!("{}={value}\n", mako_key(key)));
+        }
+    }
+}
+
+/// Split mako's `progress-color = [over|source] #RRGGBB` into its operator and color
+pub fn split_progress_color(value: &str) -> (Option<&str>, &str) {
+    match value.trim().split_once(char::is_whitespace) {
+        Some((operator, color)) if PROGRESS_OPERATORS.contains(&operator) => {
+            (Some(operator), color.trim())
+        },
+        _ => (None, value.trim()),
+    }
+}
+
+/// Store a color option, moving a progress operator into `progress_operator`
+fn insert_color(section: &mut Map<String, Value>, field: String, value: &str) {
+    if field == "progress_color" {
+        let (operator, color) = split_progress_color(value);
+        if let Some(operator) = operator {
+            section.insert("progress_operator".to_string(), json!(operator));
+        }
+        section.insert(field, json!(color));
+    } else {
+        section.insert(field, json!(value));
+    }
+}
+
+/// Non-empty colors of `section`, in `COLOR_KEYS` order. A non-default progress operator
+/// is written in front of the progress color.
+fn color_entries(section: Option<&Value>) -> Vec<(String, Value)> {
+    let operator = section
+        .and_then(|s| s.get("progress_operator"))
+        .and_then(|o| o.as_str())
+        .filter(|o| *o != "over" && PROGRESS_OPERATORS.contains(o));
+    COLOR_KEYS
+        .iter()
+        .filter_map(|key| {
+            let value = section?.get(*key)?;
+            let color = scalar(value)?;
+            if color.trim().is_empty() {
+                return None;
+            }
+            let value = match operator {
+                Some(operator) if *key == "progress_color" => json!(format!("{operator} {color}")),
+                _ => value.clone(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
 impl ConfigGenerator for MakoGenerator {
     fn get_app_name(&self) -> &'static str {
         "mako"
@@ -18,95 +172,393 @@ impl ConfigGenerator for MakoGenerator {
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let mako = theme_data.get("mako").unwrap_or(&empty_obj);
+        // Missing values fall back to the schema defaults, which mirror the template
+        let defaults = default_for(&self.get_config_schema()).unwrap_or_else(|| json!({}));
+        let get = |path: &[&str]| -> Option<Value> {
+            let lookup = |root: &Value| {
+                path.iter()
+                    .try_fold(root, |v, key| v.get(key))
+                    .filter(|v| !v.is_null())
+                    .cloned()
+            };
+            lookup(mako).or_else(|| lookup(&defaults))
+        };
 
-        // Extract color values with defaults from template
-        let colors = mako.get("colors").unwrap_or(&empty_obj);
-        let normal = colors.get("normal").unwrap_or(&empty_obj);
-        let text_color = normal
-            .get("text_color")
-            .and_then(|t| t.as_str())
-            .unwrap_or("#8A8A8D");
-        let border_color = normal
-            .get("border_color")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#8A8A8D");
-        let background_color = normal
-            .get("background_color")
-            .and_then(|bg| bg.as_str())
-            .unwrap_or("#1E1E1E");
-
-        Ok(format!(
+        let mut out = String::from(
             r#"# ────────────────────────────────────────────────────────────
 # Omarchy Custom Theme for Mako
 # Generated with Omarchist
 # ────────────────────────────────────────────────────────────
 
-text-color={text_color}
-border-color={border_color}
-background-color={background_color}
-width=420
-height=110
-padding=10
-border-size=2
-font=Liberation Sans 11
-anchor=top-right
-outer-margin=20
-default-timeout=5000
-max-icon-size=32
-
-[app-name=Spotify]
-invisible=1
-
-[mode=do-not-disturb]
-invisible=true
-
-[mode=do-not-disturb app-name=notify-send]
-invisible=false
 "#,
-        ))
+        );
+
+        let normal: Map<String, Value> = COLOR_KEYS
+            .iter()
+            .chain(&["progress_operator"])
+            .filter_map(|key| get(&["colors", "normal", key]).map(|v| (key.to_string(), v)))
+            .collect();
+        write_entries(
+            &mut out,
+            color_entries(Some(&Value::Object(normal)))
+                .iter()
+                .map(|(k, v)| (k, v)),
+        );
+
+        let style: Vec<(String, Value)> = STYLE_KEYS
+            .iter()
+            .filter_map(|key| get(&["style", key]).map(|v| (key.to_string(), v)))
+            .collect();
+        write_entries(&mut out, style.iter().map(|(k, v)| (k, v)));
+
+        if let Some(Value::Object(extra)) = mako.get("extra") {
+            write_entries(&mut out, extra);
+        }
+
+        for level in URGENCY_LEVELS {
+            let colors = color_entries(mako.get("urgency").and_then(|u| u.get(level)));
+            if !colors.is_empty() {
+                out.push_str(&format!("\n[urgency={level}]\n"));
+                write_entries(&mut out, colors.iter().map(|(k, v)| (k, v)));
+            }
+        }
+
+        let criteria = get(&["criteria"]).unwrap_or_else(|| json!([]));
+        for section in criteria.as_array().into_iter().flatten() {
+            let Some(header) = section
+                .get("criteria")
+                .and_then(|c| c.as_str())
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+            else {
+                continue;
+            };
+            if header.contains(['[', ']', '\n']) {
+                return Err(format!("Invalid mako criteria '{header}'"));
+            }
+            out.push_str(&format!("\n[{header}]\n"));
+            if let Some(Value::Object(options)) = section.get("options") {
+                for (key, value) in options {
+                    if let Some(value) = scalar(value) {
+                        out.push_str(&format!("{key}={value}\n"));
+                    }
+                }
+            }
+        }
+
+        Ok(out)
     }
 
     fn get_config_schema(&self) -> Value {
+        let color = |title: &str, description: &str, default: &str| {
+            json!({
+                "type": "string",
+                "format": "color",
+                "title": title,
+                "description": description,
+                "default": default
+            })
+        };
+        let operator = || {
+            json!({
+                "type": "string",
+                "title": "Progress Operator",
+                "description": "How the progress bar is drawn over the background",
+                "enum": PROGRESS_OPERATORS,
+                "default": "over"
+            })
+        };
+        let number = |title: &str, default: i64| json!({ "type": "number", "title": title, "default": default, "minimum": 0 });
+        let urgency = |title: &str| {
+            json!({
+                "type": "object",
+                "title": title,
+                "x-order": COLOR_KEYS,
+                "properties": {
+                    "border_color": color("Border Color", "Leave empty to use the normal color", ""),
+                    "background_color": color("Background Color", "Leave empty to use the normal color", ""),
+                    "text_color": color("Text Color", "Leave empty to use the normal color", ""),
+                    "progress_color": color("Progress Color", "Leave empty to use the normal color", ""),
+                    "progress_operator": operator()
+                }
+            })
+        };
+
         json!({
             "type": "object",
+            "x-order": ["colors", "urgency", "style", "criteria", "extra"],
             "properties": {
                 "colors": {
                     "type": "object",
                     "properties": {
                         "normal": {
                             "type": "object",
+                            "x-order": COLOR_KEYS,
                             "properties": {
-                                "border_color": {
-                                    "type": "string",
-                                    "format": "color",
-                                    "title": "Border Color",
-                                    "description": "Color of notification border",
-                                    "default": "#8A8A8D",
-                                },
-                                "background_color": {
-                                    "type": "string",
-                                    "format": "color",
-                                    "title": "Background Color",
-                                    "description": "Background color of notifications",
-                                    "default": "#1E1E1E",
-                                },
-                                "text_color": {
-                                    "type": "string",
-                                    "format": "color",
-                                    "title": "Text Color",
-                                    "description": "Color of notification text",
-                                    "default": "#8A8A8D",
-                                },
+                                "border_color": color("Border Color", "Color of notification border", "#8A8A8D"),
+                                "background_color": color("Background Color", "Background color of notifications", "#1E1E1E"),
+                                "text_color": color("Text Color", "Color of notification text", "#8A8A8D"),
+                                "progress_color": color("Progress Color", "Color of the progress indicator; leave empty for mako's default", ""),
+                                "progress_operator": operator()
                             }
-                        },                      
+                        }
+                    }
+                },
+                "urgency": {
+                    "type": "object",
+                    "title": "Urgency",
+                    "x-order": URGENCY_LEVELS,
+                    "properties": {
+                        "low": urgency("Low Urgency"),
+                        "critical": urgency("Critical Urgency")
+                    }
+                },
+                "style": {
+                    "type": "object",
+                    "title": "Style",
+                    "x-order": STYLE_KEYS,
+                    "properties": {
+                        "width": number("Width", 420),
+                        "height": number("Height", 110),
+                        "padding": {"type": "string", "title": "Padding", "description": "One value or CSS-style top,right,bottom,left", "default": "10"},
+                        "border_size": number("Border Size", 2),
+                        "border_radius": number("Border Radius", 0),
+                        "font": {"type": "string", "title": "Font", "default": "Liberation Sans 11"},
+                        "anchor": {
+                            "type": "string",
+                            "title": "Anchor",
+                            "enum": [
+                                "top-right",
+                                "top-center",
+                                "top-left",
+                                "bottom-right",
+                                "bottom-center",
+                                "bottom-left",
+                                "center-right",
+                                "center-left",
+                                "center"
+                            ],
+                            "default": "top-right"
+                        },
+                        "outer_margin": {"type": "string", "title": "Outer Margin", "description": "One value or CSS-style top,right,bottom,left", "default": "20"},
+                        "default_timeout": number("Default Timeout (ms)", 5000),
+                        "max_icon_size": number("Max Icon Size", 32)
                     }
+                },
+                "criteria": {
+                    "type": "array",
+                    "title": "Criteria Sections",
+                    "description": "Extra [criteria] blocks such as app-name=Spotify, written after the urgency colors",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "criteria": {"type": "string", "title": "Criteria"},
+                            "options": {"type": "object", "title": "Options"}
+                        }
+                    },
+                    "default": [
+                        { "criteria": "app-name=Spotify", "options": { "invisible": "1" } },
+                        { "criteria": "mode=do-not-disturb", "options": { "invisible": "true" } },
+                        { "criteria": "mode=do-not-disturb app-name=notify-send", "options": { "invisible": "false" } }
+                    ]
+                },
+                "extra": {
+                    "type": "object",
+                    "title": "Other Options",
+                    "description": "Global options Omarchist has no field for, kept as written",
+                    "default": {}
                 }
             }
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement ini file parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let sections = parse_ini(content)?;
+        let mut normal = Map::new();
+        let mut style = Map::new();
+        let mut extra = Map::new();
+        let mut urgency = Map::new();
+        let mut criteria = Vec::new();
+
+        for section in sections {
+            match section.criteria.as_deref() {
+                None => {
+                    for (key, value) in section.entries {
+                        let field = schema_key(&key);
+                        if COLOR_KEYS.contains(&field.as_str()) {
+                            insert_color(&mut normal, field, &value);
+                        } else if NUMBER_STYLE_KEYS.contains(&field.as_str()) {
+                            match value.parse::<i64>() {
+                                Ok(n) => style.insert(field, json!(n)),
+                                Err(_) => extra.insert(field, json!(value)),
+                            };
+                        } else if STYLE_KEYS.contains(&field.as_str()) {
+                            style.insert(field, json!(value));
+                        } else {
+                            extra.insert(field, json!(value));
+                        }
+                    }
+                },
+                Some(header) => {
+                    let level = header
+                        .strip_prefix("urgency=")
+                        .filter(|level| URGENCY_LEVELS.contains(level));
+                    let mut options = Map::new();
+                    for (key, value) in section.entries {
+                        let field = schema_key(&key);
+                        match level {
+                            Some(level) if COLOR_KEYS.contains(&field.as_str()) => {
+                                let colors = urgency
+                                    .entry(level.to_string())
+                                    .or_insert_with(|| json!({}));
+                                if let Value::Object(colors) = colors {
+                                    insert_color(colors, field, &value);
+                                }
+                            },
+                            _ => {
+                                options.insert(key, json!(value));
+                            },
+                        }
+                    }
+                    // Urgency blocks holding only colors are fully represented above
+                    if level.is_none() || !options.is_empty() {
+                        criteria.push(json!({ "criteria": header, "options": options }));
+                    }
+                },
+            }
+        }
+
+        Ok(json!({
+            "colors": { "normal": normal },
+            "urgency": urgency,
+            "style": style,
+            "criteria": criteria,
+            "extra": extra
+        }))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_ini(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::schema_validation::{validate_value, Findings};
+
+    const TEMPLATE: &str = include_str!("../../../../resources/template/mako.ini");
+
+    #[test]
+    fn test_defaults_match_template() {
+        let config = MakoGenerator.generate_config(&json!({})).unwrap();
+        // The template's options appear in the same order; border-radius=0 is new
+        let body: Vec<&str> = config
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.is_empty() && *l != "border-radius=0")
+            .collect();
+        let template: Vec<&str> = TEMPLATE.lines().filter(|l| !l.is_empty()).collect();
+        assert_eq!(body.len(), template.len());
+        for (generated, expected) in body.iter().zip(&template) {
+            assert!(
+                generated.eq_ignore_ascii_case(expected),
+                "{generated} != {expected}"
+            );
+        }
+        assert!(MakoGenerator.validate_output(&config).is_ok());
+    }
+
+    #[test]
+    fn test_parse_preserves_unknown_keys_and_sections() {
+        let content = r#"
+text-color=#c0caf5
+background-color=#1a1b26
+border-radius=8
+padding=5,10
+icons=0
+layer=overlay
+
+[urgency=critical]
+border-color=#ff0000
+default-timeout=0
+
+[urgency=low]
+text-color=#565f89
+
+[app-name=Slack]
+background-color=#4a154b
+"#;
+        let parsed = MakoGenerator.parse_existing_config(content).unwrap();
+        assert_eq!(parsed["colors"]["normal"]["text_color"], "#c0caf5");
+        assert_eq!(parsed["style"]["border_radius"], 8);
+        assert_eq!(parsed["style"]["padding"], "5,10");
+        assert_eq!(parsed["extra"], json!({ "icons": "0", "layer": "overlay" }));
+        assert_eq!(parsed["urgency"]["critical"]["border_color"], "#ff0000");
+        assert_eq!(parsed["urgency"]["low"]["text_color"], "#565f89");
+        assert_eq!(
+            parsed["criteria"],
+            json!([
+                { "criteria": "urgency=critical", "options": { "default-timeout": "0" } },
+                { "criteria": "app-name=Slack", "options": { "background-color": "#4a154b" } }
+            ])
+        );
+
+        // Regenerating keeps every option
+        let config = MakoGenerator
+            .generate_config(&json!({ "mako": parsed }))
+            .unwrap();
+        for line in [
+            "text-color=#c0caf5",
+            "padding=5,10",
+            "layer=overlay",
+            "[urgency=low]",
+            "default-timeout=0",
+            "[app-name=Slack]",
+        ] {
+            assert!(config.contains(line), "missing {line}:\n{config}");
+        }
+        let reparsed = MakoGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(reparsed["urgency"], parsed["urgency"]);
+        assert_eq!(reparsed["extra"], parsed["extra"]);
+    }
+
+    #[test]
+    fn test_progress_color_operator_round_trips() {
+        let content =
+            "progress-color=over #5588AAFF\n\n[urgency=critical]\nprogress-color=source #ff0000\n";
+        let parsed = MakoGenerator.parse_existing_config(content).unwrap();
+        assert_eq!(parsed["colors"]["normal"]["progress_color"], "#5588AAFF");
+        assert_eq!(parsed["colors"]["normal"]["progress_operator"], "over");
+        assert_eq!(parsed["urgency"]["critical"]["progress_color"], "#ff0000");
+        assert_eq!(parsed["urgency"]["critical"]["progress_operator"], "source");
+
+        // The split values pass schema validation, so an imported theme can be saved
+        let mut findings = Findings::default();
+        validate_value(
+            &MakoGenerator.get_config_schema(),
+            &parsed,
+            "mako",
+            &mut findings,
+        );
+        assert!(findings.errors.is_empty(), "{:?}", findings.errors);
+
+        let config = MakoGenerator
+            .generate_config(&json!({ "mako": parsed }))
+            .unwrap();
+        assert!(config.contains("progress-color=#5588AAFF\n"), "{config}");
+        assert!(
+            config.contains("progress-color=source #ff0000\n"),
+            "{config}"
+        );
+        assert!(MakoGenerator.validate_output(&config).is_ok());
+
+        let reparsed = MakoGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(reparsed["urgency"], parsed["urgency"]);
+    }
+
+    #[test]
+    fn test_parse_ini_rejects_malformed_lines() {
+        assert!(parse_ini("[urgency=low\n").is_err());
+        assert!(parse_ini("not an option\n").is_err());
     }
 }
@@ -3,21 +3,39 @@ use serde_json::Value;
 pub mod alacritty;
 pub mod btop;
 pub mod chromium;
+pub mod foot;
+pub mod ghostty;
+pub mod gtk;
+pub mod gtk_css;
+pub mod hypr_conf;
 pub mod hyprland;
 pub mod hyprlock;
 pub mod icons;
+pub mod kitty;
 pub mod mako;
 pub mod neovim;
+pub mod plugin;
+pub mod qt;
 pub mod swayosd;
+pub mod terminal;
+pub mod validation;
 pub mod walker;
 pub mod waybar;
 
+pub use validation::OutputValidationError;
+
 pub trait ConfigGenerator: Send + Sync {
-    fn get_app_name(&self) -> &'static str;
-    fn get_file_name(&self) -> &'static str;
+    fn get_app_name(&self) -> &str;
+    fn get_file_name(&self) -> &str;
     fn generate_config(&self, theme_data: &Value) -> Result<String, String>;
     fn get_config_schema(&self) -> Value;
     fn parse_existing_config(&self, content: &str) -> Result<Value, String>;
+
+    /// Check that generated output is syntactically valid for the target app before it is
+    /// written to disk. Generators without a checker accept any output.
+    fn validate_output(&self, _content: &str) -> Result<(), OutputValidationError> {
+        Ok(())
+    }
 }
 
 pub struct ConfigGeneratorRegistry {
@@ -31,7 +49,15 @@ impl Default for ConfigGeneratorRegistry {
 }
 
 impl ConfigGeneratorRegistry {
+    /// Built-in generators plus any template plugins installed in the user's config dir
     pub fn new() -> Self {
+        let mut registry = Self::builtin();
+        registry.register_plugins(plugin::installed_plugins().to_vec());
+        registry
+    }
+
+    /// Only the generators compiled into the app
+    pub fn builtin() -> Self {
         let mut registry = Self {
             generators: std::collections::HashMap::new(),
         };
@@ -48,6 +74,12 @@ impl ConfigGeneratorRegistry {
         registry.register(Box::new(walker::WalkerGenerator));
         registry.register(Box::new(neovim::NeovimGenerator));
         registry.register(Box::new(icons::IconsGenerator));
+        registry.register(Box::new(kitty::KittyGenerator));
+        registry.register(Box::new(foot::FootGenerator));
+        registry.register(Box::new(ghostty::GhosttyGenerator));
+        registry.register(Box::new(gtk::GtkGenerator));
+        registry.register(Box::new(qt::Qt6ctGenerator));
+        registry.register(Box::new(qt::KvantumGenerator));
 
         registry
     }
@@ -57,6 +89,31 @@ impl ConfigGeneratorRegistry {
             .insert(generator.get_app_name().to_string(), generator);
     }
 
+    /// Register template plugins. A plugin never replaces an already registered generator
+    /// and never writes a file another generator owns.
+    pub fn register_plugins(&mut self, plugins: Vec<plugin::TemplateGenerator>) {
+        for plugin in plugins {
+            let app_name = plugin.get_app_name();
+            if self.generators.contains_key(app_name) {
+                log::warn!("Ignoring generator plugin '{app_name}': name is already registered");
+                continue;
+            }
+            let file_name = plugin.get_file_name();
+            if let Some(owner) = self
+                .generators
+                .values()
+                .find(|generator| generator.get_file_name() == file_name)
+            {
+                log::warn!(
+                    "Ignoring generator plugin '{app_name}': {file_name} belongs to '{}'",
+                    owner.get_app_name()
+                );
+                continue;
+            }
+            self.register(Box::new(plugin));
+        }
+    }
+
     pub fn get_generator(&self, app_name: &str) -> Option<&dyn ConfigGenerator> {
         self.generators.get(app_name).map(|boxed| boxed.as_ref())
     }
@@ -1,4 +1,6 @@
+use super::terminal::TerminalPalette;
 use super::ConfigGenerator;
+use regex::Regex;
 use serde_json::{json, Value};
 
 pub struct NeovimGenerator;
@@ -6,6 +8,344 @@ pub struct NeovimGenerator;
 unsafe impl Send for NeovimGenerator {}
 unsafe impl Sync for NeovimGenerator {}
 
+const HEADER: &str = "-- ────────────────────────────────────────────────────────────\n-- Omarchy Custom Theme for Neovim\n-- Generated by Omarchist\n-- ────────────────────────────────────────────────────────────\n\n";
+
+const DEFAULT_SPEC: &str = r#"return {
+  { "tahayvr/matteblack.nvim", lazy = false, priority = 1000 },
+  {
+		"LazyVim/LazyVim",
+		opts = {
+			colorscheme = "matteblack",
+		},
+	},
+}"#;
+
+/// A colorscheme plugin offered in `plugin` mode
+struct PluginScheme {
+    name: &'static str,
+    repo: &'static str,
+    /// Key in the plugin's `opts` that selects the variant, and the schema field holding it
+    variant_opt: &'static str,
+    variant_field: &'static str,
+    variants: &'static [&'static str],
+    transparent_opt: &'static str,
+}
+
+const PLUGIN_SCHEMES: [PluginScheme; 3] = [
+    PluginScheme {
+        name: "tokyonight",
+        repo: "folke/tokyonight.nvim",
+        variant_opt: "style",
+        variant_field: "tokyonight_style",
+        variants: &["night", "storm", "moon", "day"],
+        transparent_opt: "transparent",
+    },
+    PluginScheme {
+        name: "catppuccin",
+        repo: "catppuccin/nvim",
+        variant_opt: "flavour",
+        variant_field: "catppuccin_flavour",
+        variants: &["mocha", "macchiato", "frappe", "latte"],
+        transparent_opt: "transparent_background",
+    },
+    PluginScheme {
+        name: "gruvbox",
+        repo: "ellisonleao/gruvbox.nvim",
+        variant_opt: "contrast",
+        variant_field: "gruvbox_contrast",
+        variants: &["medium", "hard", "soft"],
+        transparent_opt: "transparent_mode",
+    },
+];
+
+/// Highlight groups of the generated colorscheme, as Lua `nvim_set_hl` specs over the palette `p`
+const HIGHLIGHTS: &[(&str, &str)] = &[
+    // Editor
+    ("Normal", "{ fg = p.fg, bg = p.bg_normal }"),
+    ("NormalNC", "{ fg = p.fg, bg = p.bg_normal }"),
+    ("NormalFloat", "{ fg = p.fg, bg = p.bg_float }"),
+    ("FloatBorder", "{ fg = p.border, bg = p.bg_float }"),
+    ("Cursor", "{ fg = p.bg, bg = p.cursor }"),
+    ("CursorLine", "{ bg = p.bg_highlight }"),
+    ("CursorLineNr", "{ fg = p.yellow, bold = true }"),
+    ("LineNr", "{ fg = p.comment }"),
+    ("SignColumn", "{ bg = p.bg_normal }"),
+    ("Visual", "{ bg = p.selection }"),
+    ("Search", "{ fg = p.bg, bg = p.yellow }"),
+    ("IncSearch", "{ fg = p.bg, bg = p.orange }"),
+    ("MatchParen", "{ fg = p.cyan, bold = true }"),
+    ("Pmenu", "{ fg = p.fg, bg = p.bg_float }"),
+    ("PmenuSel", "{ fg = p.bg, bg = p.blue }"),
+    ("StatusLine", "{ fg = p.fg, bg = p.bg_highlight }"),
+    ("StatusLineNC", "{ fg = p.comment, bg = p.bg_float }"),
+    ("WinSeparator", "{ fg = p.border }"),
+    ("Title", "{ fg = p.blue, bold = true }"),
+    ("Directory", "{ fg = p.blue }"),
+    ("NonText", "{ fg = p.comment }"),
+    // Syntax
+    ("Comment", "{ fg = p.comment, italic = true }"),
+    ("Constant", "{ fg = p.orange }"),
+    ("String", "{ fg = p.green }"),
+    ("Character", "{ fg = p.green }"),
+    ("Number", "{ fg = p.orange }"),
+    ("Boolean", "{ fg = p.orange }"),
+    ("Identifier", "{ fg = p.fg }"),
+    ("Function", "{ fg = p.blue }"),
+    ("Statement", "{ fg = p.magenta }"),
+    ("Keyword", "{ fg = p.magenta, italic = true }"),
+    ("Operator", "{ fg = p.cyan }"),
+    ("PreProc", "{ fg = p.cyan }"),
+    ("Type", "{ fg = p.yellow }"),
+    ("Special", "{ fg = p.cyan }"),
+    ("Error", "{ fg = p.red }"),
+    ("Todo", "{ fg = p.bg, bg = p.yellow, bold = true }"),
+    ("DiffAdd", "{ fg = p.green }"),
+    ("DiffChange", "{ fg = p.yellow }"),
+    ("DiffDelete", "{ fg = p.red }"),
+    // Diagnostics
+    ("DiagnosticError", "{ fg = p.red }"),
+    ("DiagnosticWarn", "{ fg = p.yellow }"),
+    ("DiagnosticInfo", "{ fg = p.blue }"),
+    ("DiagnosticHint", "{ fg = p.cyan }"),
+    ("DiagnosticOk", "{ fg = p.green }"),
+    (
+        "DiagnosticUnderlineError",
+        "{ undercurl = true, sp = p.red }",
+    ),
+    (
+        "DiagnosticUnderlineWarn",
+        "{ undercurl = true, sp = p.yellow }",
+    ),
+    (
+        "DiagnosticUnderlineInfo",
+        "{ undercurl = true, sp = p.blue }",
+    ),
+    (
+        "DiagnosticUnderlineHint",
+        "{ undercurl = true, sp = p.cyan }",
+    ),
+    // Treesitter
+    ("@comment", "{ link = \"Comment\" }"),
+    ("@variable", "{ fg = p.fg }"),
+    ("@variable.builtin", "{ fg = p.red }"),
+    ("@variable.parameter", "{ fg = p.orange }"),
+    ("@variable.member", "{ fg = p.cyan }"),
+    ("@property", "{ fg = p.cyan }"),
+    ("@function", "{ link = \"Function\" }"),
+    ("@function.builtin", "{ fg = p.cyan }"),
+    ("@function.method", "{ link = \"Function\" }"),
+    ("@constructor", "{ fg = p.yellow }"),
+    ("@keyword", "{ link = \"Keyword\" }"),
+    ("@keyword.return", "{ fg = p.magenta, bold = true }"),
+    ("@string", "{ link = \"String\" }"),
+    ("@string.escape", "{ fg = p.cyan }"),
+    ("@number", "{ link = \"Number\" }"),
+    ("@boolean", "{ link = \"Boolean\" }"),
+    ("@constant", "{ link = \"Constant\" }"),
+    ("@constant.builtin", "{ fg = p.orange, bold = true }"),
+    ("@type", "{ link = \"Type\" }"),
+    ("@type.builtin", "{ fg = p.yellow, italic = true }"),
+    ("@operator", "{ link = \"Operator\" }"),
+    ("@punctuation.delimiter", "{ fg = p.fg_dim }"),
+    ("@punctuation.bracket", "{ fg = p.fg_dim }"),
+    ("@tag", "{ fg = p.red }"),
+    ("@tag.attribute", "{ fg = p.yellow }"),
+    ("@markup.heading", "{ fg = p.blue, bold = true }"),
+    ("@markup.link", "{ fg = p.cyan, underline = true }"),
+    // Telescope
+    ("TelescopeNormal", "{ fg = p.fg, bg = p.bg_float }"),
+    ("TelescopeBorder", "{ fg = p.border, bg = p.bg_float }"),
+    (
+        "TelescopePromptNormal",
+        "{ fg = p.fg, bg = p.bg_highlight }",
+    ),
+    (
+        "TelescopePromptBorder",
+        "{ fg = p.bg_highlight, bg = p.bg_highlight }",
+    ),
+    ("TelescopePromptTitle", "{ fg = p.bg, bg = p.blue }"),
+    ("TelescopePreviewTitle", "{ fg = p.bg, bg = p.green }"),
+    (
+        "TelescopeResultsTitle",
+        "{ fg = p.bg_float, bg = p.bg_float }",
+    ),
+    ("TelescopeSelection", "{ bg = p.selection }"),
+    ("TelescopeMatching", "{ fg = p.orange, bold = true }"),
+];
+
+fn lua_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn str_field<'a>(value: &'a Value, section: &str, key: &str, default: &'a str) -> &'a str {
+    value
+        .get(section)
+        .and_then(|s| s.get(key))
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or(default)
+}
+
+fn bool_field(value: &Value, section: &str, key: &str) -> bool {
+    value
+        .get(section)
+        .and_then(|s| s.get(key))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn plugin_spec(neovim: &Value) -> Result<String, String> {
+    let name = str_field(neovim, "plugin", "colorscheme", "tokyonight");
+    let scheme = PLUGIN_SCHEMES
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("Unknown Neovim colorscheme plugin '{name}'"))?;
+    let variant = str_field(neovim, "plugin", scheme.variant_field, scheme.variants[0]);
+    if !scheme.variants.contains(&variant) {
+        return Err(format!("Unknown {name} variant '{variant}'"));
+    }
+    let transparent = bool_field(neovim, "plugin", "transparent");
+
+    // gruvbox's default contrast is an empty string, and its colorscheme has no variant suffix
+    let (opt_value, colorscheme) = match scheme.name {
+        "gruvbox" if variant == "medium" => (String::new(), "gruvbox".to_string()),
+        "gruvbox" => (variant.to_string(), "gruvbox".to_string()),
+        _ => (variant.to_string(), format!("{}-{variant}", scheme.name)),
+    };
+    let plugin_name = if scheme.name == "catppuccin" {
+        "\n    name = \"catppuccin\","
+    } else {
+        ""
+    };
+
+    Ok(format!(
+        r#"return {{
+  {{
+    "{repo}",{plugin_name}
+    lazy = false,
+    priority = 1000,
+    opts = {{
+      {variant_opt} = {opt_value},
+      {transparent_opt} = {transparent},
+    }},
+  }},
+  {{
+    "LazyVim/LazyVim",
+    opts = {{
+      colorscheme = {colorscheme},
+    }},
+  }},
+}}
+"#,
+        repo = scheme.repo,
+        variant_opt = scheme.variant_opt,
+        opt_value = lua_string(&opt_value),
+        transparent_opt = scheme.transparent_opt,
+        colorscheme = lua_string(&colorscheme),
+    ))
+}
+
+fn generated_colorscheme(theme_data: &Value, neovim: &Value) -> Result<String, String> {
+    let name = str_field(neovim, "generated", "name", "omarchy");
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid Neovim colorscheme name '{name}'"));
+    }
+    let transparent = bool_field(neovim, "generated", "transparent");
+
+    let palette = TerminalPalette::resolve(theme_data, "alacritty");
+    let colors = [
+        ("bg", &palette.background),
+        ("bg_float", &palette.background),
+        ("bg_highlight", &palette.selection_background),
+        ("fg", &palette.foreground),
+        ("fg_dim", &palette.dim_foreground),
+        ("comment", &palette.bright[0]),
+        ("border", &palette.bright[0]),
+        ("selection", &palette.selection_background),
+        ("cursor", &palette.cursor),
+        ("red", &palette.normal[1]),
+        ("green", &palette.normal[2]),
+        ("yellow", &palette.normal[3]),
+        ("blue", &palette.normal[4]),
+        ("magenta", &palette.normal[5]),
+        ("cyan", &palette.normal[6]),
+        ("orange", &palette.bright[3]),
+    ];
+    let palette_lines: String = colors
+        .iter()
+        .map(|(key, value)| format!("  {key} = {},\n", lua_string(value)))
+        .collect();
+    let highlight_lines: String = HIGHLIGHTS
+        .iter()
+        .map(|(group, spec)| format!("  hl({}, {spec})\n", lua_string(group)))
+        .collect();
+    let terminal_lines: String = palette
+        .ansi()
+        .map(|(i, color)| format!("  vim.g.terminal_color_{i} = {}\n", lua_string(color)))
+        .collect();
+
+    Ok(format!(
+        r#"local p = {{
+{palette_lines}}}
+local transparent = {transparent}
+p.bg_normal = transparent and "NONE" or p.bg
+
+local function apply()
+  vim.cmd("highlight clear")
+  if vim.fn.exists("syntax_on") == 1 then
+    vim.cmd("syntax reset")
+  end
+  vim.o.termguicolors = true
+  vim.g.colors_name = {name}
+
+  local hl = function(group, spec)
+    vim.api.nvim_set_hl(0, group, spec)
+  end
+
+{highlight_lines}
+{terminal_lines}end
+
+local function lualine_mode(accent)
+  return {{
+    a = {{ fg = p.bg, bg = accent, gui = "bold" }},
+    b = {{ fg = p.fg, bg = p.bg_highlight }},
+    c = {{ fg = p.fg_dim, bg = p.bg_normal }},
+  }}
+end
+
+local lualine_theme = {{
+  normal = lualine_mode(p.blue),
+  insert = lualine_mode(p.green),
+  visual = lualine_mode(p.magenta),
+  replace = lualine_mode(p.red),
+  command = lualine_mode(p.yellow),
+  inactive = lualine_mode(p.comment),
+}}
+
+return {{
+  {{
+    "LazyVim/LazyVim",
+    opts = {{
+      colorscheme = apply,
+    }},
+  }},
+  {{
+    "nvim-lualine/lualine.nvim",
+    optional = true,
+    opts = function(_, opts)
+      opts.options = opts.options or {{}}
+      opts.options.theme = lualine_theme
+    end,
+  }},
+}}
+"#,
+        name = lua_string(name),
+    ))
+}
+
 impl ConfigGenerator for NeovimGenerator {
     fn get_app_name(&self) -> &'static str {
         "neovim"
@@ -19,32 +359,93 @@ impl ConfigGenerator for NeovimGenerator {
         let empty_obj = json!({});
         let neovim = theme_data.get("neovim").unwrap_or(&empty_obj);
 
+        match neovim.get("mode").and_then(|m| m.as_str()).unwrap_or("raw") {
+            "generated" => {
+                return Ok(format!(
+                    "{HEADER}{}",
+                    generated_colorscheme(theme_data, neovim)?
+                ))
+            },
+            "plugin" => return Ok(format!("{HEADER}{}", plugin_spec(neovim)?)),
+            _ => {},
+        }
+
         // Check if raw_config is provided
         if let Some(raw_config) = neovim.get("raw_config").and_then(|rc| rc.as_str()) {
             if !raw_config.trim().is_empty() {
-                return Ok(format!(
-                    "-- ────────────────────────────────────────────────────────────\n-- Omarchy Custom Theme for Neovim\n-- Generated by Omarchist\n-- ────────────────────────────────────────────────────────────\n\n{raw_config}"
-                ));
+                return Ok(format!("{HEADER}{raw_config}"));
             }
         }
 
         // Fallback to default template if no raw config provided
-        Ok(r#"return {
-  { "tahayvr/matteblack.nvim", lazy = false, priority = 1000 },
-  {
-		"LazyVim/LazyVim",
-		opts = {
-			colorscheme = "matteblack",
-		},
-	},
-}"#
-        .to_string())
+        Ok(DEFAULT_SPEC.to_string())
     }
 
     fn get_config_schema(&self) -> Value {
+        let variant = |scheme: &PluginScheme, title: &str| {
+            json!({
+                "type": "string",
+                "title": title,
+                "enum": scheme.variants,
+                "default": scheme.variants[0]
+            })
+        };
+
         json!({
             "type": "object",
+            "x-order": ["mode", "generated", "plugin", "raw_config"],
             "properties": {
+                "mode": {
+                    "type": "string",
+                    "title": "Mode",
+                    "description": "raw uses the Lua below, generated builds a colorscheme from the terminal palette, plugin picks a colorscheme plugin",
+                    "enum": ["raw", "generated", "plugin"],
+                    "default": "raw"
+                },
+                "generated": {
+                    "type": "object",
+                    "title": "Generated Colorscheme",
+                    "x-order": ["name", "transparent"],
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "title": "Colorscheme Name",
+                            "default": "omarchy"
+                        },
+                        "transparent": {
+                            "type": "boolean",
+                            "title": "Transparent Background",
+                            "default": false
+                        }
+                    }
+                },
+                "plugin": {
+                    "type": "object",
+                    "title": "Colorscheme Plugin",
+                    "x-order": [
+                        "colorscheme",
+                        "tokyonight_style",
+                        "catppuccin_flavour",
+                        "gruvbox_contrast",
+                        "transparent"
+                    ],
+                    "properties": {
+                        "colorscheme": {
+                            "type": "string",
+                            "title": "Colorscheme",
+                            "enum": PLUGIN_SCHEMES.iter().map(|s| s.name).collect::<Vec<_>>(),
+                            "default": "tokyonight"
+                        },
+                        "tokyonight_style": variant(&PLUGIN_SCHEMES[0], "Tokyo Night Style"),
+                        "catppuccin_flavour": variant(&PLUGIN_SCHEMES[1], "Catppuccin Flavour"),
+                        "gruvbox_contrast": variant(&PLUGIN_SCHEMES[2], "Gruvbox Contrast"),
+                        "transparent": {
+                            "type": "boolean",
+                            "title": "Transparent Background",
+                            "default": false
+                        }
+                    }
+                },
                 "raw_config": {
                     "type": "string",
                     "title": "Neovim Configuration",
@@ -56,6 +457,42 @@ impl ConfigGenerator for NeovimGenerator {
     }
 
     fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        // A colorscheme generated by Omarchist
+        let colors_name =
+            Regex::new(r#"vim\.g\.colors_name = "([A-Za-z0-9_-]+)""#).map_err(|e| e.to_string())?;
+        if let Some(captures) = colors_name.captures(content) {
+            return Ok(json!({
+                "mode": "generated",
+                "generated": {
+                    "name": &captures[1],
+                    "transparent": content.contains("local transparent = true")
+                }
+            }));
+        }
+
+        // A spec for one of the known colorscheme plugins
+        if let Some(scheme) = PLUGIN_SCHEMES
+            .iter()
+            .find(|s| content.contains(&format!("\"{}\"", s.repo)))
+        {
+            let option = |key: &str| {
+                Regex::new(&format!(r#"\b{key}\s*=\s*"?([A-Za-z0-9_-]*)"?"#))
+                    .ok()
+                    .and_then(|re| re.captures(content))
+                    .map(|c| c[1].to_string())
+            };
+            let variant = match option(scheme.variant_opt) {
+                Some(v) if scheme.variants.contains(&v.as_str()) => v,
+                _ => scheme.variants[0].to_string(),
+            };
+            let mut plugin = json!({
+                "colorscheme": scheme.name,
+                "transparent": option(scheme.transparent_opt).as_deref() == Some("true")
+            });
+            plugin[scheme.variant_field] = json!(variant);
+            return Ok(json!({ "mode": "plugin", "plugin": plugin }));
+        }
+
         // Extract the raw config by removing the header comment if present
         let lines: Vec<&str> = content.lines().collect();
         let mut start_index = 0;
@@ -78,3 +515,90 @@ impl ConfigGenerator for NeovimGenerator {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_mode_keeps_existing_behavior() {
+        let config = NeovimGenerator.generate_config(&json!({})).unwrap();
+        assert_eq!(config, DEFAULT_SPEC);
+
+        let theme_data = json!({ "neovim": { "raw_config": "return {}" } });
+        let config = NeovimGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.ends_with("\nreturn {}"));
+        let parsed = NeovimGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["raw_config"], "return {}");
+    }
+
+    #[test]
+    fn test_generated_colorscheme_uses_palette() {
+        let theme_data = json!({
+            "alacritty": { "colors": {
+                "primary": { "background": "#1a1b26" },
+                "normal": { "blue": "#7aa2f7" }
+            }},
+            "neovim": { "mode": "generated", "generated": { "name": "night", "transparent": true } }
+        });
+        let config = NeovimGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.contains("  bg = \"#1a1b26\",\n"));
+        assert!(config.contains("  blue = \"#7aa2f7\",\n"));
+        assert!(config.contains("  hl(\"DiagnosticError\", { fg = p.red })\n"));
+        assert!(config.contains("  hl(\"@keyword.return\", "));
+        assert!(config.contains("  hl(\"TelescopeSelection\", "));
+        assert!(config.contains("vim.g.terminal_color_15 = "));
+        assert!(config.contains("opts.options.theme = lualine_theme"));
+        assert_eq!(config.matches('{').count(), config.matches('}').count());
+
+        let parsed = NeovimGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(
+            parsed,
+            json!({ "mode": "generated", "generated": { "name": "night", "transparent": true } })
+        );
+    }
+
+    #[test]
+    fn test_generated_rejects_invalid_name() {
+        let theme_data =
+            json!({ "neovim": { "mode": "generated", "generated": { "name": "a\"b" } } });
+        assert!(NeovimGenerator.generate_config(&theme_data).is_err());
+    }
+
+    #[test]
+    fn test_plugin_specs_round_trip() {
+        for (plugin, colorscheme) in [
+            (
+                json!({ "colorscheme": "tokyonight", "tokyonight_style": "storm" }),
+                "tokyonight-storm",
+            ),
+            (
+                json!({ "colorscheme": "catppuccin", "catppuccin_flavour": "latte", "transparent": true }),
+                "catppuccin-latte",
+            ),
+            (
+                json!({ "colorscheme": "gruvbox", "gruvbox_contrast": "hard" }),
+                "gruvbox",
+            ),
+        ] {
+            let theme_data = json!({ "neovim": { "mode": "plugin", "plugin": plugin.clone() } });
+            let config = NeovimGenerator.generate_config(&theme_data).unwrap();
+            assert!(config.contains(&format!("colorscheme = \"{colorscheme}\"")));
+
+            let parsed = NeovimGenerator.parse_existing_config(&config).unwrap();
+            assert_eq!(parsed["mode"], "plugin");
+            for (key, value) in plugin.as_object().unwrap() {
+                assert_eq!(&parsed["plugin"][key], value, "{config}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_plugin_rejects_unknown_variant() {
+        let theme_data = json!({ "neovim": { "mode": "plugin", "plugin": {
+            "colorscheme": "tokyonight",
+            "tokyonight_style": "mocha"
+        }}});
+        assert!(NeovimGenerator.generate_config(&theme_data).is_err());
+    }
+}
@@ -0,0 +1,430 @@
+use super::{validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::schema_validation::{deep_merge, default_for};
+use crate::services::settings::app_settings;
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const SCHEMA_FILE: &str = "schema.json";
+const TEMPLATE_EXTENSIONS: [&str; 2] = ["j2", "jinja"];
+/// Theme metadata, manifest, light-mode marker and directories Omarchist manages itself;
+/// no plugin may write over them
+const RESERVED_FILE_NAMES: [&str; 6] = [
+    "custom_theme.json",
+    "theme.toml",
+    "light.mode",
+    "backgrounds",
+    "variants",
+    "chromium-theme",
+];
+
+static INSTALLED_PLUGINS: OnceLock<Vec<TemplateGenerator>> = OnceLock::new();
+
+/// A generator defined by a directory under `omarchist/generators/<app>/` holding a
+/// `schema.json` and a single minijinja template such as `kitty.conf.j2`.
+///
+/// The template is rendered with `app` (the app's data merged over schema defaults) and
+/// `theme` (the full theme data). The output file name is the template name without its
+/// template extension. The schema may set `x-validate` to one of `toml`, `css`, `ini`,
+/// `btop` or `braces` to check the rendered output before it is written.
+#[derive(Debug, Clone)]
+pub struct TemplateGenerator {
+    app_name: String,
+    file_name: String,
+    schema: Value,
+    template: String,
+    validate: Option<String>,
+}
+
+impl TemplateGenerator {
+    /// Load a plugin from its directory. The directory name becomes the app name.
+    pub fn from_dir(dir: &Path) -> Result<Self, String> {
+        let app_name = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("Invalid plugin directory: {}", dir.display()))?
+            .to_string();
+
+        if app_name.is_empty()
+            || !app_name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "Invalid plugin name '{app_name}': use lowercase letters, digits, '-' or '_'"
+            ));
+        }
+
+        let schema_path = dir.join(SCHEMA_FILE);
+        let schema_content = fs::read_to_string(&schema_path)
+            .map_err(|e| format!("Failed to read {}: {e}", schema_path.display()))?;
+        let schema: Value = serde_json::from_str(&schema_content)
+            .map_err(|e| format!("Failed to parse {}: {e}", schema_path.display()))?;
+
+        if schema.get("type").and_then(|t| t.as_str()) != Some("object") {
+            return Err(format!(
+                "{} must describe an object schema",
+                schema_path.display()
+            ));
+        }
+
+        let validate = schema
+            .get("x-validate")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        if let Some(kind) = &validate {
+            if !matches!(kind.as_str(), "toml" | "css" | "ini" | "btop" | "braces") {
+                return Err(format!("Unknown x-validate kind '{kind}' for '{app_name}'"));
+            }
+        }
+
+        let (template_path, file_name) = Self::find_template(dir)?;
+        if RESERVED_FILE_NAMES.contains(&file_name.as_str()) {
+            return Err(format!(
+                "Plugin '{app_name}' cannot write {file_name}: the name is reserved"
+            ));
+        }
+        let template = fs::read_to_string(&template_path)
+            .map_err(|e| format!("Failed to read {}: {e}", template_path.display()))?;
+
+        // Compile once so syntax errors are reported at load time instead of on save
+        Self::environment()
+            .template_from_str(&template)
+            .map_err(|e| format!("Invalid template {}: {e}", template_path.display()))?;
+
+        Ok(Self {
+            app_name,
+            file_name,
+            schema,
+            template,
+            validate,
+        })
+    }
+
+    fn find_template(dir: &Path) -> Result<(PathBuf, String), String> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read plugin directory {}: {e}", dir.display()))?;
+
+        let mut templates = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let is_template = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| TEMPLATE_EXTENSIONS.contains(&ext));
+            let file_name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_string);
+            if let (true, Some(file_name)) = (is_template, file_name) {
+                templates.push((path, file_name));
+            }
+        }
+
+        match templates.len() {
+            1 => Ok(templates.remove(0)),
+            0 => Err(format!(
+                "No .j2 or .jinja template found in {}",
+                dir.display()
+            )),
+            _ => Err(format!("Multiple templates found in {}", dir.display())),
+        }
+    }
+
+    fn environment() -> Environment<'static> {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.set_keep_trailing_newline(true);
+        env.add_filter("strip_hash", |value: String| {
+            value.trim_start_matches('#').to_string()
+        });
+        env
+    }
+
+    /// Merge the app's data over the schema defaults so templates can rely on every
+    /// property that declares a default
+    fn app_context(&self, theme_data: &Value) -> Value {
+        let mut app = default_for(&self.schema).unwrap_or_else(|| json!({}));
+        if let Some(data) = theme_data.get(&self.app_name) {
+            deep_merge(&mut app, data);
+        }
+        app
+    }
+}
+
+impl ConfigGenerator for TemplateGenerator {
+    fn get_app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    fn get_file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let context = json!({
+            "app": self.app_context(theme_data),
+            "theme": theme_data,
+        });
+
+        Self::environment()
+            .render_str(&self.template, context)
+            .map_err(|e| format!("Failed to render {} template: {e}", self.app_name))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        self.schema.clone()
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Err(format!(
+            "Parsing existing {} files is not supported for template generators",
+            self.file_name
+        ))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        match self.validate.as_deref() {
+            Some("toml") => validation::validate_toml(content),
+            Some("css") => validation::validate_css(content),
+            Some("ini") => validation::validate_ini(content),
+            Some("btop") => validation::validate_btop_theme(content),
+            Some("braces") => validation::validate_brace_balance(content),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Directory scanned for plugin generators: `$XDG_CONFIG_HOME/omarchist/generators`
+pub fn plugin_dir() -> Option<PathBuf> {
+    app_settings::app_config_dir().map(|dir| dir.join("generators"))
+}
+
+/// Load every valid plugin in `dir`, sorted by app name. Broken plugins are logged and skipped.
+pub fn load_plugins(dir: &Path) -> Vec<TemplateGenerator> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<TemplateGenerator> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| match TemplateGenerator::from_dir(&path) {
+            Ok(plugin) => Some(plugin),
+            Err(e) => {
+                log::warn!("Skipping generator plugin {}: {e}", path.display());
+                None
+            },
+        })
+        .collect();
+
+    plugins.sort_by(|a, b| a.app_name.cmp(&b.app_name));
+    plugins
+}
+
+/// Plugins from the user's config dir, loaded once per process
+pub fn installed_plugins() -> &'static [TemplateGenerator] {
+    INSTALLED_PLUGINS.get_or_init(|| {
+        let plugins = plugin_dir()
+            .map(|dir| load_plugins(&dir))
+            .unwrap_or_default();
+        if !plugins.is_empty() {
+            log::info!("Loaded {} generator plugin(s)", plugins.len());
+        }
+        plugins
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::generators::ConfigGeneratorRegistry;
+    use tempfile::TempDir;
+
+    fn write_plugin(root: &Path, name: &str, schema: &Value, template_name: &str, body: &str) {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(SCHEMA_FILE), schema.to_string()).unwrap();
+        fs::write(dir.join(template_name), body).unwrap();
+    }
+
+    fn kitty_schema() -> Value {
+        json!({
+            "type": "object",
+            "x-validate": "ini",
+            "properties": {
+                "colors": {
+                    "type": "object",
+                    "properties": {
+                        "background": { "type": "string", "format": "color", "default": "#121212" },
+                        "foreground": { "type": "string", "format": "color", "default": "#bebebe" }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_plugin_renders_with_defaults_and_data() {
+        let root = TempDir::new().unwrap();
+        write_plugin(
+            root.path(),
+            "kitty",
+            &kitty_schema(),
+            "kitty.conf.j2",
+            "background {{ app.colors.background }}\nforeground {{ app.colors.foreground | strip_hash }}\n",
+        );
+
+        let plugins = load_plugins(root.path());
+        assert_eq!(plugins.len(), 1);
+        let plugin = &plugins[0];
+        assert_eq!(plugin.get_app_name(), "kitty");
+        assert_eq!(plugin.get_file_name(), "kitty.conf");
+
+        let output = plugin
+            .generate_config(&json!({ "kitty": { "colors": { "background": "#000000" } } }))
+            .unwrap();
+        assert_eq!(output, "background #000000\nforeground bebebe\n");
+    }
+
+    #[test]
+    fn test_plugin_missing_value_is_an_error() {
+        let root = TempDir::new().unwrap();
+        write_plugin(
+            root.path(),
+            "foot",
+            &json!({ "type": "object", "properties": { "alpha": { "type": "number" } } }),
+            "foot.ini.jinja",
+            "alpha={{ app.alpha }}\n",
+        );
+
+        let plugin = &load_plugins(root.path())[0];
+        assert!(plugin.generate_config(&json!({})).is_err());
+        assert_eq!(
+            plugin
+                .generate_config(&json!({ "foot": { "alpha": 0.9 } }))
+                .unwrap(),
+            "alpha=0.9\n"
+        );
+    }
+
+    #[test]
+    fn test_broken_plugins_are_skipped() {
+        let root = TempDir::new().unwrap();
+        write_plugin(
+            root.path(),
+            "syntax",
+            &kitty_schema(),
+            "a.conf.j2",
+            "{{ app.colors ",
+        );
+        write_plugin(root.path(), "Bad Name", &kitty_schema(), "a.conf.j2", "ok");
+        write_plugin(root.path(), "notemplate", &kitty_schema(), "a.conf", "ok");
+        write_plugin(
+            root.path(),
+            "fuzzel",
+            &kitty_schema(),
+            "fuzzel.ini.j2",
+            "ok",
+        );
+
+        let plugins = load_plugins(root.path());
+        let names: Vec<&str> = plugins.iter().map(|p| p.get_app_name()).collect();
+        assert_eq!(names, vec!["fuzzel"]);
+    }
+
+    #[test]
+    fn test_plugin_output_validation() {
+        let root = TempDir::new().unwrap();
+        write_plugin(
+            root.path(),
+            "kitty",
+            &kitty_schema(),
+            "kitty.conf.j2",
+            "[colors]\nbackground-color={{ app.colors.background }}\n",
+        );
+        let plugin = &load_plugins(root.path())[0];
+
+        let output = plugin
+            .generate_config(&json!({ "kitty": { "colors": { "background": "red" } } }))
+            .unwrap();
+        assert!(plugin.validate_output(&output).is_err());
+    }
+
+    #[test]
+    fn test_registry_keeps_builtins_over_plugins() {
+        let root = TempDir::new().unwrap();
+        write_plugin(
+            root.path(),
+            "waybar",
+            &kitty_schema(),
+            "waybar.css.j2",
+            "plugin",
+        );
+        write_plugin(
+            root.path(),
+            "fuzzel",
+            &kitty_schema(),
+            "fuzzel.ini.j2",
+            "plugin",
+        );
+
+        let mut registry = ConfigGeneratorRegistry::builtin();
+        registry.register_plugins(load_plugins(root.path()));
+
+        assert_eq!(
+            registry.get_generator("waybar").unwrap().get_file_name(),
+            "waybar.css"
+        );
+        assert!(registry.get_generator("fuzzel").is_some());
+        assert_eq!(registry.get_schema_for_app("fuzzel"), Some(kitty_schema()));
+    }
+
+    #[test]
+    fn test_plugins_cannot_write_reserved_files() {
+        let root = TempDir::new().unwrap();
+        for (name, template) in [
+            ("meta", "custom_theme.json.j2"),
+            ("manifest", "theme.toml.jinja"),
+            ("mode", "light.mode.j2"),
+            ("walls", "backgrounds.j2"),
+            ("chrome", "chromium-theme.j2"),
+        ] {
+            write_plugin(root.path(), name, &kitty_schema(), template, "plugin");
+        }
+        write_plugin(
+            root.path(),
+            "fuzzel",
+            &kitty_schema(),
+            "fuzzel.ini.j2",
+            "plugin",
+        );
+
+        let plugins = load_plugins(root.path());
+        let names: Vec<&str> = plugins.iter().map(|p| p.get_app_name()).collect();
+        assert_eq!(names, vec!["fuzzel"]);
+    }
+
+    #[test]
+    fn test_registry_rejects_plugins_writing_builtin_files() {
+        let root = TempDir::new().unwrap();
+        write_plugin(
+            root.path(),
+            "alacritty2",
+            &kitty_schema(),
+            "alacritty.toml.j2",
+            "plugin",
+        );
+
+        let mut registry = ConfigGeneratorRegistry::builtin();
+        registry.register_plugins(load_plugins(root.path()));
+
+        assert!(registry.get_generator("alacritty2").is_none());
+    }
+}
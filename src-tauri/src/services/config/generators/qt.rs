@@ -0,0 +1,201 @@
+use super::gtk::DesktopPalette;
+use super::{validation, ConfigGenerator, OutputValidationError};
+use serde_json::{json, Value};
+
+/// qt6ct color scheme (`~/.config/qt6ct/colors/*.conf`)
+pub struct Qt6ctGenerator;
+
+unsafe impl Send for Qt6ctGenerator {}
+unsafe impl Sync for Qt6ctGenerator {}
+
+/// Kvantum theme config (`~/.config/Kvantum/<Theme>/<Theme>.kvconfig`)
+pub struct KvantumGenerator;
+
+unsafe impl Send for KvantumGenerator {}
+unsafe impl Sync for KvantumGenerator {}
+
+/// Both Qt generators derive everything from the GTK colors, so they have nothing to edit
+fn derived_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "Generated from the GTK colors",
+        "properties": {}
+    })
+}
+
+/// QPalette roles in the order qt6ct expects them
+fn qt_palette_roles(palette: &DesktopPalette, disabled: bool) -> Vec<&str> {
+    let (window_text, view_text) = if disabled {
+        (&palette.muted_fg, &palette.muted_fg)
+    } else {
+        (&palette.window_fg, &palette.view_fg)
+    };
+
+    vec![
+        window_text,         // WindowText
+        &palette.card_bg,    // Button
+        &palette.popover_bg, // Light
+        &palette.card_bg,    // Midlight
+        &palette.window_bg,  // Dark
+        &palette.card_bg,    // Mid
+        view_text,           // Text
+        "#ffffff",           // BrightText
+        window_text,         // ButtonText
+        &palette.view_bg,    // Base
+        &palette.window_bg,  // Window
+        "#000000",           // Shadow
+        &palette.accent_bg,  // Highlight
+        &palette.accent_fg,  // HighlightedText
+        &palette.accent_bg,  // Link
+        &palette.accent_bg,  // LinkVisited
+        &palette.card_bg,    // AlternateBase
+        &palette.window_bg,  // NoRole
+        &palette.popover_bg, // ToolTipBase
+        &palette.window_fg,  // ToolTipText
+        &palette.muted_fg,   // PlaceholderText
+    ]
+}
+
+impl ConfigGenerator for Qt6ctGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "qt6ct"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "qt6ct.conf"
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let palette = DesktopPalette::resolve(theme_data);
+        let active = qt_palette_roles(&palette, false).join(", ");
+        let disabled = qt_palette_roles(&palette, true).join(", ");
+
+        Ok(format!(
+            r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for qt6ct
+# Generated with Omarchist
+# ────────────────────────────────────────────────────────────
+
+[ColorScheme]
+active_colors={active}
+disabled_colors={disabled}
+inactive_colors={active}
+"#
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        derived_schema()
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Ok(json!({}))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_ini(content)
+    }
+}
+
+impl ConfigGenerator for KvantumGenerator {
+    fn get_app_name(&self) -> &'static str {
+        "kvantum"
+    }
+
+    fn get_file_name(&self) -> &'static str {
+        "kvantum.kvconfig"
+    }
+
+    fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
+        let DesktopPalette {
+            accent_bg,
+            accent_fg,
+            window_bg,
+            window_fg,
+            view_bg,
+            view_fg,
+            card_bg,
+            popover_bg,
+            muted_fg,
+            ..
+        } = DesktopPalette::resolve(theme_data);
+
+        Ok(format!(
+            r#"# ────────────────────────────────────────────────────────────
+# Omarchy Custom Theme for Kvantum
+# Generated with Omarchist
+# ────────────────────────────────────────────────────────────
+
+[%General]
+author=Omarchist
+comment=Generated from the Omarchy theme palette
+composite=true
+translucent_windows=false
+
+[GeneralColors]
+window.color={window_bg}
+base.color={view_bg}
+alt.base.color={card_bg}
+button.color={card_bg}
+light.color={popover_bg}
+mid.light.color={card_bg}
+dark.color={window_bg}
+mid.color={card_bg}
+highlight.color={accent_bg}
+inactive.highlight.color={accent_bg}
+text.color={view_fg}
+window.text.color={window_fg}
+button.text.color={window_fg}
+disabled.text.color={muted_fg}
+tooltip.text.color={window_fg}
+highlight.text.color={accent_fg}
+link.color={accent_bg}
+link.visited.color={accent_bg}
+progress.indicator.text.color={accent_fg}
+"#
+        ))
+    }
+
+    fn get_config_schema(&self) -> Value {
+        derived_schema()
+    }
+
+    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
+        Ok(json!({}))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_ini(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qt6ct_has_all_palette_roles() {
+        let config = Qt6ctGenerator.generate_config(&json!({})).unwrap();
+        let active = config
+            .lines()
+            .find_map(|l| l.strip_prefix("active_colors="))
+            .unwrap();
+        assert_eq!(active.split(", ").count(), 21);
+        assert!(active.starts_with("#bebebe, "));
+
+        let disabled = config
+            .lines()
+            .find_map(|l| l.strip_prefix("disabled_colors="))
+            .unwrap();
+        assert!(disabled.starts_with("#8a8a8d, "));
+    }
+
+    #[test]
+    fn test_kvantum_uses_gtk_colors() {
+        let theme_data = json!({ "gtk": { "colors": { "accent_bg_color": "#ff0000" } } });
+        let config = KvantumGenerator.generate_config(&theme_data).unwrap();
+        assert!(config.contains("highlight.color=#ff0000\n"));
+        assert!(config.contains("highlight.text.color=#000000\n"));
+        assert!(KvantumGenerator.validate_output(&config).is_ok());
+    }
+}
@@ -1,11 +1,48 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::gtk_css::{self, GtkCss};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::schema_validation::default_for;
+use serde_json::{json, Map, Value};
 
 pub struct SwayosdGenerator;
 
 unsafe impl Send for SwayosdGenerator {}
 unsafe impl Sync for SwayosdGenerator {}
 
+/// (schema key, `@define-color` name) of the colors Omarchy's SwayOSD style reads
+const COLORS: [(&str, &str); 5] = [
+    ("background_color", "background-color"),
+    ("border_color", "border-color"),
+    ("label", "label"),
+    ("image", "image"),
+    ("progress", "progress"),
+];
+
+const TROUGH_SELECTOR: &str = "progressbar trough";
+const PROGRESS_SELECTOR: &str = "progressbar progress";
+
+/// Pixel sizes from `style`; 0 leaves SwayOSD's own value
+fn px(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(|v| v.as_f64())
+        .filter(|v| *v > 0.0)
+        .map(|v| format!("{v}px"))
+}
+
+fn parse_px(value: &str) -> Option<Value> {
+    value
+        .strip_suffix("px")?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|v| {
+            if v.fract() == 0.0 {
+                json!(v as u64)
+            } else {
+                json!(v)
+            }
+        })
+}
+
 impl ConfigGenerator for SwayosdGenerator {
     fn get_app_name(&self) -> &'static str {
         "swayosd"
@@ -18,52 +55,68 @@ impl ConfigGenerator for SwayosdGenerator {
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let swayosd = theme_data.get("swayosd").unwrap_or(&empty_obj);
+        let defaults = default_for(&self.get_config_schema()).unwrap_or_else(|| json!({}));
+        let lookup = |path: &[&str]| -> Option<Value> {
+            [swayosd, &defaults].iter().find_map(|root| {
+                path.iter()
+                    .try_fold(*root, |v, key| v.get(key))
+                    .filter(|v| !v.is_null())
+                    .cloned()
+            })
+        };
+        let get = |path: &[&str]| -> String {
+            lookup(path)
+                .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+                .unwrap_or_default()
+        };
+
+        let mut colors: Vec<(&str, String)> = COLORS
+            .iter()
+            .map(|(key, name)| (*name, get(&["colors", key])))
+            .collect();
+
+        let mut trough = Vec::new();
+        let trough_color = get(&["colors", "trough"]);
+        if !trough_color.is_empty() {
+            colors.push(("trough", trough_color));
+            trough.push(("background-color", "@trough".to_string()));
+        }
+        let mut progress = Vec::new();
+        if let Some(height) = px(lookup(&["style", "progress_height"]).as_ref()) {
+            trough.push(("min-height", height.clone()));
+            progress.push(("min-height", height));
+        }
+        if let Some(radius) = px(lookup(&["style", "trough_radius"]).as_ref()) {
+            trough.push(("border-radius", radius));
+        }
+        if let Some(radius) = px(lookup(&["style", "progress_radius"]).as_ref()) {
+            progress.push(("border-radius", radius));
+        }
 
-        // Extract color values with defaults from template
-        let colors = swayosd.get("colors").unwrap_or(&empty_obj);
-        let background_color = colors
-            .get("background_color")
-            .and_then(|bg| bg.as_str())
-            .unwrap_or("#121212");
-        let border_color = colors
-            .get("border_color")
-            .and_then(|bc| bc.as_str())
-            .unwrap_or("#8A8A8D");
-        let label = colors
-            .get("label")
-            .and_then(|l| l.as_str())
-            .unwrap_or("#8A8A8D");
-        let image = colors
-            .get("image")
-            .and_then(|i| i.as_str())
-            .unwrap_or("#8A8A8D");
-        let progress = colors
-            .get("progress")
-            .and_then(|p| p.as_str())
-            .unwrap_or("#8A8A8D");
-
-        Ok(format!(
-            r#"/* ────────────────────────────────────────────────────────────
- * Omarchy Custom Theme for SwayOSD
- * Generated with Omarchist
- * ────────────────────────────────────────────────────────────
- */
-
-@define-color background-color {background_color};
-@define-color border-color {border_color};
-@define-color label {label};
-@define-color image {image};
-@define-color progress {progress};
-"#
+        let mut rules = Vec::new();
+        if !trough.is_empty() {
+            rules.push(gtk_css::rule(TROUGH_SELECTOR, &trough));
+        }
+        if !progress.is_empty() {
+            rules.push(gtk_css::rule(PROGRESS_SELECTOR, &progress));
+        }
+
+        Ok(gtk_css::render(
+            "SwayOSD",
+            &colors,
+            &rules,
+            &get(&["custom_css"]),
         ))
     }
 
     fn get_config_schema(&self) -> Value {
         json!({
                 "type": "object",
+                "x-order": ["colors", "style", "custom_css"],
                 "properties": {
                     "colors": {
                         "type": "object",
+                        "x-order": ["background_color", "border_color", "label", "image", "progress", "trough"],
                         "properties": {
                             "background_color": {
                                 "type": "string",
@@ -99,15 +152,175 @@ impl ConfigGenerator for SwayosdGenerator {
                                 "default": "#8A8A8D",
                                 "title": "Progress Color",
                                 "description": "Color of progress bars"
+                            },
+                            "trough": {
+                                "type": "string",
+                                "format": "color",
+                                "default": "",
+                                "title": "Trough Color",
+                                "description": "Unfilled part of progress bars; empty keeps SwayOSD's default"
                             }
                      }
-                }
+                },
+                "style": {
+                    "type": "object",
+                    "title": "Progress Bar",
+                    "x-order": ["progress_height", "trough_radius", "progress_radius"],
+                    "properties": {
+                        "progress_height": {
+                            "type": "number",
+                            "title": "Height",
+                            "description": "Bar height in pixels; 0 keeps SwayOSD's default",
+                            "minimum": 0,
+                            "default": 0
+                        },
+                        "trough_radius": {
+                            "type": "number",
+                            "title": "Trough Radius",
+                            "description": "Corner radius of the trough in pixels; 0 keeps SwayOSD's default",
+                            "minimum": 0,
+                            "default": 0
+                        },
+                        "progress_radius": {
+                            "type": "number",
+                            "title": "Slider Radius",
+                            "description": "Corner radius of the filled part in pixels; 0 keeps SwayOSD's default",
+                            "minimum": 0,
+                            "default": 0
+                        }
+                    }
+                },
+                "custom_css": gtk_css::custom_css_schema()
             }
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement CSS parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let mut css = GtkCss::parse(content)?;
+
+        let mut colors = Map::new();
+        for (key, name) in COLORS.iter().chain(&[("trough", "trough")]) {
+            if let Some(value) = css.color(name) {
+                colors.insert(key.to_string(), json!(value));
+            }
+        }
+
+        // Only rules made entirely of properties we write are taken over
+        let mut style = Map::new();
+        let owned = |decls: &[(String, String)], allowed: &[&str]| {
+            decls.iter().all(|(p, v)| {
+                allowed.contains(&p.as_str())
+                    && (p.starts_with("background") || parse_px(v).is_some())
+            })
+        };
+        if let Some(decls) = css
+            .rule(TROUGH_SELECTOR)
+            .filter(|d| owned(d, &["background-color", "min-height", "border-radius"]))
+            .filter(|d| {
+                d.iter()
+                    .all(|(p, v)| p != "background-color" || v == "@trough")
+            })
+        {
+            css.take_rule(TROUGH_SELECTOR);
+            for (property, value) in decls {
+                let key = match property.as_str() {
+                    "min-height" => "progress_height",
+                    "border-radius" => "trough_radius",
+                    _ => continue,
+                };
+                style.insert(key.into(), parse_px(&value).unwrap_or_default());
+            }
+        }
+        if let Some(decls) = css
+            .rule(PROGRESS_SELECTOR)
+            .filter(|d| owned(d, &["min-height", "border-radius"]))
+        {
+            css.take_rule(PROGRESS_SELECTOR);
+            for (property, value) in decls {
+                let key = if property == "min-height" {
+                    "progress_height"
+                } else {
+                    "progress_radius"
+                };
+                style.insert(key.into(), parse_px(&value).unwrap_or_default());
+            }
+        }
+
+        let mut result = Map::new();
+        if !colors.is_empty() {
+            result.insert("colors".into(), Value::Object(colors));
+        }
+        if !style.is_empty() {
+            result.insert("style".into(), Value::Object(style));
+        }
+        let owned: Vec<&str> = COLORS
+            .iter()
+            .map(|(_, name)| *name)
+            .chain(["trough"])
+            .collect();
+        let custom_css = css.custom_css(&owned);
+        if !custom_css.is_empty() {
+            result.insert("custom_css".into(), json!(custom_css));
+        }
+        Ok(Value::Object(result))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_css(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_omarchy_template() {
+        let config = SwayosdGenerator.generate_config(&json!({})).unwrap();
+        assert!(config.contains("@define-color background-color #121212;"));
+        assert!(config.contains("@define-color progress #8A8A8D;"));
+        assert!(!config.contains("trough"));
+        assert!(!config.contains('{'));
+        assert!(SwayosdGenerator.validate_output(&config).is_ok());
+    }
+
+    #[test]
+    fn test_progress_styling_round_trip() {
+        let theme = json!({
+            "swayosd": {
+                "colors": { "progress": "#7aa2f7", "trough": "#24283b" },
+                "style": { "progress_height": 8, "progress_radius": 4 },
+                "custom_css": "label {\n  font-weight: bold;\n}"
+            }
+        });
+        let config = SwayosdGenerator.generate_config(&theme).unwrap();
+        assert!(config
+            .contains("progressbar trough {\n  background-color: @trough;\n  min-height: 8px;\n}"));
+        assert!(
+            config.contains("progressbar progress {\n  min-height: 8px;\n  border-radius: 4px;\n}")
+        );
+        assert!(SwayosdGenerator.validate_output(&config).is_ok());
+
+        let parsed = SwayosdGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["progress"], "#7aa2f7");
+        assert_eq!(parsed["colors"]["trough"], "#24283b");
+        assert_eq!(
+            parsed["style"],
+            json!({ "progress_height": 8, "progress_radius": 4 })
+        );
+        assert_eq!(parsed["custom_css"], "label {\n  font-weight: bold;\n}");
+    }
+
+    #[test]
+    fn test_parse_keeps_foreign_progress_rules() {
+        let existing = "@define-color label #ffffff;\n\
+                        progressbar trough {\n  background-color: #000000;\n}\n";
+        let parsed = SwayosdGenerator.parse_existing_config(existing).unwrap();
+        assert_eq!(parsed["colors"], json!({ "label": "#ffffff" }));
+        assert!(parsed.get("style").is_none());
+        assert_eq!(
+            parsed["custom_css"],
+            "progressbar trough {\n  background-color: #000000;\n}"
+        );
     }
-}
\ No newline at end of file
+}
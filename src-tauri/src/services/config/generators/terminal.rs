@@ -0,0 +1,262 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// ANSI color names in palette order (color0..color7)
+pub const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+const DEFAULT_NORMAL: [&str; 8] = [
+    "#333333", "#D35F5F", "#FFC107", "#b91c1c", "#e68e0d", "#D35F5F", "#bebebe", "#bebebe",
+];
+const DEFAULT_BRIGHT: [&str; 8] = [
+    "#8a8a8d", "#B91C1C", "#FFC107", "#b90a0a", "#f59e0b", "#b91c1c", "#eaeaea", "#eaeaea",
+];
+
+/// Terminal colors shared by the Alacritty, Kitty, Foot and Ghostty generators.
+///
+/// Each value is looked up in the app's own `colors` data first, then in the Alacritty
+/// `colors` data, so a theme only has to define its palette once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalPalette {
+    pub background: String,
+    pub foreground: String,
+    pub dim_foreground: String,
+    pub cursor_text: String,
+    pub cursor: String,
+    pub selection_background: String,
+    pub normal: [String; 8],
+    pub bright: [String; 8],
+}
+
+impl TerminalPalette {
+    pub fn resolve(theme_data: &Value, app_name: &str) -> Self {
+        let lookup = |section: &str, key: &str, default: &str| -> String {
+            [app_name, "alacritty"]
+                .iter()
+                .filter_map(|app| {
+                    theme_data
+                        .get(*app)
+                        .and_then(|a| a.get("colors"))
+                        .and_then(|c| c.get(section))
+                        .and_then(|s| s.get(key))
+                        .and_then(|v| v.as_str())
+                })
+                .find(|v| !v.trim().is_empty())
+                .unwrap_or(default)
+                .to_string()
+        };
+
+        Self {
+            background: lookup("primary", "background", "#121212"),
+            foreground: lookup("primary", "foreground", "#bebebe"),
+            dim_foreground: lookup("primary", "dim_foreground", "#8a8a8d"),
+            cursor_text: lookup("cursor", "text", "#121212"),
+            cursor: lookup("cursor", "cursor", "#eaeaea"),
+            selection_background: lookup("selection", "background", "#333333"),
+            normal: std::array::from_fn(|i| lookup("normal", ANSI_NAMES[i], DEFAULT_NORMAL[i])),
+            bright: std::array::from_fn(|i| lookup("bright", ANSI_NAMES[i], DEFAULT_BRIGHT[i])),
+        }
+    }
+
+    /// The 16-color palette, normal colors first
+    pub fn ansi(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.normal
+            .iter()
+            .chain(self.bright.iter())
+            .map(String::as_str)
+            .enumerate()
+    }
+}
+
+/// Colors read back from a terminal config file, keyed the same way as theme data
+#[derive(Debug, Default)]
+pub struct ParsedPalette {
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub cursor_text: Option<String>,
+    pub cursor: Option<String>,
+    pub selection_background: Option<String>,
+    ansi: BTreeMap<usize, String>,
+}
+
+impl ParsedPalette {
+    /// Record palette entry `index` (0-15). Bare `RRGGBB` values get a leading `#`.
+    pub fn set_ansi(&mut self, index: usize, value: &str) {
+        if index < 16 {
+            self.ansi.insert(index, with_hash(value));
+        }
+    }
+
+    /// Convert to the `{ "colors": { ... } }` shape used by the terminal schemas
+    pub fn into_theme_data(self) -> Value {
+        let mut colors = Map::new();
+        let mut section = |name: &str, entries: Vec<(&str, Option<String>)>| {
+            let values: Map<String, Value> = entries
+                .into_iter()
+                .filter_map(|(key, value)| value.map(|v| (key.to_string(), json!(with_hash(&v)))))
+                .collect();
+            if !values.is_empty() {
+                colors.insert(name.to_string(), Value::Object(values));
+            }
+        };
+
+        section(
+            "primary",
+            vec![
+                ("background", self.background),
+                ("foreground", self.foreground),
+            ],
+        );
+        section(
+            "cursor",
+            vec![("text", self.cursor_text), ("cursor", self.cursor)],
+        );
+        section("selection", vec![("background", self.selection_background)]);
+        for (name, offset) in [("normal", 0), ("bright", 8)] {
+            section(
+                name,
+                ANSI_NAMES
+                    .iter()
+                    .enumerate()
+                    .map(|(i, color)| (*color, self.ansi.get(&(i + offset)).cloned()))
+                    .collect(),
+            );
+        }
+
+        json!({ "colors": colors })
+    }
+}
+
+fn with_hash(color: &str) -> String {
+    let color = color.trim();
+    if color.starts_with('#') {
+        color.to_string()
+    } else {
+        format!("#{color}")
+    }
+}
+
+/// Strip the leading `#` for formats that expect bare `RRGGBB`
+pub fn bare_hex(color: &str) -> &str {
+    color.trim().trim_start_matches('#')
+}
+
+/// Schema for the `colors` object of terminals that inherit from the Alacritty palette.
+/// Every field defaults to empty, meaning "use the Alacritty value".
+pub fn inherited_colors_schema() -> Value {
+    let color = |title: &str| {
+        json!({
+            "type": "string",
+            "format": "color",
+            "title": title,
+            "default": "",
+            "description": "Leave empty to use the Alacritty color"
+        })
+    };
+    let ansi_section = |prefix: &str| {
+        let properties: Map<String, Value> = ANSI_NAMES
+            .iter()
+            .map(|name| {
+                let mut title = name.to_string();
+                title[..1].make_ascii_uppercase();
+                (name.to_string(), color(&format!("{prefix}{title}")))
+            })
+            .collect();
+        json!({
+            "type": "object",
+            "x-order": ANSI_NAMES,
+            "properties": properties
+        })
+    };
+
+    json!({
+        "type": "object",
+        "x-order": ["colors"],
+        "properties": {
+            "colors": {
+                "type": "object",
+                "x-order": ["primary", "cursor", "selection", "normal", "bright"],
+                "properties": {
+                    "primary": {
+                        "type": "object",
+                        "x-order": ["background", "foreground"],
+                        "properties": {
+                            "background": color("Background Color"),
+                            "foreground": color("Foreground Color")
+                        }
+                    },
+                    "cursor": {
+                        "type": "object",
+                        "properties": {
+                            "text": color("Cursor Text"),
+                            "cursor": color("Cursor Color")
+                        }
+                    },
+                    "selection": {
+                        "type": "object",
+                        "properties": {
+                            "background": color("Selection Background")
+                        }
+                    },
+                    "normal": ansi_section(""),
+                    "bright": ansi_section("Bright ")
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefers_app_colors_then_alacritty() {
+        let theme_data = json!({
+            "alacritty": { "colors": {
+                "primary": { "background": "#101010", "foreground": "#f0f0f0" },
+                "normal": { "red": "#aa0000" }
+            }},
+            "kitty": { "colors": {
+                "primary": { "background": "#202020", "foreground": "" }
+            }}
+        });
+
+        let palette = TerminalPalette::resolve(&theme_data, "kitty");
+        assert_eq!(palette.background, "#202020");
+        assert_eq!(palette.foreground, "#f0f0f0");
+        assert_eq!(palette.normal[1], "#aa0000");
+        assert_eq!(palette.bright[7], "#eaeaea");
+        assert_eq!(palette.ansi().count(), 16);
+    }
+
+    #[test]
+    fn test_parsed_palette_adds_hash_and_skips_missing() {
+        let mut parsed = ParsedPalette {
+            background: Some("121212".to_string()),
+            ..Default::default()
+        };
+        parsed.set_ansi(1, "ff0000");
+        parsed.set_ansi(9, "#ff5555");
+        parsed.set_ansi(16, "#000000");
+
+        let data = parsed.into_theme_data();
+        assert_eq!(
+            data,
+            json!({ "colors": {
+                "primary": { "background": "#121212" },
+                "normal": { "red": "#ff0000" },
+                "bright": { "red": "#ff5555" }
+            }})
+        );
+    }
+
+    #[test]
+    fn test_inherited_schema_allows_empty_colors() {
+        let schema = inherited_colors_schema();
+        let red = &schema["properties"]["colors"]["properties"]["bright"]["properties"]["red"];
+        assert_eq!(red["title"], "Bright Red");
+        assert_eq!(red["default"], "");
+    }
+}
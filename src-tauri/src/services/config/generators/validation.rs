@@ -0,0 +1,597 @@
+// Syntax checks for generated config files, run before anything is written to disk
+use std::fmt;
+
+/// Describes why a generated config file was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputValidationError {
+    /// Config key, CSS variable or section that caused the failure (if known)
+    pub field: Option<String>,
+    /// 1-based line number in the generated output (if known)
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl OutputValidationError {
+    pub fn new(field: Option<&str>, line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.map(|f| f.to_string()),
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for OutputValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.field, self.line) {
+            (Some(field), Some(line)) => {
+                write!(f, "field '{field}' (line {line}): {}", self.message)
+            },
+            (Some(field), None) => write!(f, "field '{field}': {}", self.message),
+            (None, Some(line)) => write!(f, "line {line}: {}", self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+pub type ValidationResult = Result<(), OutputValidationError>;
+
+/// Validate that the content parses as TOML (Alacritty)
+pub fn validate_toml(content: &str) -> ValidationResult {
+    match toml::from_str::<toml::Table>(content) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let line = e.span().map(|span| line_of_offset(content, span.start));
+            let field = line
+                .and_then(|l| content.lines().nth(l - 1))
+                .and_then(toml_key_of_line);
+            Err(OutputValidationError::new(
+                field.as_deref(),
+                line,
+                e.message().trim().to_string(),
+            ))
+        },
+    }
+}
+
+/// Tokenize GTK-style CSS (Waybar, Walker, SwayOSD) and check that comments, strings and
+/// blocks are closed and that every `@define-color` has a name and a usable color value
+pub fn validate_css(content: &str) -> ValidationResult {
+    let chars: Vec<char> = content.chars().collect();
+    let mut line = 1;
+    let mut depth = 0usize;
+    let mut statement = String::new();
+    let mut statement_line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+
+        // Comments
+        if ch == '/' && chars.get(i + 1) == Some(&'*') {
+            let start_line = line;
+            i += 2;
+            loop {
+                match chars.get(i) {
+                    Some('*') if chars.get(i + 1) == Some(&'/') => {
+                        i += 2;
+                        break;
+                    },
+                    Some(c) => {
+                        if *c == '\n' {
+                            line += 1;
+                        }
+                        i += 1;
+                    },
+                    None => {
+                        return Err(OutputValidationError::new(
+                            None,
+                            Some(start_line),
+                            "unterminated comment",
+                        ));
+                    },
+                }
+            }
+            continue;
+        }
+
+        // Strings
+        if ch == '"' || ch == '\'' {
+            let start_line = line;
+            statement.push(ch);
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some('\\') => {
+                        statement.push('\\');
+                        if let Some(next) = chars.get(i + 1) {
+                            statement.push(*next);
+                        }
+                        i += 2;
+                    },
+                    Some(c) if *c == ch => {
+                        statement.push(*c);
+                        i += 1;
+                        break;
+                    },
+                    Some('\n') | None => {
+                        return Err(OutputValidationError::new(
+                            None,
+                            Some(start_line),
+                            "unterminated string",
+                        ));
+                    },
+                    Some(c) => {
+                        statement.push(*c);
+                        i += 1;
+                    },
+                }
+            }
+            continue;
+        }
+
+        match ch {
+            '{' => {
+                depth += 1;
+                statement.clear();
+            },
+            '}' => {
+                if depth == 0 {
+                    return Err(OutputValidationError::new(
+                        None,
+                        Some(line),
+                        "unexpected '}' without a matching '{'",
+                    ));
+                }
+                depth -= 1;
+                statement.clear();
+            },
+            ';' => {
+                check_css_statement(statement.trim(), statement_line)?;
+                statement.clear();
+            },
+            '\n' => {
+                line += 1;
+                if !statement.trim().is_empty() {
+                    statement.push(ch);
+                }
+            },
+            c => {
+                if statement.trim().is_empty() && !c.is_whitespace() {
+                    statement.clear();
+                    statement_line = line;
+                }
+                statement.push(c);
+            },
+        }
+        i += 1;
+    }
+
+    if depth > 0 {
+        return Err(OutputValidationError::new(
+            None,
+            Some(line),
+            "unclosed '{' block at end of file",
+        ));
+    }
+
+    let rest = statement.trim();
+    if rest.starts_with('@') {
+        let field = rest.split_whitespace().nth(1);
+        return Err(OutputValidationError::new(
+            field,
+            Some(statement_line),
+            "missing ';' at end of statement",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check a single `;`-terminated CSS statement
+fn check_css_statement(statement: &str, line: usize) -> ValidationResult {
+    let Some(rest) = statement.strip_prefix("@define-color") else {
+        return Ok(());
+    };
+
+    let mut parts = rest.trim().splitn(2, char::is_whitespace);
+    let name = parts.next().unwrap_or("").trim();
+    let value = parts.next().unwrap_or("").trim();
+
+    if name.is_empty() {
+        return Err(OutputValidationError::new(
+            None,
+            Some(line),
+            "@define-color is missing a color name",
+        ));
+    }
+    if !is_css_identifier(name) {
+        return Err(OutputValidationError::new(
+            Some(name),
+            Some(line),
+            format!("'{name}' is not a valid color name"),
+        ));
+    }
+    if value.is_empty() {
+        return Err(OutputValidationError::new(
+            Some(name),
+            Some(line),
+            "color value is empty",
+        ));
+    }
+    if !is_css_color_value(value) {
+        return Err(OutputValidationError::new(
+            Some(name),
+            Some(line),
+            format!("'{value}' is not a valid color value"),
+        ));
+    }
+
+    Ok(())
+}
+
+fn is_css_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '-' => {},
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Accepts hex colors, color references, named colors and GTK color functions
+fn is_css_color_value(value: &str) -> bool {
+    if let Some(hex) = value.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    if let Some(reference) = value.strip_prefix('@') {
+        return is_css_identifier(reference);
+    }
+    if let Some(open) = value.find('(') {
+        let function = &value[..open];
+        let known = matches!(
+            function,
+            "rgb" | "rgba" | "hsl" | "hsla" | "alpha" | "shade" | "mix" | "lighter" | "darker"
+        );
+        return known && value.ends_with(')') && parens_balanced(&value[open..]);
+    }
+    // Named colors such as `transparent` or `white`
+    value.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn parens_balanced(value: &str) -> bool {
+    let mut depth = 0i32;
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            },
+            _ => {},
+        }
+    }
+    depth == 0
+}
+
+/// Validate INI-style content (Mako): sections must be closed, every other line must be
+/// `key=value`, and `*-color` keys must hold `#RRGGBB` or `#RRGGBBAA`
+pub fn validate_ini(content: &str) -> ValidationResult {
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[') {
+            let Some(name) = section.strip_suffix(']') else {
+                return Err(OutputValidationError::new(
+                    Some(section),
+                    Some(line_no),
+                    "section header is missing ']'",
+                ));
+            };
+            if name.trim().is_empty() {
+                return Err(OutputValidationError::new(
+                    None,
+                    Some(line_no),
+                    "section header is empty",
+                ));
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(OutputValidationError::new(
+                Some(line),
+                Some(line_no),
+                "expected 'key=value'",
+            ));
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        if key.is_empty() {
+            return Err(OutputValidationError::new(
+                None,
+                Some(line_no),
+                "key is empty",
+            ));
+        }
+
+        // mako allows a blend operator in front of the progress color
+        let color = match key {
+            "progress-color" => super::mako::split_progress_color(value).1,
+            _ => value,
+        };
+        if key.ends_with("color") && !is_hex_color(color, &[6, 8]) {
+            return Err(OutputValidationError::new(
+                Some(key),
+                Some(line_no),
+                format!("'{value}' is not a valid color (expected #RRGGBB or #RRGGBBAA)"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate btop theme lines of the form `theme[key]="value"`
+pub fn validate_btop_theme(content: &str) -> ValidationResult {
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("theme[") else {
+            return Err(OutputValidationError::new(
+                None,
+                Some(line_no),
+                "expected a theme[...] entry",
+            ));
+        };
+        let Some((key, rest)) = rest.split_once(']') else {
+            return Err(OutputValidationError::new(
+                None,
+                Some(line_no),
+                "theme key is missing ']'",
+            ));
+        };
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(OutputValidationError::new(
+                Some(key),
+                Some(line_no),
+                "theme key must be alphanumeric",
+            ));
+        }
+        let Some(value) = rest.trim().strip_prefix('=') else {
+            return Err(OutputValidationError::new(
+                Some(key),
+                Some(line_no),
+                "expected '=' after theme key",
+            ));
+        };
+        let value = value.trim();
+        let unquoted = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+            &value[1..value.len() - 1]
+        } else {
+            return Err(OutputValidationError::new(
+                Some(key),
+                Some(line_no),
+                "value must be wrapped in double quotes",
+            ));
+        };
+
+        if !is_btop_color(unquoted) {
+            return Err(OutputValidationError::new(
+                Some(key),
+                Some(line_no),
+                format!("'{unquoted}' is not a valid btop color"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// btop accepts an empty value (terminal default), `#RRGGBB`, `#GG` greyscale or `R G B`
+fn is_btop_color(value: &str) -> bool {
+    let value = value.trim();
+    if value.is_empty() || is_hex_color(value, &[2, 6]) {
+        return true;
+    }
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    parts.len() == 3 && parts.iter().all(|p| p.parse::<u8>().is_ok())
+}
+
+/// Check that `{`/`}` blocks in Hyprland-style config (Hyprland, Hyprlock) are balanced.
+/// `#` starts a comment, `##` is an escaped literal `#`.
+pub fn validate_brace_balance(content: &str) -> ValidationResult {
+    let mut open_sections: Vec<(String, usize)> = Vec::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = strip_hypr_comment(raw_line);
+        let mut before_brace = String::new();
+
+        for ch in line.chars() {
+            match ch {
+                '{' => {
+                    let name = before_brace.trim().to_string();
+                    open_sections.push((name, line_no));
+                    before_brace.clear();
+                },
+                '}' => {
+                    if open_sections.pop().is_none() {
+                        return Err(OutputValidationError::new(
+                            None,
+                            Some(line_no),
+                            "unexpected '}' without a matching '{'",
+                        ));
+                    }
+                    before_brace.clear();
+                },
+                c => before_brace.push(c),
+            }
+        }
+    }
+
+    if let Some((name, line_no)) = open_sections.pop() {
+        let field = if name.is_empty() {
+            None
+        } else {
+            Some(name.as_str())
+        };
+        return Err(OutputValidationError::new(
+            field,
+            Some(line_no),
+            "block is never closed",
+        ));
+    }
+
+    Ok(())
+}
+
+fn strip_hypr_comment(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '#' {
+            if chars.peek() == Some(&'#') {
+                chars.next();
+                result.push('#');
+                continue;
+            }
+            break;
+        }
+        result.push(ch);
+    }
+    result
+}
+
+fn is_hex_color(value: &str, lengths: &[usize]) -> bool {
+    value
+        .strip_prefix('#')
+        .map(|hex| lengths.contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .unwrap_or(false)
+}
+
+fn line_of_offset(content: &str, offset: usize) -> usize {
+    let offset = offset.min(content.len());
+    content[..offset].matches('\n').count() + 1
+}
+
+fn toml_key_of_line(line: &str) -> Option<String> {
+    let (key, _) = line.split_once('=')?;
+    let key = key.trim().trim_matches('"');
+    if key.is_empty() {
+        None
+    } else {
+        Some(key.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::generators::ConfigGeneratorRegistry;
+    use serde_json::json;
+
+    #[test]
+    fn test_builtin_generators_produce_valid_output() {
+        let registry = ConfigGeneratorRegistry::builtin();
+        for app_name in registry.get_all_apps() {
+            let generator = registry.get_generator(app_name).unwrap();
+            let content = generator.generate_config(&json!({})).unwrap();
+            if let Err(e) = generator.validate_output(&content) {
+                panic!("default {app_name} output failed validation: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_toml_reports_key() {
+        assert!(validate_toml("[colors.primary]\nbackground = \"#121212\"\n").is_ok());
+
+        let err =
+            validate_toml("[colors.primary]\nbackground = \"#121212\nforeground = \"#fff\"\n")
+                .unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.field.as_deref(), Some("background"));
+    }
+
+    #[test]
+    fn test_validate_css() {
+        assert!(validate_css(
+            "/* header */\n@define-color background #1e1e1e;\n@define-color border #EAEAEA88;\n\
+             @define-color fg @background;\nwindow { color: alpha(@fg, 0.5); }\n"
+        )
+        .is_ok());
+
+        let err = validate_css("@define-color background 1e1e1e;\n").unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("background"));
+
+        let err = validate_css("@define-color foreground #fff\n").unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("foreground"));
+
+        assert!(validate_css("/* never closed\n@define-color a #fff;").is_err());
+        assert!(validate_css("window { color: red;\n").is_err());
+        assert!(validate_css("}\n").is_err());
+    }
+
+    #[test]
+    fn test_validate_ini() {
+        assert!(
+            validate_ini("text-color=#8A8A8D\nwidth=420\n\n[app-name=Spotify]\ninvisible=1\n")
+                .is_ok()
+        );
+
+        let err = validate_ini("text-color=#8A8A8D\nborder-color=oops\n").unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("border-color"));
+        assert_eq!(err.line, Some(2));
+
+        assert!(validate_ini("progress-color=over #5588AAFF\n").is_ok());
+        assert!(validate_ini("progress-color=source #5588AA\n").is_ok());
+        assert!(validate_ini("progress-color=under #5588AA\n").is_err());
+
+        assert!(validate_ini("[urgency=low\n").is_err());
+        assert!(validate_ini("just some text\n").is_err());
+    }
+
+    #[test]
+    fn test_validate_btop_theme() {
+        assert!(validate_btop_theme(
+            "# comment\ntheme[main_bg]=\"\"\ntheme[main_fg]=\"#EAEAEA\"\ntheme[title]=\"#8a\"\n\
+             theme[hi_fg]=\"255 0 10\"\n"
+        )
+        .is_ok());
+
+        let err = validate_btop_theme("theme[cpu_box]=\"#12345\"\n").unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("cpu_box"));
+
+        assert!(validate_btop_theme("theme[cpu_box]=#123456\n").is_err());
+        assert!(validate_btop_theme("main_fg=\"#123456\"\n").is_err());
+    }
+
+    #[test]
+    fn test_validate_brace_balance() {
+        assert!(validate_brace_balance(
+            "general {\n    col.active_border = rgb(8A8A8D) # comment {\n}\n$text = ##fff\n"
+        )
+        .is_ok());
+
+        let err =
+            validate_brace_balance("general {\n  gaps_in = 5\n\ndecoration {\n}\n").unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("general"));
+        assert_eq!(err.line, Some(1));
+
+        assert!(validate_brace_balance("}\n").is_err());
+    }
+}
@@ -1,11 +1,62 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::gtk_css::{self, GtkCss};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::schema_validation::default_for;
+use serde_json::{json, Map, Value};
 
 pub struct WalkerGenerator;
 
 unsafe impl Send for WalkerGenerator {}
 unsafe impl Sync for WalkerGenerator {}
 
+/// Colors Omarchy's walker theme always defines: (schema key, `@define-color` name)
+const BASE_COLORS: [(&str, &str); 6] = [
+    ("selected_text", "selected-text"),
+    ("text", "text"),
+    ("base", "base"),
+    ("border", "border"),
+    ("foreground", "foreground"),
+    ("background", "background"),
+];
+
+/// Optional colors: (schema key, `@define-color` name, selector, property). Each is only
+/// written, together with its rule, when set.
+const RULE_COLORS: [(&str, &str, &str, &str); 4] = [
+    (
+        "highlight",
+        "highlight",
+        "child:selected .item-box",
+        "background",
+    ),
+    ("hover", "hover", "child:hover .item-box", "background"),
+    (
+        "search_background",
+        "search-background",
+        ".search-container",
+        "background",
+    ),
+    ("search_text", "search-text", ".input", "color"),
+];
+
+fn optional_color(title: &str, description: &str) -> Value {
+    json!({
+        "type": "string",
+        "format": "color",
+        "default": "",
+        "title": title,
+        "description": description
+    })
+}
+
+/// Hex-alpha values are stored without `#`; CSS needs it
+fn css_color(value: &str) -> String {
+    let is_bare_hex = matches!(value.len(), 6 | 8) && value.chars().all(|c| c.is_ascii_hexdigit());
+    if is_bare_hex {
+        format!("#{value}")
+    } else {
+        value.to_string()
+    }
+}
+
 impl ConfigGenerator for WalkerGenerator {
     fn get_app_name(&self) -> &'static str {
         "walker"
@@ -18,58 +69,53 @@ impl ConfigGenerator for WalkerGenerator {
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let walker = theme_data.get("walker").unwrap_or(&empty_obj);
+        let defaults = default_for(&self.get_config_schema()).unwrap_or_else(|| json!({}));
+        let get = |path: &[&str]| -> String {
+            [walker, &defaults]
+                .iter()
+                .find_map(|root| {
+                    path.iter()
+                        .try_fold(*root, |v, key| v.get(key))
+                        .and_then(|v| v.as_str())
+                })
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+
+        let mut colors: Vec<(&str, String)> = BASE_COLORS
+            .iter()
+            .map(|(key, name)| (*name, css_color(&get(&["colors", key]))))
+            .collect();
+        let mut rules = Vec::new();
+        for (key, name, selector, property) in RULE_COLORS {
+            let value = get(&["colors", key]);
+            if value.is_empty() {
+                continue;
+            }
+            colors.push((name, css_color(&value)));
+            rules.push(gtk_css::rule(selector, &[(property, format!("@{name}"))]));
+        }
 
-        // Extract color values with defaults from template
-        let colors = walker.get("colors").unwrap_or(&empty_obj);
-
-        let selected_text = colors
-            .get("selected_text")
-            .and_then(|st| st.as_str())
-            .unwrap_or("#B91C1C");
-        let text = colors
-            .get("text")
-            .and_then(|t| t.as_str())
-            .unwrap_or("#EAEAEA");
-        let base = colors
-            .get("base")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#121212");
-        let border = colors
-            .get("border")
-            .and_then(|br| br.as_str())
-            .unwrap_or("#EAEAEA88");
-        let foreground = colors
-            .get("foreground")
-            .and_then(|fg| fg.as_str())
-            .unwrap_or("#EAEAEA");
-        let background = colors
-            .get("background")
-            .and_then(|bg| bg.as_str())
-            .unwrap_or("#121212");
-
-        Ok(format!(
-            r#"/* ────────────────────────────────────────────────────────────
- * Omarchy Custom Theme for Walker
- * Generated with Omarchist
- * ────────────────────────────────────────────────────────────
- */
-
-@define-color selected-text {selected_text};
-@define-color text {text};
-@define-color base {base};
-@define-color border {border};
-@define-color foreground {foreground};
-@define-color background {background};
-"#
+        Ok(gtk_css::render(
+            "Walker",
+            &colors,
+            &rules,
+            &get(&["custom_css"]),
         ))
     }
 
     fn get_config_schema(&self) -> Value {
         json!({
                 "type": "object",
+                "x-order": ["colors", "custom_css"],
                 "properties": {
                     "colors": {
                     "type": "object",
+                    "x-order": [
+                        "selected_text", "text", "base", "border", "foreground", "background",
+                        "highlight", "hover", "search_background", "search_text"
+                    ],
                     "properties": {
                     "selected_text": {
                         "type": "string",
@@ -113,15 +159,133 @@ impl ConfigGenerator for WalkerGenerator {
                         "default": "#121212",
                         "title": "Background Color",
                         "description": "Primary background color"
+                    },
+                    "highlight": optional_color(
+                        "Highlight Color",
+                        "Background of the selected item; empty keeps Walker's default"
+                    ),
+                    "hover": optional_color(
+                        "Hover Color",
+                        "Background of the item under the pointer; empty keeps Walker's default"
+                    ),
+                    "search_background": optional_color(
+                        "Search Bar Background",
+                        "Background of the search bar; empty keeps Walker's default"
+                    ),
+                    "search_text": optional_color(
+                        "Search Text Color",
+                        "Color of the typed query; empty keeps Walker's default"
+                    )
                     }
-                    }
-                }
+                },
+                "custom_css": gtk_css::custom_css_schema()
             }
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement CSS parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let mut css = GtkCss::parse(content)?;
+
+        let mut colors = Map::new();
+        for (key, name) in BASE_COLORS {
+            if let Some(value) = css.color(name) {
+                colors.insert(key.to_string(), json!(value));
+            }
+        }
+        for (key, name, selector, property) in RULE_COLORS {
+            if let Some(value) = css.color(name) {
+                colors.insert(key.to_string(), json!(value));
+            }
+            // Drop the rule we would write again; anything else stays custom CSS
+            if css.rule(selector).is_some_and(|decls| {
+                decls.len() == 1 && decls[0] == (property.to_string(), format!("@{name}"))
+            }) {
+                css.take_rule(selector);
+            }
+        }
+
+        let mut result = Map::new();
+        if !colors.is_empty() {
+            result.insert("colors".into(), Value::Object(colors));
+        }
+        let owned: Vec<&str> = BASE_COLORS
+            .iter()
+            .map(|(_, name)| *name)
+            .chain(RULE_COLORS.iter().map(|(_, name, _, _)| *name))
+            .collect();
+        let custom_css = css.custom_css(&owned);
+        if !custom_css.is_empty() {
+            result.insert("custom_css".into(), json!(custom_css));
+        }
+        Ok(Value::Object(result))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_css(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_omarchy_template() {
+        let config = WalkerGenerator.generate_config(&json!({})).unwrap();
+        assert!(config.contains("@define-color selected-text #B91C1C;"));
+        assert!(config.contains("@define-color border #EAEAEA88;"));
+        assert!(config.contains("@define-color background #121212;"));
+        assert!(!config.contains("highlight"));
+        assert!(!config.contains('{'));
+        assert!(WalkerGenerator.validate_output(&config).is_ok());
+    }
+
+    #[test]
+    fn test_extra_colors_round_trip() {
+        let theme = json!({
+            "walker": {
+                "colors": {
+                    "base": "#1a1b26",
+                    "highlight": "#33467c",
+                    "search_text": "#c0caf5"
+                },
+                "custom_css": "#window {\n  border-width: 2px;\n}"
+            }
+        });
+        let config = WalkerGenerator.generate_config(&theme).unwrap();
+        assert!(config.contains("@define-color highlight #33467c;"));
+        assert!(config.contains("child:selected .item-box {\n  background: @highlight;\n}"));
+        assert!(config.contains(".input {\n  color: @search-text;\n}"));
+        assert!(!config.contains("child:hover"));
+        assert!(WalkerGenerator.validate_output(&config).is_ok());
+
+        let parsed = WalkerGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["base"], "#1a1b26");
+        assert_eq!(parsed["colors"]["border"], "#EAEAEA88");
+        assert_eq!(parsed["colors"]["highlight"], "#33467c");
+        assert_eq!(parsed["colors"]["search_text"], "#c0caf5");
+        assert!(parsed["colors"].get("hover").is_none());
+        assert_eq!(parsed["custom_css"], "#window {\n  border-width: 2px;\n}");
+    }
+
+    #[test]
+    fn test_parse_keeps_user_rules() {
+        let existing = "@define-color text #ffffff;\n\n/* tweak */\n\
+                        .input {\n  color: @search-text;\n  font-size: 20px;\n}\n";
+        let parsed = WalkerGenerator.parse_existing_config(existing).unwrap();
+        assert_eq!(parsed["colors"], json!({ "text": "#ffffff" }));
+        assert_eq!(
+            parsed["custom_css"],
+            "/* tweak */\n\n.input {\n  color: @search-text;\n  font-size: 20px;\n}"
+        );
+
+        // Color variables the generator does not own are the user's and stay
+        let existing = "@define-color text #ffffff;\n@define-color my-accent #ff8800;\n";
+        let parsed = WalkerGenerator.parse_existing_config(existing).unwrap();
+        assert_eq!(parsed["custom_css"], "@define-color my-accent #ff8800;");
+        let rendered = WalkerGenerator
+            .generate_config(&json!({ "walker": parsed }))
+            .unwrap();
+        assert!(rendered.contains("@define-color my-accent #ff8800;"));
     }
-}
\ No newline at end of file
+}
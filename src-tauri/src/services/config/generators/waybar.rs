@@ -1,11 +1,89 @@
-use super::ConfigGenerator;
-use serde_json::{json, Value};
+use super::gtk_css::{self, GtkCss};
+use super::{validation, ConfigGenerator, OutputValidationError};
+use crate::services::config::schema_validation::default_for;
+use serde_json::{json, Map, Value};
 
 pub struct WaybarGenerator;
 
 unsafe impl Send for WaybarGenerator {}
 unsafe impl Sync for WaybarGenerator {}
 
+/// Named colors beyond background/foreground, written as `@define-color` variables
+pub const PALETTE_KEYS: [&str; 5] = [
+    "accent",
+    "warning",
+    "critical",
+    "muted",
+    "module_background",
+];
+
+/// Modules with color overrides; their background follows `@module_background`
+const MODULES: [&str; 5] = ["workspaces", "clock", "battery", "network", "pulseaudio"];
+
+/// Per-module rules: (module, state, CSS selector, palette color). Each sets the text color
+/// of the selector to the override, or to the palette color when the override is empty.
+const MODULE_RULES: [(&str, &str, &str, Option<&str>); 8] = [
+    (
+        "workspaces",
+        "active",
+        "#workspaces button.active",
+        Some("accent"),
+    ),
+    (
+        "workspaces",
+        "urgent",
+        "#workspaces button.urgent",
+        Some("critical"),
+    ),
+    ("clock", "foreground", "#clock", None),
+    ("battery", "charging", "#battery.charging", Some("accent")),
+    (
+        "battery",
+        "warning",
+        "#battery.warning:not(.charging)",
+        Some("warning"),
+    ),
+    (
+        "battery",
+        "critical",
+        "#battery.critical:not(.charging)",
+        Some("critical"),
+    ),
+    (
+        "network",
+        "disconnected",
+        "#network.disconnected",
+        Some("muted"),
+    ),
+    ("pulseaudio", "muted", "#pulseaudio.muted", Some("muted")),
+];
+
+fn module_selector() -> String {
+    MODULES.map(|module| format!("#{module}")).join(", ")
+}
+
+fn color(title: &str, default: &str) -> Value {
+    json!({"type": "string", "format": "color", "title": title, "default": default})
+}
+
+fn override_color(module: &str, state: &str, title: &str) -> Value {
+    let palette = MODULE_RULES
+        .iter()
+        .find(|(m, s, _, _)| *m == module && *s == state)
+        .and_then(|(_, _, _, palette)| *palette);
+    let description = match palette {
+        Some(name) => format!("Leave empty to use the palette's {name} color"),
+        None => "Leave empty to keep the bar's foreground".to_string(),
+    };
+    json!({
+        "type": "string",
+        "format": "color",
+        "title": title,
+        "description": description,
+        "default": ""
+    })
+}
+
 impl ConfigGenerator for WaybarGenerator {
     fn get_app_name(&self) -> &'static str {
         "waybar"
@@ -18,54 +96,308 @@ impl ConfigGenerator for WaybarGenerator {
     fn generate_config(&self, theme_data: &Value) -> Result<String, String> {
         let empty_obj = json!({});
         let waybar = theme_data.get("waybar").unwrap_or(&empty_obj);
+        let defaults = default_for(&self.get_config_schema()).unwrap_or_else(|| json!({}));
+        let get = |path: &[&str]| -> String {
+            [waybar, &defaults]
+                .iter()
+                .find_map(|root| {
+                    path.iter()
+                        .try_fold(*root, |v, key| v.get(key))
+                        .and_then(|v| v.as_str())
+                })
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+
+        let mut colors = vec![
+            ("background", get(&["colors", "main", "background"])),
+            ("foreground", get(&["colors", "main", "foreground"])),
+        ];
+        for key in PALETTE_KEYS {
+            let value = get(&["colors", "palette", key]);
+            // An empty module background follows the bar background
+            if value.is_empty() && key == "module_background" {
+                colors.push((key, "@background".to_string()));
+            } else if !value.is_empty() {
+                colors.push((key, value));
+            }
+        }
 
-        // Extract color variables with defaults from template
-        let empty_colors = json!({});
-        let colors = waybar.get("colors").unwrap_or(&empty_colors);
-        let main = colors.get("main").unwrap_or(&empty_colors);
-        let bg = main
-            .get("background")
-            .and_then(|b| b.as_str())
-            .unwrap_or("#1e1e1e");
-        let fg = main
-            .get("foreground")
-            .and_then(|f| f.as_str())
-            .unwrap_or("#8a8a8d");
-        Ok(format!(
-            r#"/* ────────────────────────────────────────────────────────────
- * Omarchy Custom Theme for Waybar
- * Generated with Omarchist
- * ────────────────────────────────────────────────────────────
- */
-
-@define-color background {bg};
-@define-color foreground {fg};
-"#
+        let mut rules = vec![gtk_css::rule(
+            &module_selector(),
+            &[("background-color", "@module_background".to_string())],
+        )];
+        rules.extend(
+            MODULE_RULES
+                .iter()
+                .filter_map(|(module, state, selector, palette)| {
+                    let value = get(&["modules", module, state]);
+                    let value = match palette {
+                        Some(name) if value.is_empty() => format!("@{name}"),
+                        _ => value,
+                    };
+                    (!value.is_empty()).then(|| gtk_css::rule(selector, &[("color", value)]))
+                }),
+        );
+
+        Ok(gtk_css::render(
+            "Waybar",
+            &colors,
+            &rules,
+            &get(&["custom_css"]),
         ))
     }
 
     fn get_config_schema(&self) -> Value {
         json!({
             "type": "object",
+            "x-order": ["colors", "modules", "custom_css"],
             "properties": {
                 "colors": {
                     "type": "object",
+                    "x-order": ["main", "palette"],
                     "properties": {
                         "main": {
                             "type": "object",
                             "properties": {
-                                "background": {"type": "string", "format": "color", "title": "Background", "default": "#1e1e1e"},
-                                "foreground": {"type": "string", "format": "color", "title": "Foreground", "default": "#8a8a8d"}
+                                "background": color("Background", "#1e1e1e"),
+                                "foreground": color("Foreground", "#8a8a8d")
                             }
                         },
+                        "palette": {
+                            "type": "object",
+                            "title": "Palette",
+                            "x-order": PALETTE_KEYS,
+                            "properties": {
+                                "accent": color("Accent", "#f59e0b"),
+                                "warning": color("Warning", "#e68e0d"),
+                                "critical": color("Critical", "#d35f5f"),
+                                "muted": color("Muted", "#5c5c5e"),
+                                "module_background": {
+                                    "type": "string",
+                                    "format": "color",
+                                    "title": "Module Background",
+                                    "description": "Leave empty to use the bar background",
+                                    "default": ""
+                                }
+                            }
+                        }
                     }
-                }
+                },
+                "modules": {
+                    "type": "object",
+                    "title": "Module Overrides",
+                    "x-order": MODULES,
+                    "properties": {
+                        "workspaces": {
+                            "type": "object",
+                            "title": "Workspaces",
+                            "properties": {
+                                "active": override_color("workspaces", "active", "Active"),
+                                "urgent": override_color("workspaces", "urgent", "Urgent")
+                            }
+                        },
+                        "clock": {
+                            "type": "object",
+                            "title": "Clock",
+                            "properties": { "foreground": override_color("clock", "foreground", "Foreground") }
+                        },
+                        "battery": {
+                            "type": "object",
+                            "title": "Battery",
+                            "x-order": ["charging", "warning", "critical"],
+                            "properties": {
+                                "charging": override_color("battery", "charging", "Charging"),
+                                "warning": override_color("battery", "warning", "Warning"),
+                                "critical": override_color("battery", "critical", "Critical")
+                            }
+                        },
+                        "network": {
+                            "type": "object",
+                            "title": "Network",
+                            "properties": { "disconnected": override_color("network", "disconnected", "Disconnected") }
+                        },
+                        "pulseaudio": {
+                            "type": "object",
+                            "title": "PulseAudio",
+                            "properties": { "muted": override_color("pulseaudio", "muted", "Muted") }
+                        }
+                    }
+                },
+                "custom_css": gtk_css::custom_css_schema()
             }
         })
     }
 
-    fn parse_existing_config(&self, _content: &str) -> Result<Value, String> {
-        // For now, return empty - could implement CSS parsing if needed
-        Ok(json!({}))
+    fn parse_existing_config(&self, content: &str) -> Result<Value, String> {
+        let mut css = GtkCss::parse(content)?;
+
+        let mut main = Map::new();
+        for name in ["background", "foreground"] {
+            if let Some(value) = css.color(name) {
+                main.insert(name.to_string(), json!(value));
+            }
+        }
+        let mut palette = Map::new();
+        for name in PALETTE_KEYS {
+            match css.color(name) {
+                Some("@background") if name == "module_background" => {
+                    palette.insert(name.to_string(), json!(""));
+                },
+                Some(value) => {
+                    palette.insert(name.to_string(), json!(value));
+                },
+                None => {},
+            }
+        }
+
+        let selector = module_selector();
+        if css.rule(&selector).as_deref()
+            == Some(&[(
+                "background-color".to_string(),
+                "@module_background".to_string(),
+            )])
+        {
+            css.take_rule(&selector);
+        }
+        let mut modules = Map::new();
+        for (module, state, selector, palette) in MODULE_RULES {
+            let Some(value) = css.take_single(selector, "color") else {
+                continue;
+            };
+            // A rule pointing at its palette color is the default, not an override
+            if palette.is_some_and(|name| value == format!("@{name}")) {
+                continue;
+            }
+            modules
+                .entry(module.to_string())
+                .or_insert_with(|| json!({}))[state] = json!(value);
+        }
+
+        let mut colors = Map::new();
+        if !main.is_empty() {
+            colors.insert("main".into(), Value::Object(main));
+        }
+        if !palette.is_empty() {
+            colors.insert("palette".into(), Value::Object(palette));
+        }
+        let mut result = Map::new();
+        if !colors.is_empty() {
+            result.insert("colors".into(), Value::Object(colors));
+        }
+        if !modules.is_empty() {
+            result.insert("modules".into(), Value::Object(modules));
+        }
+        let owned: Vec<&str> = ["background", "foreground"]
+            .into_iter()
+            .chain(PALETTE_KEYS)
+            .collect();
+        let custom_css = css.custom_css(&owned);
+        if !custom_css.is_empty() {
+            result.insert("custom_css".into(), json!(custom_css));
+        }
+        Ok(Value::Object(result))
+    }
+
+    fn validate_output(&self, content: &str) -> Result<(), OutputValidationError> {
+        validation::validate_css(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_point_module_rules_at_the_palette() {
+        let config = WaybarGenerator.generate_config(&json!({})).unwrap();
+        assert!(config.contains("@define-color background #1e1e1e;"));
+        assert!(config.contains("@define-color foreground #8a8a8d;"));
+        assert!(config.contains("@define-color accent #f59e0b;"));
+        assert!(config.contains("@define-color module_background @background;"));
+        assert!(config.contains(
+            "#workspaces, #clock, #battery, #network, #pulseaudio {\n  background-color: @module_background;\n}"
+        ));
+        assert!(config.contains("#workspaces button.active {\n  color: @accent;\n}"));
+        assert!(config.contains("#battery.critical:not(.charging) {\n  color: @critical;\n}"));
+        assert!(config.contains("#pulseaudio.muted {\n  color: @muted;\n}"));
+        assert!(!config.contains("#clock {"));
+        assert!(WaybarGenerator.validate_output(&config).is_ok());
+
+        // Palette defaults are not read back as overrides
+        let parsed = WaybarGenerator.parse_existing_config(&config).unwrap();
+        assert!(parsed.get("modules").is_none());
+        assert!(parsed.get("custom_css").is_none());
+    }
+
+    #[test]
+    fn test_module_overrides_round_trip() {
+        let theme = json!({
+            "waybar": {
+                "colors": {
+                    "main": { "background": "#1a1b26", "foreground": "#c0caf5" },
+                    "palette": { "accent": "#7aa2f7", "module_background": "#24283b" }
+                },
+                "modules": {
+                    "workspaces": { "active": "#7aa2f7" },
+                    "battery": { "critical": "#f7768e" }
+                }
+            }
+        });
+        let config = WaybarGenerator.generate_config(&theme).unwrap();
+        assert!(config.contains("#workspaces button.active {\n  color: #7aa2f7;\n}"));
+        assert!(config.contains("#battery.critical:not(.charging) {\n  color: #f7768e;\n}"));
+        assert!(config.contains("#workspaces button.urgent {\n  color: @critical;\n}"));
+        assert!(!config.contains("#clock {"));
+        assert!(WaybarGenerator.validate_output(&config).is_ok());
+
+        let parsed = WaybarGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(parsed["colors"]["main"]["background"], "#1a1b26");
+        assert_eq!(parsed["colors"]["palette"]["accent"], "#7aa2f7");
+        assert_eq!(parsed["colors"]["palette"]["module_background"], "#24283b");
+        assert_eq!(parsed["colors"]["palette"]["critical"], "#d35f5f");
+        assert_eq!(
+            parsed["modules"],
+            json!({
+                "workspaces": { "active": "#7aa2f7" },
+                "battery": { "critical": "#f7768e" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_rules_survive_round_trip() {
+        let existing = "@define-color background #111111;\n@define-color foreground #eeeeee;\n\n\
+                        /* bigger clock */\n#clock {\n  font-size: 14px;\n}\n\n\
+                        #pulseaudio.muted {\n  color: #ff0000;\n}\n";
+        let parsed = WaybarGenerator.parse_existing_config(existing).unwrap();
+        assert_eq!(parsed["modules"]["pulseaudio"]["muted"], "#ff0000");
+        assert_eq!(
+            parsed["custom_css"],
+            "/* bigger clock */\n\n#clock {\n  font-size: 14px;\n}"
+        );
+
+        let config = WaybarGenerator
+            .generate_config(&json!({ "waybar": parsed }))
+            .unwrap();
+        assert!(config.contains("#clock {\n  font-size: 14px;\n}"));
+        assert_eq!(config.matches("#pulseaudio.muted").count(), 1);
+        assert!(WaybarGenerator.validate_output(&config).is_ok());
+        let reparsed = WaybarGenerator.parse_existing_config(&config).unwrap();
+        assert_eq!(reparsed["custom_css"], parsed["custom_css"]);
+    }
+
+    #[test]
+    fn test_parse_omarchy_waybar_css() {
+        let parsed = WaybarGenerator
+            .parse_existing_config(
+                "@define-color foreground #cdd6f4;\n@define-color background #181824;\n",
+            )
+            .unwrap();
+        assert_eq!(
+            parsed,
+            json!({ "colors": { "main": { "foreground": "#cdd6f4", "background": "#181824" } } })
+        );
     }
 }
@@ -1,5 +1,9 @@
+use crate::services::safe_path;
+use crate::services::themes::chromium_extension;
+use crate::services::themes::custom_themes::CustomThemeService;
+use crate::types::ThemeVariant;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
 pub struct LightModeService {
@@ -8,11 +12,7 @@ pub struct LightModeService {
 
 impl LightModeService {
     pub fn new(_app_handle: &AppHandle) -> Result<Self, String> {
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| "Failed to get home directory".to_string())?;
-        
-        let themes_dir = home_dir.join(".config").join("omarchy").join("themes");
-        
+        let themes_dir = safe_path::themes_root()?;
         Ok(Self { themes_dir })
     }
 
@@ -35,8 +35,7 @@ impl LightModeService {
 
     /// Check if a theme is in light mode
     pub fn is_light_mode(&self, theme_name: &str) -> Result<bool, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let theme_dir = safe_path::theme_dir(&self.themes_dir, &Self::sanitize_name(theme_name))?;
         
         if !theme_dir.exists() {
             return Err(format!("Theme '{}' not found", theme_name));
@@ -48,8 +47,7 @@ impl LightModeService {
 
     /// Enable light mode for a theme (create light.mode file)
     pub fn enable_light_mode(&self, theme_name: &str) -> Result<(), String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let theme_dir = safe_path::theme_dir(&self.themes_dir, &Self::sanitize_name(theme_name))?;
         
         if !theme_dir.exists() {
             return Err(format!("Theme '{}' not found", theme_name));
@@ -63,13 +61,13 @@ impl LightModeService {
             .map_err(|e| format!("Failed to create light.mode file: {}", e))?;
         
         log::info!("Enabled light mode for theme '{}'", theme_name);
+        Self::refresh_chromium_extension(&theme_dir);
         Ok(())
     }
 
     /// Disable light mode for a theme (remove light.mode file)
     pub fn disable_light_mode(&self, theme_name: &str) -> Result<(), String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let theme_dir = safe_path::theme_dir(&self.themes_dir, &Self::sanitize_name(theme_name))?;
         
         if !theme_dir.exists() {
             return Err(format!("Theme '{}' not found", theme_name));
@@ -82,11 +80,19 @@ impl LightModeService {
                 .map_err(|e| format!("Failed to remove light.mode file: {}", e))?;
             
             log::info!("Disabled light mode for theme '{}'", theme_name);
+            Self::refresh_chromium_extension(&theme_dir);
         }
         
         Ok(())
     }
 
+    /// The Chromium theme extension has separate light and dark colors
+    fn refresh_chromium_extension(theme_dir: &Path) {
+        if let Err(e) = chromium_extension::refresh_extension(theme_dir) {
+            log::warn!("Failed to refresh Chromium theme extension: {e}");
+        }
+    }
+
     /// Set light mode for a theme (enable if true, disable if false)
     pub fn set_light_mode(&self, theme_name: &str, is_light: bool) -> Result<(), String> {
         if is_light {
@@ -110,6 +116,17 @@ pub async fn set_theme_light_mode(
     theme_name: String, 
     is_light: bool
 ) -> Result<(), String> {
+    // Themes with a light variant regenerate their files instead of only flipping the marker
+    let custom_themes = CustomThemeService::new(&app_handle)?;
+    if custom_themes.has_light_variant(&theme_name) {
+        let variant = if is_light {
+            ThemeVariant::Light
+        } else {
+            ThemeVariant::Dark
+        };
+        return custom_themes.switch_variant(&theme_name, variant).map(|_| ());
+    }
+
     let service = LightModeService::new(&app_handle)?;
     service.set_light_mode(&theme_name, is_light)
 }
\ No newline at end of file
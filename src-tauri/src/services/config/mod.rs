@@ -1,6 +1,8 @@
 // Configuration management services
 pub mod generators;
 pub mod light_mode;
+pub mod oklch;
+pub mod schema_validation;
 
 // Re-export the config generators module
 pub use generators as config_generators;
@@ -0,0 +1,167 @@
+//! sRGB <-> OKLCH conversion for perceptual color blending
+
+/// A color in the OKLCH space: lightness 0-1, chroma >= 0 and hue in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f64,
+    pub c: f64,
+    pub h: f64,
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Parse `#RRGGBB` / `RRGGBB` into 0-1 channels
+fn parse_hex(hex: &str) -> Option<[f64; 3]> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(&hex[i..i + 2], 16)
+            .ok()
+            .map(|v| f64::from(v) / 255.0)
+    };
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+impl Oklch {
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let [r, g, b] = parse_hex(hex)?.map(srgb_to_linear);
+
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        let lightness = 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s;
+        let a = 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s;
+        let b = 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s;
+
+        Some(Self {
+            l: lightness,
+            c: a.hypot(b),
+            h: b.atan2(a).to_degrees().rem_euclid(360.0),
+        })
+    }
+
+    /// Convert back to `#rrggbb`, clamping colors outside the sRGB gamut
+    pub fn to_hex(self) -> String {
+        let (sin, cos) = self.h.to_radians().sin_cos();
+        let (a, b) = (self.c * cos, self.c * sin);
+
+        let l = (self.l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m = (self.l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s = (self.l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+        let rgb = [
+            4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+            -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+            -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        ]
+        .map(|c| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0).round() as u8);
+
+        format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+    }
+
+    /// Interpolate towards `other` by `t` (0-1), taking the shorter way around the hue circle.
+    /// Near-greys have no meaningful hue, so the other color's hue is used for them.
+    pub fn mix(self, other: Self, t: f64) -> Self {
+        const ACHROMATIC: f64 = 0.02;
+        let (h1, h2) = match (self.c < ACHROMATIC, other.c < ACHROMATIC) {
+            (true, false) => (other.h, other.h),
+            (false, true) => (self.h, self.h),
+            _ => (self.h, other.h),
+        };
+        let mut delta = h2 - h1;
+        if delta > 180.0 {
+            delta -= 360.0;
+        } else if delta < -180.0 {
+            delta += 360.0;
+        }
+
+        Self {
+            l: self.l + (other.l - self.l) * t,
+            c: self.c + (other.c - self.c) * t,
+            h: (h1 + delta * t).rem_euclid(360.0),
+        }
+    }
+
+    /// Mirror lightness around the middle, keeping chroma and hue: dark becomes light
+    pub fn invert_lightness(self) -> Self {
+        Self {
+            l: (1.0 - self.l).clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Perceptual distance: Euclidean distance in OKLab
+    pub fn distance(self, other: Self) -> f64 {
+        let (a1, b1) = (
+            self.c * self.h.to_radians().cos(),
+            self.c * self.h.to_radians().sin(),
+        );
+        let (a2, b2) = (
+            other.c * other.h.to_radians().cos(),
+            other.c * other.h.to_radians().sin(),
+        );
+        ((self.l - other.l).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+}
+
+/// The perceptual midpoint of two hex colors
+pub fn midpoint(start: &str, end: &str) -> Option<String> {
+    Some(
+        Oklch::from_hex(start)?
+            .mix(Oklch::from_hex(end)?, 0.5)
+            .to_hex(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for hex in [
+            "#000000", "#ffffff", "#f59e0b", "#b91c1c", "#8a8a8d", "#3366ff",
+        ] {
+            assert_eq!(Oklch::from_hex(hex).unwrap().to_hex(), hex);
+        }
+        assert!(Oklch::from_hex("#12345").is_none());
+    }
+
+    #[test]
+    fn test_known_values() {
+        let white = Oklch::from_hex("#ffffff").unwrap();
+        assert!((white.l - 1.0).abs() < 1e-3);
+        assert!(white.c < 1e-3);
+
+        let red = Oklch::from_hex("#ff0000").unwrap();
+        assert!((red.l - 0.628).abs() < 1e-3);
+        assert!((red.h - 29.23).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_midpoint() {
+        assert_eq!(midpoint("#000000", "#000000").as_deref(), Some("#000000"));
+        // Grey to a color keeps the color's hue instead of passing through an arbitrary one
+        let mid = Oklch::from_hex(&midpoint("#8a8a8d", "#b91c1c").unwrap()).unwrap();
+        let red = Oklch::from_hex("#b91c1c").unwrap();
+        assert!((mid.h - red.h).abs() < 5.0);
+        assert!(midpoint("", "#ffffff").is_none());
+    }
+}
@@ -0,0 +1,503 @@
+use super::generators::ConfigGeneratorRegistry;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+/// A single schema violation, addressed by a dotted path such as `alacritty.colors.primary.background`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub message: String,
+}
+
+/// Result of validating theme `apps` data against the generator schemas
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaValidationReport {
+    pub valid: bool,
+    /// Normalized data: numbers coerced, defaults filled in
+    pub data: Value,
+    pub errors: Vec<SchemaViolation>,
+    /// Problems that do not block saving, such as an icon theme that is not installed
+    pub warnings: Vec<SchemaViolation>,
+}
+
+/// Errors and warnings collected while walking the data
+#[derive(Debug, Default)]
+pub struct Findings {
+    pub errors: Vec<SchemaViolation>,
+    pub warnings: Vec<SchemaViolation>,
+}
+
+impl SchemaValidationReport {
+    /// Collapse the error list into a single message for commands that return `String` errors
+    pub fn error_summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|e| format!("{}: {}", e.path, e.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Validates theme data against the schemas exposed by each registered generator
+pub struct SchemaValidator<'a> {
+    registry: &'a ConfigGeneratorRegistry,
+}
+
+impl<'a> SchemaValidator<'a> {
+    pub fn new(registry: &'a ConfigGeneratorRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// Validate and normalize the `apps` object of a custom theme.
+    /// Apps without a registered generator are passed through untouched.
+    pub fn validate_apps(&self, apps: &Value) -> SchemaValidationReport {
+        let mut findings = Findings::default();
+
+        let data = match apps {
+            Value::Object(app_map) => {
+                let mut normalized = Map::new();
+                for (app_name, app_data) in app_map {
+                    let value = match self.registry.get_schema_for_app(app_name) {
+                        Some(schema) => validate_value(&schema, app_data, app_name, &mut findings),
+                        None => app_data.clone(),
+                    };
+                    normalized.insert(app_name.clone(), value);
+                }
+                Value::Object(normalized)
+            },
+            _ => {
+                findings.errors.push(SchemaViolation {
+                    path: String::new(),
+                    message: "theme data must be an object".to_string(),
+                });
+                apps.clone()
+            },
+        };
+
+        SchemaValidationReport {
+            valid: findings.errors.is_empty(),
+            data,
+            errors: findings.errors,
+            warnings: findings.warnings,
+        }
+    }
+}
+
+/// Validate a value against a (subset of) JSON schema, returning the normalized value
+pub fn validate_value(schema: &Value, value: &Value, path: &str, findings: &mut Findings) -> Value {
+    let mut push_error = |message: String| {
+        findings.errors.push(SchemaViolation {
+            path: path.to_string(),
+            message,
+        });
+    };
+
+    let normalized = match schema.get("type").and_then(|t| t.as_str()) {
+        Some("object") => {
+            let Value::Object(map) = value else {
+                push_error(format!("expected an object, got {}", type_name(value)));
+                return value.clone();
+            };
+            return validate_object(schema, map, path, findings);
+        },
+        Some("array") => {
+            let Value::Array(items) = value else {
+                push_error(format!("expected an array, got {}", type_name(value)));
+                return value.clone();
+            };
+            let Some(item_schema) = schema.get("items") else {
+                return value.clone();
+            };
+            let items = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    validate_value(item_schema, item, &format!("{path}[{i}]"), findings)
+                })
+                .collect();
+            return Value::Array(items);
+        },
+        Some("number") | Some("integer") => {
+            let integer_only = schema.get("type").and_then(|t| t.as_str()) == Some("integer");
+            match coerce_number(value, integer_only) {
+                Some(number) => {
+                    if let Some(message) = check_range(schema, &number) {
+                        push_error(message);
+                    }
+                    Value::Number(number)
+                },
+                None => {
+                    let expected = if integer_only {
+                        "an integer"
+                    } else {
+                        "a number"
+                    };
+                    push_error(format!("expected {expected}, got {}", describe(value)));
+                    return value.clone();
+                },
+            }
+        },
+        Some("boolean") => match value {
+            Value::Bool(_) => value.clone(),
+            Value::String(s) if s == "true" || s == "false" => Value::Bool(s == "true"),
+            _ => {
+                push_error(format!("expected a boolean, got {}", describe(value)));
+                return value.clone();
+            },
+        },
+        Some("string") => match value {
+            Value::String(_) => value.clone(),
+            Value::Number(n) => Value::String(n.to_string()),
+            Value::Bool(b) => Value::String(b.to_string()),
+            _ => {
+                push_error(format!("expected a string, got {}", type_name(value)));
+                return value.clone();
+            },
+        },
+        _ => value.clone(),
+    };
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(&normalized) {
+            let options = allowed
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| v.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            // `x-allow-unlisted` marks the enum as suggestions, e.g. what is installed locally
+            if schema.get("x-allow-unlisted").and_then(|a| a.as_bool()) == Some(true) {
+                findings.warnings.push(SchemaViolation {
+                    path: path.to_string(),
+                    message: format!(
+                        "{} is not one of the available values ({options})",
+                        describe(&normalized)
+                    ),
+                });
+            } else {
+                push_error(format!(
+                    "{} is not one of the allowed values ({options})",
+                    describe(&normalized)
+                ));
+            }
+        }
+    }
+
+    if let Value::String(s) = &normalized {
+        if is_color_field(schema) {
+            let allow_empty = schema.get("default").and_then(|d| d.as_str()) == Some("");
+            let output_format = schema.get("output_format").and_then(|f| f.as_str());
+            let accepted = (allow_empty && s.is_empty()) || is_valid_color(s, output_format);
+            if !accepted {
+                push_error(format!(
+                    "'{s}' is not a valid color{}",
+                    output_format
+                        .map(|f| format!(" for format '{f}'"))
+                        .unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    normalized
+}
+
+/// Validate each known property of an object and fill in missing defaults
+fn validate_object(
+    schema: &Value,
+    map: &Map<String, Value>,
+    path: &str,
+    findings: &mut Findings,
+) -> Value {
+    let mut result = map.clone();
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Value::Object(result);
+    };
+
+    for (key, prop_schema) in properties {
+        let child_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+
+        match map.get(key) {
+            Some(child) => {
+                let normalized = validate_value(prop_schema, child, &child_path, findings);
+                result.insert(key.clone(), normalized);
+            },
+            None => {
+                if let Some(default) = default_for(prop_schema) {
+                    result.insert(key.clone(), default);
+                }
+            },
+        }
+    }
+
+    Value::Object(result)
+}
+
+/// Build the default value for a schema: its own `default`, or an object assembled from
+/// the defaults of its properties
+pub fn default_for(schema: &Value) -> Option<Value> {
+    if let Some(default) = schema.get("default") {
+        return Some(default.clone());
+    }
+
+    if schema.get("type").and_then(|t| t.as_str()) == Some("object") {
+        let properties = schema.get("properties")?.as_object()?;
+        let defaults: Map<String, Value> = properties
+            .iter()
+            .filter_map(|(key, prop)| default_for(prop).map(|d| (key.clone(), d)))
+            .collect();
+        if !defaults.is_empty() {
+            return Some(Value::Object(defaults));
+        }
+    }
+
+    None
+}
+
+/// Deep-merge JSON values: when both sides are objects, merge keys recursively.
+/// Otherwise, overwrite target with source.
+pub fn deep_merge(target: &mut Value, src: &Value) {
+    match (target, src) {
+        (Value::Object(t_map), Value::Object(s_map)) => {
+            for (k, v) in s_map {
+                match t_map.get_mut(k) {
+                    Some(t_child) => deep_merge(t_child, v),
+                    None => {
+                        t_map.insert(k.clone(), v.clone());
+                    },
+                }
+            }
+        },
+        (t, s) => *t = s.clone(),
+    }
+}
+
+/// Whether a schema describes a color string
+pub fn is_color_field(schema: &Value) -> bool {
+    schema.get("format").and_then(|f| f.as_str()) == Some("color")
+        || schema.get("output_format").is_some()
+}
+
+/// Check a color string against the representation a generator expects
+pub fn is_valid_color(value: &str, output_format: Option<&str>) -> bool {
+    let value = value.trim();
+    match output_format {
+        Some("rgba-comma") => {
+            let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+            if parts.len() != 3 && parts.len() != 4 {
+                return false;
+            }
+            let rgb_ok = parts[..3].iter().all(|p| p.parse::<u8>().is_ok());
+            let alpha_ok = parts
+                .get(3)
+                .map(|a| {
+                    a.parse::<f64>()
+                        .map(|a| (0.0..=1.0).contains(&a))
+                        .unwrap_or(false)
+                })
+                .unwrap_or(true);
+            rgb_ok && alpha_ok
+        },
+        Some("hex-no-hash") | Some("hex-alpha") => {
+            let hex = value.strip_prefix('#').unwrap_or(value);
+            is_hex_digits(hex, &[6, 8])
+        },
+        _ => value
+            .strip_prefix('#')
+            .map(|hex| is_hex_digits(hex, &[3, 6, 8]))
+            .unwrap_or(false),
+    }
+}
+
+fn is_hex_digits(hex: &str, lengths: &[usize]) -> bool {
+    lengths.contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Coerce numbers and numeric strings; whole numbers are stored as integers so generators
+/// reading them with `as_u64()` keep working
+fn coerce_number(value: &Value, integer_only: bool) -> Option<Number> {
+    let float = match value {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => s.trim().parse::<f64>().ok()?,
+        _ => return None,
+    };
+
+    if !float.is_finite() {
+        return None;
+    }
+
+    if float.fract() == 0.0 && float.abs() < i64::MAX as f64 {
+        return Some(Number::from(float as i64));
+    }
+
+    if integer_only {
+        return None;
+    }
+
+    Number::from_f64(float)
+}
+
+fn check_range(schema: &Value, number: &Number) -> Option<String> {
+    let n = number.as_f64()?;
+    if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+        if n < min {
+            return Some(format!("{n} is below the minimum of {min}"));
+        }
+    }
+    if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+        if n > max {
+            return Some(format!("{n} is above the maximum of {max}"));
+        }
+    }
+    None
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{s}'"),
+        Value::Object(_) | Value::Array(_) | Value::Null => type_name(value).to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Validate theme data against the generator schemas without saving anything
+#[tauri::command]
+pub async fn validate_theme_data(theme_data: Value) -> Result<SchemaValidationReport, String> {
+    let registry = ConfigGeneratorRegistry::new();
+    Ok(SchemaValidator::new(&registry).validate_apps(&theme_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn validate(apps: Value) -> SchemaValidationReport {
+        let registry = ConfigGeneratorRegistry::builtin();
+        SchemaValidator::new(&registry).validate_apps(&apps)
+    }
+
+    #[test]
+    fn test_coerces_numeric_strings() {
+        let report = validate(json!({
+            "hyprland": { "general": { "border_size": "3", "gaps_out": 12.0 } }
+        }));
+        assert!(report.valid, "{:?}", report.errors);
+        assert_eq!(report.data["hyprland"]["general"]["border_size"], json!(3));
+        assert_eq!(
+            report.data["hyprland"]["general"]["border_size"].as_u64(),
+            Some(3)
+        );
+        assert_eq!(report.data["hyprland"]["general"]["gaps_out"], json!(12));
+    }
+
+    #[test]
+    fn test_rejects_non_numeric_values() {
+        let report = validate(json!({
+            "hyprland": { "general": { "border_size": "wide" } }
+        }));
+        assert!(!report.valid);
+        assert_eq!(report.errors[0].path, "hyprland.general.border_size");
+    }
+
+    #[test]
+    fn test_rejects_unknown_enum_value() {
+        let report = validate(json!({ "hyprland": { "animations": { "preset": "bouncy" } } }));
+        assert!(!report.valid);
+        assert_eq!(report.errors[0].path, "hyprland.animations.preset");
+
+        let report = validate(json!({ "hyprland": { "animations": { "preset": "snappy" } } }));
+        assert!(report.valid);
+    }
+
+    #[test]
+    fn test_booleans_match_string_toggles() {
+        // Hyprland themes saved before the inherit choice hold plain booleans
+        let report = validate(json!({
+            "hyprland": { "decoration": { "dim_inactive": true, "blur": { "enabled": false } } }
+        }));
+        assert!(report.valid, "{:?}", report.errors);
+        assert_eq!(
+            report.data["hyprland"]["decoration"]["dim_inactive"],
+            "true"
+        );
+        assert_eq!(
+            report.data["hyprland"]["decoration"]["blur"]["enabled"],
+            "false"
+        );
+    }
+
+    #[test]
+    fn test_unlisted_icon_theme_is_a_warning() {
+        let report = validate(json!({ "icons": { "theme_name": "Not-Installed-Anywhere" } }));
+        assert!(report.valid);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].path, "icons.theme_name");
+    }
+
+    #[test]
+    fn test_rejects_malformed_colors() {
+        let report = validate(json!({
+            "alacritty": { "colors": { "primary": { "background": "#12345g" } } },
+            "hyprlock": { "colors": { "color": "12,12,12,2.0" } },
+            "hyprland": { "general": { "active_border": "8A8A8D" } }
+        }));
+        let paths: Vec<&str> = report.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "alacritty.colors.primary.background",
+                "hyprlock.colors.color"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_allows_empty_color_when_default_is_empty() {
+        let report = validate(json!({
+            "btop": { "colors": { "basic": { "main_bg": "" } } }
+        }));
+        assert!(report.valid, "{:?}", report.errors);
+    }
+
+    #[test]
+    fn test_fills_defaults() {
+        let report = validate(json!({ "mako": {} }));
+        assert!(report.valid);
+        assert_eq!(
+            report.data["mako"]["colors"]["normal"]["background_color"],
+            json!("#1E1E1E")
+        );
+    }
+
+    #[test]
+    fn test_passes_through_unknown_apps() {
+        let report = validate(json!({ "kitty": { "anything": 1 } }));
+        assert!(report.valid);
+        assert_eq!(report.data["kitty"]["anything"], json!(1));
+    }
+
+    #[test]
+    fn test_rejects_non_object_data() {
+        let report = validate(json!([1, 2, 3]));
+        assert!(!report.valid);
+    }
+}
@@ -2,6 +2,7 @@
 pub mod cache;
 pub mod config;
 pub mod nvidia_detection;
+pub mod safe_path;
 pub mod settings;
 pub mod themes;
 
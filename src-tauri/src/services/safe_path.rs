@@ -0,0 +1,255 @@
+//! Confining names and paths sent by the webview to the directories they belong to.
+//!
+//! Theme files must stay inside `~/.config/omarchy/themes/<theme>` and imports must come
+//! from an allowed root. Checks canonicalize, so `..` and symlinks pointing elsewhere are
+//! caught as well as plain absolute paths.
+
+use std::path::{Component, Path, PathBuf};
+
+/// `~/.config/omarchy/themes`
+pub fn themes_root() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".config").join("omarchy").join("themes"))
+        .ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+/// Directories images may be imported from: the home directory, removable media and the
+/// system wallpaper folders
+pub fn import_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = dirs::home_dir().into_iter().collect();
+    roots.extend(
+        [
+            "/media",
+            "/mnt",
+            "/run/media",
+            "/usr/share/backgrounds",
+            "/usr/share/wallpapers",
+        ]
+        .map(PathBuf::from),
+    );
+    roots
+}
+
+/// `name` when it is a single plain file or directory name: no separators, `.` or `..`
+pub fn file_name(name: &str) -> Result<&str, String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.contains(['/', '\\', '\0']) => Ok(name),
+        _ => Err(format!("Invalid name '{name}'")),
+    }
+}
+
+/// Canonical form of `path`. A missing final component is allowed, so paths about to be
+/// created can be checked too.
+fn canonical(path: &Path) -> Result<PathBuf, String> {
+    if let Ok(resolved) = path.canonicalize() {
+        return Ok(resolved);
+    }
+    // A dangling symlink would be followed once something writes through it
+    if path.symlink_metadata().is_ok() {
+        return Err(format!("Path '{}' does not resolve", path.display()));
+    }
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(format!("Invalid path '{}'", path.display()));
+    };
+    parent
+        .canonicalize()
+        .map(|parent| parent.join(name))
+        .map_err(|e| format!("Invalid path '{}': {e}", path.display()))
+}
+
+/// `path` resolved, if it lies inside `root` once both are canonicalized
+pub fn contained_in(root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Invalid directory '{}': {e}", root.display()))?;
+    let resolved = canonical(path)?;
+    if resolved.starts_with(&root) && resolved != root {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "Path '{}' is outside of '{}'",
+            path.display(),
+            root.display()
+        ))
+    }
+}
+
+/// The entry `name` of `dir`, refusing names or symlinks that lead out of it
+pub fn child(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    contained_in(dir, &dir.join(file_name(name)?))
+}
+
+/// A theme's directory under `themes_dir`. Omarchy links its bundled themes into the themes
+/// folder, so the directory itself may be a symlink; only the name is checked here.
+pub fn theme_dir(themes_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    file_name(name).map(|name| themes_dir.join(name))
+}
+
+/// A file the webview asked to import, if it is an absolute path inside one of `roots`
+pub fn import_source(path: &str, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let requested = Path::new(path);
+    if !requested.is_absolute() {
+        return Err(format!("Path '{path}' is not absolute"));
+    }
+    let resolved = requested
+        .canonicalize()
+        .map_err(|_| format!("File '{path}' does not exist"))?;
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+    if allowed {
+        Ok(resolved)
+    } else {
+        Err(format!("Importing from '{path}' is not allowed"))
+    }
+}
+
+/// Where the webview asked to write an export: an absolute `.json` path inside one of `roots`
+pub fn export_target(path: &str, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let requested = Path::new(path);
+    if !requested.is_absolute() {
+        return Err(format!("Path '{path}' is not absolute"));
+    }
+    if requested.extension().map_or(true, |ext| ext != "json") {
+        return Err(format!("Export '{path}' must be a .json file"));
+    }
+    let resolved = canonical(requested)?;
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+    if allowed && !resolved.is_dir() {
+        Ok(resolved)
+    } else {
+        Err(format!("Exporting to '{path}' is not allowed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_name_rejects_traversal() {
+        assert_eq!(file_name("wall.png").unwrap(), "wall.png");
+        assert_eq!(file_name("..hidden.png").unwrap(), "..hidden.png");
+        for bad in [
+            "",
+            ".",
+            "..",
+            "../custom_theme.json",
+            "../../custom_theme.json",
+            "a/b.png",
+            "/etc/passwd",
+            "a\\b.png",
+            "a\0.png",
+        ] {
+            assert!(file_name(bad).is_err(), "{bad:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_child_stays_inside_directory() {
+        let theme = TempDir::new().unwrap();
+        let backgrounds = theme.path().join("backgrounds");
+        fs::create_dir(&backgrounds).unwrap();
+        fs::write(backgrounds.join("wall.png"), b"img").unwrap();
+        fs::write(theme.path().join("custom_theme.json"), b"{}").unwrap();
+
+        assert!(child(&backgrounds, "wall.png").is_ok());
+        assert!(child(&backgrounds, "new.png").is_ok());
+        assert!(child(&backgrounds, "../custom_theme.json").is_err());
+        assert!(contained_in(&backgrounds, &backgrounds.join("../custom_theme.json")).is_err());
+        assert!(contained_in(&backgrounds, &backgrounds).is_err());
+    }
+
+    #[test]
+    fn test_symlinks_out_of_directory_are_rejected() {
+        let theme = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let backgrounds = theme.path().join("backgrounds");
+        fs::create_dir(&backgrounds).unwrap();
+        fs::write(outside.path().join("secret"), b"secret").unwrap();
+
+        symlink(outside.path().join("secret"), backgrounds.join("link.png")).unwrap();
+        symlink(outside.path(), backgrounds.join("dir")).unwrap();
+        symlink(
+            backgrounds.join("missing"),
+            backgrounds.join("dangling.png"),
+        )
+        .unwrap();
+        fs::write(backgrounds.join("inside.png"), b"img").unwrap();
+        symlink(
+            backgrounds.join("inside.png"),
+            backgrounds.join("alias.png"),
+        )
+        .unwrap();
+
+        assert!(child(&backgrounds, "link.png").is_err());
+        assert!(contained_in(&backgrounds, &backgrounds.join("dir/secret")).is_err());
+        assert!(child(&backgrounds, "dangling.png").is_err());
+        assert!(child(&backgrounds, "alias.png").is_ok());
+    }
+
+    #[test]
+    fn test_theme_dir_may_be_a_symlink() {
+        let themes = TempDir::new().unwrap();
+        let bundled = TempDir::new().unwrap();
+        symlink(bundled.path(), themes.path().join("tokyo-night")).unwrap();
+
+        let dir = theme_dir(themes.path(), "tokyo-night").unwrap();
+        assert_eq!(dir, themes.path().join("tokyo-night"));
+        assert!(theme_dir(themes.path(), "").is_err());
+        assert!(theme_dir(themes.path(), "..").is_err());
+    }
+
+    #[test]
+    fn test_import_source_roots() {
+        let root = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+        fs::write(root.path().join("wall.png"), b"img").unwrap();
+        fs::write(elsewhere.path().join("wall.png"), b"img").unwrap();
+        symlink(
+            elsewhere.path().join("wall.png"),
+            root.path().join("link.png"),
+        )
+        .unwrap();
+        let roots = vec![root.path().to_path_buf()];
+
+        let inside = root.path().join("wall.png");
+        assert!(import_source(inside.to_str().unwrap(), &roots).is_ok());
+        let escaped = root
+            .path()
+            .join("..")
+            .join(elsewhere.path().file_name().unwrap())
+            .join("wall.png");
+        assert!(import_source(escaped.to_str().unwrap(), &roots).is_err());
+        let link = root.path().join("link.png");
+        assert!(import_source(link.to_str().unwrap(), &roots).is_err());
+        assert!(import_source("wall.png", &roots).is_err());
+        let missing = root.path().join("missing.png");
+        assert!(import_source(missing.to_str().unwrap(), &roots).is_err());
+    }
+
+    #[test]
+    fn test_export_target() {
+        let root = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+        let roots = vec![root.path().to_path_buf()];
+
+        let new_file = root.path().join("themes.json");
+        assert!(export_target(new_file.to_str().unwrap(), &roots).is_ok());
+        assert!(export_target("themes.json", &roots).is_err());
+        let not_json = root.path().join(".bashrc");
+        assert!(export_target(not_json.to_str().unwrap(), &roots).is_err());
+        let outside = elsewhere.path().join("themes.json");
+        assert!(export_target(outside.to_str().unwrap(), &roots).is_err());
+        let missing_dir = root.path().join("missing").join("themes.json");
+        assert!(export_target(missing_dir.to_str().unwrap(), &roots).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::services::themes::schedule;
 use crate::types::{AppSettings, SettingsError, SettingsFile, SettingsMetadata, SettingsResult};
 use chrono::Utc;
 use std::path::PathBuf;
@@ -36,19 +37,27 @@ async fn load_default_settings_from_resources(
     }
 }
 
+/// The user's config directory, `$XDG_CONFIG_HOME` or `~/.config`
+pub fn config_home() -> Option<PathBuf> {
+    // Check for XDG_CONFIG_HOME first, then fall back to dirs::config_dir() for Arch Linux
+    if let Ok(xdg_config_home) = std::env::var("XDG_CONFIG_HOME") {
+        Some(PathBuf::from(xdg_config_home))
+    } else {
+        dirs::config_dir()
+    }
+}
+
+/// `~/.config/omarchist`, honoring `XDG_CONFIG_HOME`; other app state files live here too
+pub fn app_config_dir() -> Option<PathBuf> {
+    config_home().map(|dir| dir.join("omarchist"))
+}
+
 /// Get the path to the settings file with optional directory override (for testing)
 fn get_settings_file_path_with_override(override_dir: Option<PathBuf>) -> SettingsResult<PathBuf> {
     let app_data_dir = if let Some(dir) = override_dir {
         dir.join("omarchist")
     } else {
-        // Check for XDG_CONFIG_HOME first, then fall back to dirs::config_dir() for Arch Linux
-        if let Ok(xdg_config_home) = std::env::var("XDG_CONFIG_HOME") {
-            PathBuf::from(xdg_config_home).join("omarchist")
-        } else {
-            dirs::config_dir()
-                .ok_or(SettingsError::AppDataDir)?
-                .join("omarchist")
-        }
+        app_config_dir().ok_or(SettingsError::AppDataDir)?
     };
 
     // Create the directory if it doesn't exist
@@ -230,6 +239,16 @@ async fn load_settings_with_override(
     }
 }
 
+/// Read the saved settings without creating or repairing the file, for CLI subcommands
+/// that run before Tauri starts
+pub fn read_saved_settings() -> Option<AppSettings> {
+    let settings_path = get_settings_file_path_with_override(None).ok()?;
+    let content = fs::read_to_string(settings_path).ok()?;
+    serde_json::from_str::<SettingsFile>(&content)
+        .map(|file| file.settings)
+        .ok()
+}
+
 /// Save settings to file with atomic write
 pub async fn save_settings(settings: AppSettings) -> SettingsResult<()> {
     save_settings_with_override(settings, None).await
@@ -256,11 +275,14 @@ async fn save_settings_with_override(
         },
     };
 
-    // If file exists, preserve creation time
+    // If file exists, preserve creation time and, unless replaced, the schedule
     if settings_path.exists() {
         if let Ok(existing_content) = fs::read_to_string(&settings_path) {
             if let Ok(existing_file) = serde_json::from_str::<SettingsFile>(&existing_content) {
                 settings_file.metadata.created_at = existing_file.metadata.created_at;
+                if settings_file.settings.schedule.is_none() {
+                    settings_file.settings.schedule = existing_file.settings.schedule;
+                }
             }
         }
     }
@@ -307,6 +329,10 @@ pub fn validate_settings(settings: &AppSettings) -> SettingsResult<()> {
         settings.auto_apply_theme
     );
 
+    if let Some(schedule) = &settings.schedule {
+        schedule::validate_schedule(schedule).map_err(SettingsError::Validation)?;
+    }
+
     // All validations passed
     log::debug!("Settings validation completed successfully");
     Ok(())
@@ -319,6 +345,13 @@ pub fn validate_and_sanitize_settings(settings: AppSettings) -> SettingsResult<A
     // Create a sanitized copy of the settings
     let sanitized_settings = AppSettings {
         auto_apply_theme: settings.auto_apply_theme, // Boolean is already safe
+        schedule: settings.schedule.map(|mut schedule| {
+            for rule in &mut schedule.rules {
+                rule.at = rule.at.trim().to_string();
+                rule.theme = rule.theme.trim().to_string();
+            }
+            schedule
+        }),
     };
 
     // Validate the sanitized settings
@@ -407,6 +440,7 @@ mod tests {
     fn test_app_settings_serialization() {
         let settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -431,11 +465,13 @@ mod tests {
     fn test_validate_settings_valid_cases() {
         let settings_true = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
         assert!(validate_settings(&settings_true).is_ok());
 
         let settings_false = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
         assert!(validate_settings(&settings_false).is_ok());
     }
@@ -444,6 +480,7 @@ mod tests {
     fn test_validate_and_sanitize_settings() {
         let settings = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
         let result = validate_and_sanitize_settings(settings.clone());
         assert!(result.is_ok());
@@ -451,6 +488,7 @@ mod tests {
 
         let settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
         let result = validate_and_sanitize_settings(settings.clone());
         assert!(result.is_ok());
@@ -474,6 +512,7 @@ mod tests {
 
         let test_settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
 
         // Save settings
@@ -496,6 +535,7 @@ mod tests {
 
         let test_settings = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
 
         // Save should create the directory
@@ -516,6 +556,7 @@ mod tests {
 
         let test_settings = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
 
         // Save settings
@@ -543,6 +584,7 @@ mod tests {
 
         let initial_settings = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
 
         // Save initial settings
@@ -562,6 +604,7 @@ mod tests {
         // Save updated settings
         let updated_settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
         save_settings_with_override(updated_settings, Some(temp_dir.path().to_path_buf()))
             .await
@@ -575,6 +618,64 @@ mod tests {
         assert!(updated_file.metadata.last_modified > creation_time);
     }
 
+    #[tokio::test]
+    async fn test_save_settings_keeps_schedule_when_omitted() {
+        let temp_dir = TempDir::new().unwrap();
+        let schedule = crate::types::ThemeSchedule {
+            enabled: true,
+            latitude: None,
+            longitude: None,
+            rules: vec![crate::types::ScheduleRule {
+                at: " 07:30 ".to_string(),
+                theme: "catppuccin-latte".to_string(),
+                variant: None,
+            }],
+        };
+        let with_schedule = AppSettings {
+            auto_apply_theme: true,
+            schedule: Some(schedule),
+        };
+        save_settings_with_override(with_schedule, Some(temp_dir.path().to_path_buf()))
+            .await
+            .unwrap();
+
+        // The settings page only sends auto_apply_theme
+        let without_schedule = AppSettings {
+            auto_apply_theme: false,
+            schedule: None,
+        };
+        save_settings_with_override(without_schedule, Some(temp_dir.path().to_path_buf()))
+            .await
+            .unwrap();
+
+        let loaded = load_settings_test_only(Some(temp_dir.path().to_path_buf()))
+            .await
+            .unwrap();
+        assert!(!loaded.auto_apply_theme);
+        assert_eq!(loaded.schedule.unwrap().rules[0].at, "07:30");
+    }
+
+    #[test]
+    fn test_validate_settings_rejects_bad_schedule() {
+        let settings = AppSettings {
+            auto_apply_theme: true,
+            schedule: Some(crate::types::ThemeSchedule {
+                enabled: true,
+                latitude: None,
+                longitude: None,
+                rules: vec![crate::types::ScheduleRule {
+                    at: "sunset".to_string(),
+                    theme: "tokyo-night".to_string(),
+                    variant: None,
+                }],
+            }),
+        };
+        assert!(matches!(
+            validate_settings(&settings),
+            Err(SettingsError::Validation(_))
+        ));
+    }
+
     // Error handling tests
     #[tokio::test]
     async fn test_load_corrupted_json() {
@@ -646,6 +747,7 @@ mod tests {
 
             let test_settings = AppSettings {
                 auto_apply_theme: true,
+                schedule: None,
             };
 
             // Save should fail
@@ -677,6 +779,7 @@ mod tests {
         let settings_file = settings_dir.join("settings.json");
         let legacy_settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
         let legacy_json = serde_json::to_string_pretty(&legacy_settings).unwrap();
         fs::write(&settings_file, legacy_json).unwrap();
@@ -702,6 +805,7 @@ mod tests {
             version: "2.0.0".to_string(),
             settings: AppSettings {
                 auto_apply_theme: false,
+                schedule: None,
             },
             metadata: SettingsMetadata {
                 created_at: Utc::now(),
@@ -725,9 +829,11 @@ mod tests {
 
         let settings1 = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
         let settings2 = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
 
         // Start multiple saves concurrently
@@ -756,6 +862,7 @@ mod tests {
         // Save settings and verify the file format
         let test_settings = AppSettings {
             auto_apply_theme: true,
+            schedule: None,
         };
 
         save_settings_with_override(test_settings.clone(), Some(temp_dir.path().to_path_buf()))
@@ -0,0 +1,437 @@
+//! Copying images into a theme's `backgrounds/`: content checks, dedup and safe names.
+
+use super::backgrounds::{self, BACKGROUNDS_DIR};
+use crate::services::safe_path;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Largest file accepted as a background
+pub const MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+/// Largest width or height accepted; beyond this wallpaper daemons tend to run out of memory
+pub const MAX_DIMENSION: u32 = 16384;
+/// Enough of the file to find the dimensions of every format but JPEG, whose frame header
+/// can follow metadata segments of any size
+const HEADER_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+    Webp,
+}
+
+/// Detect the image format from the first bytes of a file
+pub fn sniff_format(header: &[u8]) -> Option<ImageFormat> {
+    match header {
+        [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, ..] => Some(ImageFormat::Png),
+        [0xff, 0xd8, 0xff, ..] => Some(ImageFormat::Jpeg),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+        [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(ImageFormat::Webp),
+        _ => None,
+    }
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?).into())
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?).into())
+}
+
+fn u24_le(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 3)?;
+    Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn i32_le(bytes: &[u8], at: usize) -> Option<i32> {
+    Some(i32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn read_byte(reader: &mut impl Read) -> Option<u8> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte).ok()?;
+    Some(byte[0])
+}
+
+/// JPEG dimensions come from the first start-of-frame segment. Other segments are skipped
+/// by their length, so large EXIF or ICC blocks are never read.
+fn jpeg_dimensions(reader: &mut (impl Read + Seek)) -> Option<(u32, u32)> {
+    reader.seek(SeekFrom::Start(2)).ok()?;
+    loop {
+        while read_byte(reader)? != 0xff {}
+        let mut marker = read_byte(reader)?;
+        while marker == 0xff {
+            marker = read_byte(reader)?;
+        }
+        match marker {
+            // Markers without a length
+            0xd0..=0xd9 | 0x01 => continue,
+            // SOF0..SOF15, except DHT, JPG and DAC
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                let mut frame = [0; 7];
+                reader.read_exact(&mut frame).ok()?;
+                return Some((u16_be(&frame, 5)?, u16_be(&frame, 3)?));
+            },
+            _ => {
+                let mut length = [0; 2];
+                reader.read_exact(&mut length).ok()?;
+                let skip = i64::from(u16_be(&length, 0)?.checked_sub(2)?);
+                reader.seek(SeekFrom::Current(skip)).ok()?;
+            },
+        }
+    }
+}
+
+fn webp_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    match bytes.get(12..16)? {
+        b"VP8 " => Some((u16_le(bytes, 26)? & 0x3fff, u16_le(bytes, 28)? & 0x3fff)),
+        b"VP8L" => {
+            let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        },
+        b"VP8X" => Some((u24_le(bytes, 24)? + 1, u24_le(bytes, 27)? + 1)),
+        _ => None,
+    }
+}
+
+/// Width and height from the image header
+pub fn dimensions(format: ImageFormat, bytes: &[u8]) -> Option<(u32, u32)> {
+    match format {
+        ImageFormat::Png => Some((u32_be(bytes, 16)?, u32_be(bytes, 20)?)),
+        ImageFormat::Jpeg => jpeg_dimensions(&mut Cursor::new(bytes)),
+        ImageFormat::Gif => Some((u16_le(bytes, 6)?, u16_le(bytes, 8)?)),
+        // Negative heights mark top-down bitmaps
+        ImageFormat::Bmp => Some((
+            i32_le(bytes, 18)?.unsigned_abs(),
+            i32_le(bytes, 22)?.unsigned_abs(),
+        )),
+        ImageFormat::Webp => webp_dimensions(bytes),
+    }
+}
+
+/// Check that `path` is an image wallpaper daemons can load within the size limits
+pub fn validate_image(path: &Path) -> Result<(), String> {
+    if !backgrounds::is_image(path) {
+        return Err("unsupported file extension".to_string());
+    }
+    let size = fs::metadata(path)
+        .map_err(|e| format!("cannot read file: {e}"))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!(
+            "file is {} MiB, the limit is {} MiB",
+            size / (1024 * 1024),
+            MAX_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let mut file = fs::File::open(path).map_err(|e| format!("cannot read file: {e}"))?;
+    let mut header = Vec::new();
+    (&mut file)
+        .take(HEADER_BYTES)
+        .read_to_end(&mut header)
+        .map_err(|e| format!("cannot read file: {e}"))?;
+    let format = sniff_format(&header).ok_or("content is not a supported image")?;
+    let dimensions = match format {
+        ImageFormat::Jpeg => jpeg_dimensions(&mut BufReader::new(file)),
+        _ => dimensions(format, &header),
+    };
+    let (width, height) = dimensions.ok_or("cannot read image dimensions")?;
+    if width == 0 || height == 0 {
+        return Err("image has no pixels".to_string());
+    }
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(format!(
+            "image is {width}x{height}, the limit is {MAX_DIMENSION} pixels per side"
+        ));
+    }
+    Ok(())
+}
+
+/// Hex SHA-256 of a file's contents
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("cannot read file: {e}"))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("cannot read file: {e}"))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// `name`, or `name-1`, `name-2`, ... with the same extension, whichever is free in `dir`
+pub fn free_file_name(dir: &Path, name: &str) -> String {
+    if !dir.join(name).exists() {
+        return name.to_string();
+    }
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| format!("{stem}-{n}{extension}"))
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap_or_default()
+}
+
+/// What happened to one source file
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum BackgroundImport {
+    Copied {
+        source: String,
+        filename: String,
+    },
+    /// Same content as a background the theme already has
+    Duplicate {
+        source: String,
+        existing: String,
+    },
+    Rejected {
+        source: String,
+        reason: String,
+    },
+}
+
+/// Copy images from inside `roots` into `theme_dir/backgrounds`, skipping duplicates and
+/// non-images
+pub fn import_backgrounds(
+    theme_dir: &Path,
+    source_paths: Vec<String>,
+    roots: &[PathBuf],
+) -> Result<Vec<BackgroundImport>, String> {
+    let backgrounds_dir = theme_dir.join(BACKGROUNDS_DIR);
+    fs::create_dir_all(&backgrounds_dir)
+        .map_err(|e| format!("Failed to create backgrounds directory: {e}"))?;
+
+    let mut known: HashMap<String, String> = HashMap::new();
+    for existing in backgrounds::image_files(theme_dir)? {
+        match sha256_file(&backgrounds_dir.join(&existing)) {
+            Ok(hash) => {
+                known.insert(hash, existing);
+            },
+            Err(e) => log::warn!("Skipping {existing} for duplicate detection: {e}"),
+        }
+    }
+
+    let mut results = Vec::new();
+    for source in source_paths {
+        let result = safe_path::import_source(&source, roots)
+            .and_then(|path| import_one(&backgrounds_dir, &path, &mut known));
+        results.push(match result {
+            Ok(Ok(filename)) => {
+                log::debug!("Copied background image: {source} -> {filename}");
+                BackgroundImport::Copied { source, filename }
+            },
+            Ok(Err(existing)) => BackgroundImport::Duplicate { source, existing },
+            Err(reason) => {
+                log::warn!("Rejected background image {source}: {reason}");
+                BackgroundImport::Rejected { source, reason }
+            },
+        });
+    }
+    Ok(results)
+}
+
+/// Copy one file; `Ok(Err(existing))` when its content is already present
+fn import_one(
+    backgrounds_dir: &Path,
+    source: &Path,
+    known: &mut HashMap<String, String>,
+) -> Result<Result<String, String>, String> {
+    if !source.is_file() {
+        return Err("not a file".to_string());
+    }
+    validate_image(source)?;
+
+    let hash = sha256_file(source)?;
+    if let Some(existing) = known.get(&hash) {
+        return Ok(Err(existing.clone()));
+    }
+
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("file has no name")?;
+    let filename = free_file_name(backgrounds_dir, &name);
+    let destination: PathBuf = backgrounds_dir.join(&filename);
+    fs::copy(source, &destination).map_err(|e| format!("copy failed: {e}"))?;
+
+    known.insert(hash, filename.clone());
+    Ok(Ok(filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Smallest header that passes for a PNG of the given size
+    fn png(width: u32, height: u32, seed: u8) -> Vec<u8> {
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        bytes.extend_from_slice(&13u32.to_be_bytes());
+        bytes.extend_from_slice(b"IHDR");
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 2, 0, 0, 0, seed]);
+        bytes
+    }
+
+    fn jpeg(width: u16, height: u16) -> Vec<u8> {
+        let mut bytes = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00];
+        bytes.extend_from_slice(&[0xff, 0xc0, 0x00, 0x11, 0x08]);
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&[0x03; 12]);
+        bytes
+    }
+
+    #[test]
+    fn test_sniff_and_dimensions() {
+        let bytes = png(1920, 1080, 0);
+        let format = sniff_format(&bytes).unwrap();
+        assert_eq!(format, ImageFormat::Png);
+        assert_eq!(dimensions(format, &bytes), Some((1920, 1080)));
+
+        let bytes = jpeg(3840, 2160);
+        assert_eq!(sniff_format(&bytes), Some(ImageFormat::Jpeg));
+        assert_eq!(dimensions(ImageFormat::Jpeg, &bytes), Some((3840, 2160)));
+
+        let gif = b"GIF89a\x80\x02\xe0\x01".to_vec();
+        assert_eq!(
+            dimensions(sniff_format(&gif).unwrap(), &gif),
+            Some((640, 480))
+        );
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X".to_vec();
+        webp.extend_from_slice(&[0; 8]);
+        webp.extend_from_slice(&[0x7f, 0x07, 0x00, 0x37, 0x04, 0x00]);
+        assert_eq!(
+            dimensions(sniff_format(&webp).unwrap(), &webp),
+            Some((1920, 1080))
+        );
+
+        assert_eq!(sniff_format(b"#!/bin/sh\n"), None);
+    }
+
+    #[test]
+    fn test_jpeg_with_large_metadata_segments() {
+        // Two full-size APP1 (EXIF) segments push the frame header past the first 64 KiB
+        let mut bytes = vec![0xff, 0xd8];
+        for _ in 0..2 {
+            bytes.extend_from_slice(&[0xff, 0xe1, 0xff, 0xff]);
+            bytes.extend(std::iter::repeat(0xff).take(0xfffd));
+        }
+        bytes.extend_from_slice(&jpeg(4000, 3000)[2..]);
+        assert!(bytes.len() as u64 > 2 * HEADER_BYTES);
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("camera.jpg");
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(validate_image(&path), Ok(()));
+        assert_eq!(dimensions(ImageFormat::Jpeg, &bytes), Some((4000, 3000)));
+    }
+
+    #[test]
+    fn test_free_file_name() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(free_file_name(dir.path(), "a.png"), "a.png");
+        fs::write(dir.path().join("a.png"), b"").unwrap();
+        fs::write(dir.path().join("a-1.png"), b"").unwrap();
+        assert_eq!(free_file_name(dir.path(), "a.png"), "a-2.png");
+    }
+
+    #[test]
+    fn test_import_reports_each_source() {
+        let theme = TempDir::new().unwrap();
+        let sources = TempDir::new().unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = sources.path().join(name);
+            fs::write(&path, bytes).unwrap();
+            path.to_string_lossy().to_string()
+        };
+
+        fs::create_dir(theme.path().join(BACKGROUNDS_DIR)).unwrap();
+        fs::write(
+            theme.path().join(BACKGROUNDS_DIR).join("wall.png"),
+            png(100, 100, 1),
+        )
+        .unwrap();
+
+        let same_content = write("copy.png", &png(100, 100, 1));
+        let name_clash = write("wall.png", &png(200, 100, 2));
+        let twice = write("other.jpg", &jpeg(800, 600));
+        let renamed_script = write("evil.png", b"#!/bin/sh\nrm -rf ~\n");
+        let huge = write("huge.png", &png(MAX_DIMENSION + 1, 10, 3));
+        let outside_root = theme
+            .path()
+            .join(BACKGROUNDS_DIR)
+            .join("wall.png")
+            .to_string_lossy()
+            .to_string();
+
+        let results = import_backgrounds(
+            theme.path(),
+            vec![
+                same_content.clone(),
+                name_clash.clone(),
+                twice.clone(),
+                twice.clone(),
+                renamed_script.clone(),
+                huge.clone(),
+                outside_root,
+            ],
+            &[sources.path().to_path_buf()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            results[0],
+            BackgroundImport::Duplicate {
+                source: same_content,
+                existing: "wall.png".into()
+            }
+        );
+        assert_eq!(
+            results[1],
+            BackgroundImport::Copied {
+                source: name_clash,
+                filename: "wall-1.png".into()
+            }
+        );
+        assert!(
+            matches!(&results[2], BackgroundImport::Copied { filename, .. } if filename == "other.jpg")
+        );
+        assert!(
+            matches!(&results[3], BackgroundImport::Duplicate { existing, .. } if existing == "other.jpg")
+        );
+        assert!(
+            matches!(&results[4], BackgroundImport::Rejected { reason, .. } if reason.contains("not a supported image"))
+        );
+        assert!(
+            matches!(&results[5], BackgroundImport::Rejected { reason, .. } if reason.contains("limit"))
+        );
+        assert!(
+            matches!(&results[6], BackgroundImport::Rejected { reason, .. } if reason.contains("not allowed"))
+        );
+
+        assert_eq!(
+            backgrounds::image_files(theme.path()).unwrap(),
+            vec!["other.jpg", "wall-1.png", "wall.png"]
+        );
+    }
+}
@@ -0,0 +1,447 @@
+//! Background playlists: ordering, the active background and timed rotation.
+//!
+//! The playlist is stored in `custom_theme.json`. Only the theme `current/theme` points at
+//! shows its background right away; other themes keep their choice for the next apply.
+
+use super::custom_themes::CustomThemeService;
+use super::desktop_links;
+use crate::services::safe_path::contained_in;
+use crate::types::BackgroundPlaylist;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+pub const BACKGROUNDS_DIR: &str = "backgrounds";
+const IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "webp", "bmp", "gif"];
+/// How often the rotation timer checks whether the current theme is due a new background
+const ROTATION_TICK: Duration = Duration::from_secs(30);
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Image file names in a theme's `backgrounds/`, alphabetically
+pub fn image_files(theme_dir: &Path) -> Result<Vec<String>, String> {
+    let backgrounds_dir = theme_dir.join(BACKGROUNDS_DIR);
+    if !backgrounds_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&backgrounds_dir)
+        .map_err(|e| format!("Failed to read backgrounds directory: {e}"))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read directory entry: {e}"))?
+            .path();
+        // Symlinks leading out of the theme are not offered as backgrounds
+        if path.is_file() && is_image(&path) && contained_in(&backgrounds_dir, &path).is_ok() {
+            if let Some(filename) = path.file_name() {
+                files.push(filename.to_string_lossy().to_string());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `files` in playlist order: listed files first, the rest alphabetically after them
+pub fn ordered(playlist: Option<&BackgroundPlaylist>, files: Vec<String>) -> Vec<String> {
+    let order = playlist.map(|p| p.order.as_slice()).unwrap_or_default();
+    let mut result: Vec<String> = order
+        .iter()
+        .filter(|name| files.contains(name))
+        .cloned()
+        .collect();
+    result.extend(files.into_iter().filter(|name| !order.contains(name)));
+    result
+}
+
+/// The background `delta` places away from `active`, wrapping around. Without a valid active
+/// background the first one counts as active, since that is what Omarchy shows.
+pub fn step(files: &[String], active: Option<&str>, delta: isize) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+    let current = active
+        .and_then(|name| files.iter().position(|f| f == name))
+        .unwrap_or(0);
+    let index = (current as isize + delta).rem_euclid(files.len() as isize) as usize;
+    Some(files[index].clone())
+}
+
+/// The playlist with `order` listing every background and `active` only when it still exists
+pub fn playlist(theme_dir: &Path) -> Result<BackgroundPlaylist, String> {
+    let theme = CustomThemeService::read_metadata(theme_dir)?;
+    let stored = theme.backgrounds.unwrap_or_default();
+    let order = ordered(Some(&stored), image_files(theme_dir)?);
+    let active = stored.active.filter(|name| order.contains(name));
+    Ok(BackgroundPlaylist {
+        order,
+        active,
+        rotate_minutes: stored.rotate_minutes,
+    })
+}
+
+/// Load, change and save a theme's playlist
+fn update_playlist(
+    theme_dir: &Path,
+    change: impl FnOnce(&mut BackgroundPlaylist) -> Result<(), String>,
+) -> Result<BackgroundPlaylist, String> {
+    let mut updated = playlist(theme_dir)?;
+    change(&mut updated)?;
+
+    let mut theme = CustomThemeService::read_metadata(theme_dir)?;
+    theme.backgrounds = Some(updated.clone()).filter(|p| *p != BackgroundPlaylist::default());
+    theme.modified_at = chrono::Utc::now().to_rfc3339();
+    CustomThemeService::write_metadata(theme_dir, &theme)?;
+    Ok(updated)
+}
+
+pub fn reorder(theme_dir: &Path, order: Vec<String>) -> Result<BackgroundPlaylist, String> {
+    update_playlist(theme_dir, |playlist| {
+        if let Some(unknown) = order.iter().find(|name| !playlist.order.contains(name)) {
+            return Err(format!("Background image '{unknown}' not found"));
+        }
+        playlist.order = ordered(
+            Some(&BackgroundPlaylist {
+                order,
+                ..Default::default()
+            }),
+            std::mem::take(&mut playlist.order),
+        );
+        Ok(())
+    })
+}
+
+/// Make `filename` the active background and show it if the theme is applied
+pub fn set_active(theme_dir: &Path, filename: &str) -> Result<BackgroundPlaylist, String> {
+    let updated = update_playlist(theme_dir, |playlist| {
+        if !playlist.order.iter().any(|name| name == filename) {
+            return Err(format!("Background image '{filename}' not found"));
+        }
+        playlist.active = Some(filename.to_string());
+        Ok(())
+    })?;
+    if desktop_links::is_current_theme(theme_dir) {
+        show(&theme_dir.join(BACKGROUNDS_DIR).join(filename))?;
+    }
+    Ok(updated)
+}
+
+/// Move the active background `delta` places through the playlist
+pub fn step_active(theme_dir: &Path, delta: isize) -> Result<BackgroundPlaylist, String> {
+    let current = playlist(theme_dir)?;
+    let next = step(&current.order, current.active.as_deref(), delta)
+        .ok_or_else(|| "Theme has no background images".to_string())?;
+    set_active(theme_dir, &next)
+}
+
+pub fn set_rotation(theme_dir: &Path, minutes: u32) -> Result<BackgroundPlaylist, String> {
+    update_playlist(theme_dir, |playlist| {
+        playlist.rotate_minutes = minutes;
+        Ok(())
+    })
+}
+
+/// Drop a deleted background from the playlist
+pub fn forget(theme_dir: &Path, filename: &str) -> Result<(), String> {
+    if !theme_dir.join("custom_theme.json").exists() {
+        return Ok(());
+    }
+    update_playlist(theme_dir, |playlist| {
+        playlist.order.retain(|name| name != filename);
+        if playlist.active.as_deref() == Some(filename) {
+            playlist.active = None;
+        }
+        Ok(())
+    })
+    .map(|_| ())
+}
+
+/// Something that can put an image on the desktop
+pub trait WallpaperSetter: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Whether this setter drives the wallpaper of the running session
+    fn is_active(&self) -> bool;
+    fn set(&self, image: &Path) -> Result<(), String>;
+}
+
+fn process_running(name: &str) -> bool {
+    Command::new("pgrep")
+        .args(["-x", name])
+        .stdout(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Omarchy's default: one swaybg process showing `current/background`
+pub struct Swaybg;
+
+/// The swaybg this app started, so it can be reaped when replaced
+static SWAYBG: Mutex<Option<Child>> = Mutex::new(None);
+
+impl WallpaperSetter for Swaybg {
+    fn name(&self) -> &'static str {
+        "swaybg"
+    }
+
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    fn set(&self, image: &Path) -> Result<(), String> {
+        let mut running = SWAYBG.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(mut previous) = running.take() {
+            let _ = previous.kill();
+            let _ = previous.wait();
+        }
+        // Any swaybg started by Omarchy or an earlier run of the app
+        let _ = Command::new("pkill").args(["-x", "swaybg"]).status();
+        let child = Command::new("setsid")
+            .arg("swaybg")
+            .arg("-i")
+            .arg(image)
+            .args(["-m", "fill"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start swaybg: {e}"))?;
+        *running = Some(child);
+        Ok(())
+    }
+}
+
+pub struct Hyprpaper;
+
+impl WallpaperSetter for Hyprpaper {
+    fn name(&self) -> &'static str {
+        "hyprpaper"
+    }
+
+    fn is_active(&self) -> bool {
+        process_running("hyprpaper")
+    }
+
+    fn set(&self, image: &Path) -> Result<(), String> {
+        let image = image.to_string_lossy();
+        for args in [
+            vec!["preload", image.as_ref()],
+            vec!["wallpaper", &format!(",{image}")],
+            vec!["unload", "unused"],
+        ] {
+            let output = Command::new("hyprctl")
+                .arg("hyprpaper")
+                .args(&args)
+                .output()
+                .map_err(|e| format!("Failed to run hyprctl: {e}"))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "hyprctl hyprpaper {} failed: {}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The setter for this session; swaybg unless hyprpaper is running
+pub fn wallpaper_setter() -> Box<dyn WallpaperSetter> {
+    let setters: Vec<Box<dyn WallpaperSetter>> = vec![Box::new(Hyprpaper), Box::new(Swaybg)];
+    setters
+        .into_iter()
+        .find(|setter| setter.is_active())
+        .unwrap_or_else(|| Box::new(Swaybg))
+}
+
+fn current_background_link() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/omarchy/current/background"))
+}
+
+/// Point `current/background` at `image`, as Omarchy's own background scripts do
+fn link_current_background(link: &Path, image: &Path) -> Result<(), String> {
+    if link.symlink_metadata().is_ok() {
+        fs::remove_file(link).map_err(|e| format!("Failed to replace {}: {e}", link.display()))?;
+    }
+    std::os::unix::fs::symlink(image, link)
+        .map_err(|e| format!("Failed to link {}: {e}", link.display()))
+}
+
+/// Put `image` on the desktop
+pub fn show(image: &Path) -> Result<(), String> {
+    let setter = wallpaper_setter();
+    let target = match current_background_link() {
+        Some(link) => {
+            link_current_background(&link, image)?;
+            // hyprpaper caches by path, so it gets the image itself
+            if setter.name() == "swaybg" {
+                link
+            } else {
+                image.to_path_buf()
+            }
+        },
+        None => image.to_path_buf(),
+    };
+    log::info!(
+        "Setting background {} via {}",
+        image.display(),
+        setter.name()
+    );
+    setter.set(&target)
+}
+
+/// After `omarchy-theme-set`, replace its default background with the theme's active one
+pub fn show_current_active() {
+    let Some(theme_dir) = desktop_links::current_theme_dir() else {
+        return;
+    };
+    let Ok(Some(active)) = playlist(&theme_dir).map(|p| p.active) else {
+        return;
+    };
+    if let Err(e) = show(&theme_dir.join(BACKGROUNDS_DIR).join(active)) {
+        log::warn!("Failed to show the theme's active background: {e}");
+    }
+}
+
+/// Which theme the rotation timer is counting for and since when
+type RotationClock = Option<(PathBuf, Instant)>;
+
+fn rotation_tick(clock: &mut RotationClock) {
+    let Some(theme_dir) = desktop_links::current_theme_dir().and_then(|d| fs::canonicalize(d).ok())
+    else {
+        *clock = None;
+        return;
+    };
+    let minutes = playlist(&theme_dir).map_or(0, |p| p.rotate_minutes);
+    if minutes == 0 {
+        *clock = None;
+        return;
+    }
+
+    match clock {
+        Some((dir, since)) if *dir == theme_dir => {
+            if since.elapsed() >= Duration::from_secs(u64::from(minutes) * 60) {
+                if let Err(e) = step_active(&theme_dir, 1) {
+                    log::warn!("Background rotation failed: {e}");
+                }
+                *since = Instant::now();
+            }
+        },
+        _ => *clock = Some((theme_dir, Instant::now())),
+    }
+}
+
+/// Rotate the current theme's backgrounds while the app is running
+pub fn start_rotation() {
+    tauri::async_runtime::spawn(async {
+        let mut clock = None;
+        loop {
+            tokio::time::sleep(ROTATION_TICK).await;
+            rotation_tick(&mut clock);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CustomTheme;
+    use tempfile::TempDir;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn theme_with_backgrounds(files: &[&str]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(BACKGROUNDS_DIR)).unwrap();
+        for file in files {
+            fs::write(dir.path().join(BACKGROUNDS_DIR).join(file), b"img").unwrap();
+        }
+        let theme = CustomTheme {
+            name: "Test".into(),
+            created_at: String::new(),
+            modified_at: String::new(),
+            apps: serde_json::json!({}),
+            colors: None,
+            light_apps: None,
+            backgrounds: None,
+        };
+        CustomThemeService::write_metadata(dir.path(), &theme).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_ordered_puts_unlisted_files_last() {
+        let playlist = BackgroundPlaylist {
+            order: names(&["c.png", "gone.png", "a.png"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            ordered(Some(&playlist), names(&["a.png", "b.png", "c.png"])),
+            names(&["c.png", "a.png", "b.png"])
+        );
+        assert_eq!(ordered(None, names(&["a.png"])), names(&["a.png"]));
+    }
+
+    #[test]
+    fn test_step_wraps_around() {
+        let files = names(&["a.png", "b.png", "c.png"]);
+        assert_eq!(step(&files, Some("c.png"), 1).unwrap(), "a.png");
+        assert_eq!(step(&files, Some("a.png"), -1).unwrap(), "c.png");
+        assert_eq!(step(&files, None, 1).unwrap(), "b.png");
+        assert_eq!(step(&files, Some("missing.png"), -1).unwrap(), "c.png");
+        assert!(step(&[], None, 1).is_none());
+    }
+
+    #[test]
+    fn test_playlist_is_stored_in_metadata() {
+        let dir = theme_with_backgrounds(&["a.png", "b.jpg", "c.webp", "notes.txt"]);
+        let theme_dir = dir.path();
+        assert_eq!(
+            playlist(theme_dir).unwrap().order,
+            names(&["a.png", "b.jpg", "c.webp"])
+        );
+
+        reorder(theme_dir, names(&["c.webp", "a.png"])).unwrap();
+        set_rotation(theme_dir, 15).unwrap();
+        let stored = CustomThemeService::read_metadata(theme_dir)
+            .unwrap()
+            .backgrounds
+            .unwrap();
+        assert_eq!(stored.order, names(&["c.webp", "a.png", "b.jpg"]));
+        assert_eq!(stored.rotate_minutes, 15);
+
+        assert!(reorder(theme_dir, names(&["missing.png"])).is_err());
+        assert!(set_active(theme_dir, "missing.png").is_err());
+    }
+
+    #[test]
+    fn test_step_and_forget_active_background() {
+        let dir = theme_with_backgrounds(&["a.png", "b.png"]);
+        let theme_dir = dir.path();
+        // Not the current theme, so nothing is shown on the desktop
+        assert_eq!(
+            step_active(theme_dir, 1).unwrap().active.as_deref(),
+            Some("b.png")
+        );
+        assert_eq!(
+            step_active(theme_dir, 1).unwrap().active.as_deref(),
+            Some("a.png")
+        );
+
+        forget(theme_dir, "a.png").unwrap();
+        let stored = CustomThemeService::read_metadata(theme_dir)
+            .unwrap()
+            .backgrounds
+            .unwrap_or_default();
+        assert_eq!(stored.active, None);
+        assert!(!stored.order.contains(&"a.png".to_string()));
+    }
+}
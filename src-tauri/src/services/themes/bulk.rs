@@ -0,0 +1,584 @@
+//! Batch operations on custom themes: delete, regenerate, export, import and patch.
+//! Every theme is attempted, progress is emitted as a `bulk-progress` event per theme and
+//! the command returns which themes succeeded and why the others failed.
+
+use super::custom_themes::CustomThemeService;
+use super::{background_import, backgrounds, manifest};
+use crate::services::safe_path;
+use crate::types::{CustomTheme, ThemeManifest, ThemeVariant};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+pub const PROGRESS_EVENT: &str = "bulk-progress";
+
+/// Format version written into export bundles
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOperation {
+    Delete,
+    Regenerate,
+    Export,
+    Import,
+    Patch,
+}
+
+/// Emitted after each theme; `index` counts from 1 up to `total`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BulkProgress {
+    pub operation: BulkOperation,
+    pub theme: String,
+    pub index: usize,
+    pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BulkFailure {
+    pub theme: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BulkSummary {
+    pub operation: BulkOperation,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<BulkFailure>,
+}
+
+/// Several custom themes in one file. Generated configs are left out; importing renders them
+/// again from the stored app data.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThemeBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub themes: Vec<BundledTheme>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundledTheme {
+    pub theme: CustomTheme,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<ThemeManifest>,
+    #[serde(default)]
+    pub backgrounds: Vec<BundledFile>,
+}
+
+/// A file stored inline as base64
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundledFile {
+    pub name: String,
+    pub data: String,
+}
+
+/// Run `action` for each distinct name in order, reporting every outcome to `progress`
+pub fn run_batch(
+    operation: BulkOperation,
+    names: &[String],
+    mut action: impl FnMut(&str) -> Result<(), String>,
+    mut progress: impl FnMut(&BulkProgress),
+) -> BulkSummary {
+    let mut seen = HashSet::new();
+    let names: Vec<&String> = names.iter().filter(|name| seen.insert(*name)).collect();
+    let mut summary = BulkSummary {
+        operation,
+        succeeded: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for (index, name) in names.iter().enumerate() {
+        let error = action(name).err();
+        progress(&BulkProgress {
+            operation,
+            theme: name.to_string(),
+            index: index + 1,
+            total: names.len(),
+            error: error.clone(),
+        });
+        match error {
+            None => summary.succeeded.push(name.to_string()),
+            Some(error) => {
+                log::warn!("Bulk {operation:?} failed for '{name}': {error}");
+                summary.failed.push(BulkFailure {
+                    theme: name.to_string(),
+                    error,
+                });
+            },
+        }
+    }
+
+    summary
+}
+
+/// Expand dotted keys such as `alacritty.colors.primary.background` into nested app data
+pub fn patch_from_keys(patch: &BTreeMap<String, Value>) -> Result<Value, String> {
+    if patch.is_empty() {
+        return Err("The patch is empty".to_string());
+    }
+
+    let mut root = Map::new();
+    for (key, value) in patch {
+        let segments: Vec<&str> = key.split('.').collect();
+        if segments.iter().any(|segment| segment.trim().is_empty()) {
+            return Err(format!("Invalid patch key '{key}'"));
+        }
+        let (last, parents) = segments.split_last().expect("split yields a segment");
+
+        let mut node = &mut root;
+        for segment in parents {
+            node = node
+                .entry(segment.to_string())
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .ok_or_else(|| format!("Patch key '{key}' conflicts with another key"))?;
+        }
+        if node.insert(last.to_string(), value.clone()).is_some() {
+            return Err(format!("Patch key '{key}' conflicts with another key"));
+        }
+    }
+
+    Ok(Value::Object(root))
+}
+
+/// Pack a custom theme's data, manifest and background images
+pub fn bundle_theme(theme_dir: &Path, theme: CustomTheme) -> Result<BundledTheme, String> {
+    let backgrounds_dir = theme_dir.join(backgrounds::BACKGROUNDS_DIR);
+    let backgrounds = backgrounds::image_files(theme_dir)?
+        .into_iter()
+        .map(|name| {
+            let bytes = fs::read(backgrounds_dir.join(&name))
+                .map_err(|e| format!("Failed to read background '{name}': {e}"))?;
+            Ok(BundledFile {
+                name,
+                data: general_purpose::STANDARD.encode(bytes),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(BundledTheme {
+        theme,
+        manifest: manifest::load(theme_dir),
+        backgrounds,
+    })
+}
+
+/// Write a bundle's background images, checking names and contents like any import
+pub fn unpack_backgrounds(theme_dir: &Path, files: &[BundledFile]) -> Result<(), String> {
+    let backgrounds_dir = theme_dir.join(backgrounds::BACKGROUNDS_DIR);
+    fs::create_dir_all(&backgrounds_dir)
+        .map_err(|e| format!("Failed to create backgrounds directory: {e}"))?;
+
+    for file in files {
+        let path = backgrounds_dir.join(safe_path::file_name(&file.name)?);
+        let bytes = general_purpose::STANDARD
+            .decode(&file.data)
+            .map_err(|e| format!("Background '{}' is not valid base64: {e}", file.name))?;
+        fs::write(&path, bytes)
+            .map_err(|e| format!("Failed to write background '{}': {e}", file.name))?;
+        if let Err(e) = background_import::validate_image(&path) {
+            let _ = fs::remove_file(&path);
+            return Err(format!("Background '{}' was rejected: {e}", file.name));
+        }
+    }
+    Ok(())
+}
+
+/// Read and parse a theme bundle
+pub fn read_bundle(path: &Path) -> Result<ThemeBundle, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let bundle: ThemeBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not a theme bundle: {e}", path.display()))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Theme bundle version {} is newer than this app supports",
+            bundle.version
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Where to write an export: an absolute `.json` path in an allowed directory that is either
+/// new or an earlier bundle, so no other file is ever overwritten
+pub fn export_path(path: &str, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let target = safe_path::export_target(path, roots)?;
+    if target.exists() && read_bundle(&target).is_err() {
+        return Err(format!("'{path}' already exists and is not a theme bundle"));
+    }
+    Ok(target)
+}
+
+fn emit_progress(app_handle: &AppHandle) -> impl FnMut(&BulkProgress) + '_ {
+    move |progress| {
+        if let Err(e) = app_handle.emit(PROGRESS_EVENT, progress) {
+            log::warn!("Failed to emit bulk progress: {e}");
+        }
+    }
+}
+
+/// Drop changed themes from the cache and refresh it once for the whole batch
+async fn refresh_cache(names: &[String]) {
+    if names.is_empty() {
+        return;
+    }
+    if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+        for name in names {
+            cache.invalidate_theme(name).await;
+        }
+        let _ = cache.trigger_background_refresh().await;
+    }
+}
+
+#[tauri::command]
+pub async fn delete_custom_themes(
+    app_handle: AppHandle,
+    names: Vec<String>,
+) -> Result<BulkSummary, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    let summary = run_batch(
+        BulkOperation::Delete,
+        &names,
+        |name| {
+            // Only themes with custom metadata; system themes are never deleted in bulk
+            service.get_theme(name)?;
+            service.delete_theme(name)
+        },
+        emit_progress(&app_handle),
+    );
+    refresh_cache(&summary.succeeded).await;
+    Ok(summary)
+}
+
+/// Rebuild the files of the given custom themes, or of all of them, from their stored data
+#[tauri::command]
+pub async fn regenerate_custom_themes(
+    app_handle: AppHandle,
+    names: Option<Vec<String>>,
+) -> Result<BulkSummary, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    let names = match names {
+        Some(names) => names,
+        None => service
+            .list_themes()?
+            .into_iter()
+            .map(|theme| theme.name)
+            .collect(),
+    };
+    let summary = run_batch(
+        BulkOperation::Regenerate,
+        &names,
+        |name| service.regenerate_theme(name).map(|_| ()),
+        emit_progress(&app_handle),
+    );
+    refresh_cache(&summary.succeeded).await;
+    Ok(summary)
+}
+
+/// Write the given custom themes into one JSON bundle at `path`
+#[tauri::command]
+pub async fn export_custom_themes(
+    app_handle: AppHandle,
+    names: Vec<String>,
+    path: String,
+) -> Result<BulkSummary, String> {
+    let target = export_path(&path, &safe_path::import_roots())?;
+    let service = CustomThemeService::new(&app_handle)?;
+    let mut themes = Vec::new();
+    let summary = run_batch(
+        BulkOperation::Export,
+        &names,
+        |name| {
+            themes.push(service.bundle_theme(name)?);
+            Ok(())
+        },
+        emit_progress(&app_handle),
+    );
+    if themes.is_empty() {
+        return Ok(summary);
+    }
+
+    let bundle = ThemeBundle {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        themes,
+    };
+    let content = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize theme bundle: {e}"))?;
+    fs::write(&target, content).map_err(|e| format!("Failed to write theme bundle: {e}"))?;
+    log::info!(
+        "Exported {} themes to {}",
+        summary.succeeded.len(),
+        target.display()
+    );
+    Ok(summary)
+}
+
+/// Create custom themes from a bundle; themes whose name is already taken are reported as
+/// failures and left untouched
+#[tauri::command]
+pub async fn import_theme_bundle(
+    app_handle: AppHandle,
+    path: String,
+) -> Result<BulkSummary, String> {
+    let source = safe_path::import_source(&path, &safe_path::import_roots())?;
+    let bundle = read_bundle(&source)?;
+    let (names, mut themes) = index_bundle(bundle.themes);
+
+    let service = CustomThemeService::new(&app_handle)?;
+    let summary = run_batch(
+        BulkOperation::Import,
+        &names,
+        |name| {
+            let bundled = themes
+                .remove(name)
+                .unwrap_or_else(|| Err(format!("Theme '{name}' is not in the bundle")))?;
+            service.import_bundled_theme(bundled).map(|_| ())
+        },
+        emit_progress(&app_handle),
+    );
+    refresh_cache(&summary.succeeded).await;
+    Ok(summary)
+}
+
+/// Key the bundled themes by name, in bundle order. A name used by more than one theme maps
+/// to an error, so none of its copies is imported.
+fn index_bundle(
+    themes: Vec<BundledTheme>,
+) -> (Vec<String>, HashMap<String, Result<BundledTheme, String>>) {
+    let mut names = Vec::new();
+    let mut index = HashMap::new();
+    for bundled in themes {
+        let name = bundled.theme.name.clone();
+        match index.entry(name.clone()) {
+            Entry::Vacant(entry) => {
+                names.push(name);
+                entry.insert(Ok(bundled));
+            },
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() = Err(format!(
+                    "Theme '{name}' appears more than once in the bundle"
+                ));
+            },
+        }
+    }
+    (names, index)
+}
+
+/// Apply the same dotted key/value patch to one variant of each given custom theme
+#[tauri::command]
+pub async fn patch_custom_themes(
+    app_handle: AppHandle,
+    names: Vec<String>,
+    patch: BTreeMap<String, Value>,
+    variant: Option<ThemeVariant>,
+) -> Result<BulkSummary, String> {
+    let theme_data = patch_from_keys(&patch)?;
+    let service = CustomThemeService::new(&app_handle)?;
+    let variant = variant.unwrap_or(ThemeVariant::Dark);
+    let summary = run_batch(
+        BulkOperation::Patch,
+        &names,
+        |name| {
+            service.get_theme(name)?;
+            service
+                .update_theme_variant(name, variant, theme_data.clone())
+                .map(|_| ())
+        },
+        emit_progress(&app_handle),
+    );
+    refresh_cache(&summary.succeeded).await;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_batch_reports_each_theme() {
+        let names = vec!["one".to_string(), "two".to_string(), "one".to_string()];
+        let mut events = Vec::new();
+        let summary = run_batch(
+            BulkOperation::Delete,
+            &names,
+            |name| match name {
+                "two" => Err("locked".to_string()),
+                _ => Ok(()),
+            },
+            |progress| events.push(progress.clone()),
+        );
+
+        assert_eq!(summary.succeeded, vec!["one"]);
+        assert_eq!(
+            summary.failed,
+            vec![BulkFailure {
+                theme: "two".into(),
+                error: "locked".into()
+            }]
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[1].index, events[1].total), (2, 2));
+        assert_eq!(events[1].error.as_deref(), Some("locked"));
+    }
+
+    #[test]
+    fn test_patch_from_keys() {
+        let patch = BTreeMap::from([
+            (
+                "alacritty.colors.primary.background".to_string(),
+                json!("#101010"),
+            ),
+            (
+                "alacritty.colors.primary.foreground".to_string(),
+                json!("#eeeeee"),
+            ),
+            ("waybar.font_size".to_string(), json!(14)),
+        ]);
+        assert_eq!(
+            patch_from_keys(&patch).unwrap(),
+            json!({
+                "alacritty": {"colors": {"primary": {
+                    "background": "#101010",
+                    "foreground": "#eeeeee"
+                }}},
+                "waybar": {"font_size": 14}
+            })
+        );
+
+        let invalid = |key: &str| BTreeMap::from([(key.to_string(), json!(1))]);
+        assert!(patch_from_keys(&BTreeMap::new()).is_err());
+        assert!(patch_from_keys(&invalid("alacritty..colors")).is_err());
+        assert!(patch_from_keys(&invalid("")).is_err());
+
+        let conflict = BTreeMap::from([
+            ("waybar".to_string(), json!(1)),
+            ("waybar.font_size".to_string(), json!(14)),
+        ]);
+        assert!(patch_from_keys(&conflict).is_err());
+    }
+
+    fn png() -> Vec<u8> {
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        bytes.extend_from_slice(&13u32.to_be_bytes());
+        bytes.extend_from_slice(b"IHDR");
+        bytes.extend_from_slice(&4u32.to_be_bytes());
+        bytes.extend_from_slice(&4u32.to_be_bytes());
+        bytes.extend_from_slice(&[8, 2, 0, 0, 0]);
+        bytes
+    }
+
+    fn bundled_file(name: &str, bytes: &[u8]) -> BundledFile {
+        BundledFile {
+            name: name.into(),
+            data: general_purpose::STANDARD.encode(bytes),
+        }
+    }
+
+    #[test]
+    fn test_unpack_backgrounds_checks_names_and_content() {
+        let dir = TempDir::new().unwrap();
+        unpack_backgrounds(dir.path(), &[bundled_file("1.png", &png())]).unwrap();
+        assert_eq!(
+            fs::read(dir.path().join("backgrounds/1.png")).unwrap(),
+            png()
+        );
+
+        let escape = bundled_file("../escape.png", &png());
+        assert!(unpack_backgrounds(dir.path(), &[escape]).is_err());
+        assert!(!dir.path().join("escape.png").exists());
+
+        let fake = bundled_file("fake.png", b"not an image");
+        assert!(unpack_backgrounds(dir.path(), &[fake]).is_err());
+        assert!(!dir.path().join("backgrounds/fake.png").exists());
+    }
+
+    #[test]
+    fn test_export_path_only_overwrites_bundles() {
+        let dir = TempDir::new().unwrap();
+        let roots = vec![dir.path().to_path_buf()];
+        let new_file = dir.path().join("themes.json");
+        assert!(export_path(new_file.to_str().unwrap(), &roots).is_ok());
+
+        let bundle = ThemeBundle {
+            version: BUNDLE_VERSION,
+            exported_at: String::new(),
+            themes: Vec::new(),
+        };
+        fs::write(&new_file, serde_json::to_string(&bundle).unwrap()).unwrap();
+        assert!(export_path(new_file.to_str().unwrap(), &roots).is_ok());
+
+        let other = dir.path().join("settings.json");
+        fs::write(&other, "{\"auto_apply_theme\": true}").unwrap();
+        assert!(export_path(other.to_str().unwrap(), &roots).is_err());
+    }
+
+    fn theme(name: &str) -> CustomTheme {
+        CustomTheme {
+            name: name.into(),
+            created_at: String::new(),
+            modified_at: String::new(),
+            apps: json!({}),
+            colors: None,
+            light_apps: None,
+            backgrounds: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_bundle_names_are_reported() {
+        let bundled = |name: &str| BundledTheme {
+            theme: theme(name),
+            manifest: None,
+            backgrounds: Vec::new(),
+        };
+        let (names, mut themes) =
+            index_bundle(vec![bundled("Forest"), bundled("Ocean"), bundled("Forest")]);
+        assert_eq!(names, vec!["Forest", "Ocean"]);
+
+        let summary = run_batch(
+            BulkOperation::Import,
+            &names,
+            |name| themes.remove(name).unwrap().map(|_| ()),
+            |_| {},
+        );
+        assert_eq!(summary.succeeded, vec!["Ocean"]);
+        assert_eq!(
+            summary.failed,
+            vec![BulkFailure {
+                theme: "Forest".into(),
+                error: "Theme 'Forest' appears more than once in the bundle".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bundle_theme_includes_backgrounds_and_manifest() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("backgrounds")).unwrap();
+        fs::write(dir.path().join("backgrounds").join("1.png"), b"img").unwrap();
+        fs::write(
+            dir.path().join(manifest::MANIFEST_FILE),
+            "title = \"Forest\"",
+        )
+        .unwrap();
+        let bundled = bundle_theme(dir.path(), theme("Forest")).unwrap();
+        assert_eq!(bundled.manifest.unwrap().title.as_deref(), Some("Forest"));
+        assert_eq!(bundled.backgrounds.len(), 1);
+        assert_eq!(bundled.backgrounds[0].name, "1.png");
+        assert_eq!(
+            general_purpose::STANDARD
+                .decode(&bundled.backgrounds[0].data)
+                .unwrap(),
+            b"img"
+        );
+    }
+}
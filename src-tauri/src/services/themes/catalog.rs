@@ -0,0 +1,542 @@
+//! Installing community themes from the `themes.toml` catalog with git.
+//!
+//! Themes are cloned into `~/.config/omarchy/themes/<dir>`. Each install is recorded with
+//! the revision checked out, so only catalog installs can be updated or uninstalled here and
+//! installs behind their upstream can be flagged as outdated.
+
+use super::desktop_links;
+use crate::services::safe_path;
+use crate::services::settings::app_settings;
+use crate::types::Theme;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::AppHandle;
+
+const REGISTRY_FILE: &str = "catalog-installs.json";
+
+/// A catalog theme cloned into the themes directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InstalledTheme {
+    pub git_url: String,
+    /// Commit checked out in the theme directory
+    pub revision: String,
+    /// Upstream commit seen by the last update check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_revision: Option<String>,
+    pub installed_at: DateTime<Utc>,
+}
+
+impl InstalledTheme {
+    pub fn is_outdated(&self) -> bool {
+        self.latest_revision
+            .as_ref()
+            .is_some_and(|latest| *latest != self.revision)
+    }
+}
+
+/// Catalog installs keyed by theme directory name
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct InstallRegistry {
+    #[serde(default)]
+    pub themes: BTreeMap<String, InstalledTheme>,
+}
+
+impl InstallRegistry {
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize catalog installs: {e}"))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write catalog installs: {e}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogStatus {
+    NotInstalled,
+    Installed,
+    /// Installed from the catalog and behind its upstream
+    Outdated,
+}
+
+/// A catalog theme with its install state, as returned by `get_themes`
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    #[serde(flatten)]
+    pub theme: Theme,
+    /// Directory the theme installs into; `None` when it cannot be installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_dir: Option<String>,
+    pub status: CatalogStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_revision: Option<String>,
+}
+
+/// Run git, optionally inside `dir`, returning its trimmed stdout
+fn git<S: AsRef<OsStr>>(dir: Option<&Path>, args: &[S]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    let output = command
+        .args(args)
+        // Never wait for credentials; private or missing repositories just fail
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        let subcommand = args
+            .first()
+            .map(|arg| arg.as_ref().to_string_lossy())
+            .unwrap_or_default();
+        return Err(format!(
+            "git {subcommand} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Remote URLs git may clone from; anything starting with `-` would be read as an option
+fn validate_git_url(url: &str) -> Result<(), String> {
+    const PREFIXES: [&str; 6] = ["https://", "http://", "ssh://", "git://", "git@", "file://"];
+    if PREFIXES.iter().any(|prefix| url.starts_with(prefix)) || url.starts_with('/') {
+        Ok(())
+    } else {
+        Err(format!("Unsupported git URL '{url}'"))
+    }
+}
+
+/// The entry's `dir`, or the repository name without `omarchy-` and `-theme` the way
+/// `omarchy-theme-install` names it
+pub fn install_dir_name(theme: &Theme) -> Result<String, String> {
+    let name = match (&theme.dir, &theme.git_url) {
+        (Some(dir), _) => dir.clone(),
+        (None, Some(url)) => {
+            let repo = url.trim_end_matches('/').rsplit(['/', ':']).next();
+            let repo = repo.unwrap_or_default().trim_end_matches(".git");
+            let repo = repo.strip_prefix("omarchy-").unwrap_or(repo);
+            repo.strip_suffix("-theme").unwrap_or(repo).to_lowercase()
+        },
+        (None, None) => return Err(format!("Theme '{}' has no git URL", theme.title)),
+    };
+    safe_path::file_name(&name).map(str::to_string)
+}
+
+pub struct CatalogManager {
+    themes_dir: PathBuf,
+    registry_path: PathBuf,
+}
+
+impl CatalogManager {
+    pub fn new(themes_dir: PathBuf, registry_path: PathBuf) -> Self {
+        Self {
+            themes_dir,
+            registry_path,
+        }
+    }
+
+    /// Manager for `~/.config/omarchy/themes` with the registry in the app's config directory
+    pub fn from_env() -> Result<Self, String> {
+        let registry_path = app_settings::app_config_dir()
+            .map(|dir| dir.join(REGISTRY_FILE))
+            .ok_or_else(|| "Failed to get config directory".to_string())?;
+        Ok(Self::new(safe_path::themes_root()?, registry_path))
+    }
+
+    pub fn registry(&self) -> InstallRegistry {
+        InstallRegistry::load_from(&self.registry_path)
+    }
+
+    fn theme_dir(&self, dir: &str) -> Result<PathBuf, String> {
+        safe_path::theme_dir(&self.themes_dir, dir)
+    }
+
+    /// Mark catalog entries installed, outdated or not installed
+    pub fn entries(&self, catalog: Vec<Theme>) -> Vec<CatalogEntry> {
+        let registry = self.registry();
+        catalog
+            .into_iter()
+            .map(|theme| {
+                let install_dir = install_dir_name(&theme).ok();
+                let installed = install_dir.as_deref().and_then(|dir| {
+                    let exists = self.theme_dir(dir).is_ok_and(|path| path.exists());
+                    exists.then(|| registry.themes.get(dir))
+                });
+                let status = match installed {
+                    None => CatalogStatus::NotInstalled,
+                    Some(Some(record)) if record.is_outdated() => CatalogStatus::Outdated,
+                    Some(_) => CatalogStatus::Installed,
+                };
+                CatalogEntry {
+                    installed_revision: installed.flatten().map(|r| r.revision.clone()),
+                    theme,
+                    install_dir,
+                    status,
+                }
+            })
+            .collect()
+    }
+
+    /// Clone the entry's repository into the themes directory
+    pub fn install(&self, theme: &Theme) -> Result<InstalledTheme, String> {
+        let url = theme
+            .git_url
+            .as_deref()
+            .ok_or_else(|| format!("Theme '{}' has no git URL", theme.title))?;
+        validate_git_url(url)?;
+        let dir = install_dir_name(theme)?;
+        let target = self.theme_dir(&dir)?;
+        if target.symlink_metadata().is_ok() {
+            return Err(format!("Theme '{dir}' is already installed"));
+        }
+
+        // Clone next to the target and move it in place, so a failed clone leaves nothing behind
+        fs::create_dir_all(&self.themes_dir)
+            .map_err(|e| format!("Failed to create themes directory: {e}"))?;
+        let staging = self.themes_dir.join(format!(".{dir}.installing"));
+        let _ = fs::remove_dir_all(&staging);
+        let result = git(
+            None,
+            &[
+                OsStr::new("clone"),
+                OsStr::new("--quiet"),
+                OsStr::new("--"),
+                OsStr::new(url),
+                staging.as_os_str(),
+            ],
+        )
+        .and_then(|_| git(Some(&staging), &["rev-parse", "HEAD"]));
+        let revision = match result {
+            Ok(revision) => revision,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(e);
+            },
+        };
+        fs::rename(&staging, &target).map_err(|e| {
+            let _ = fs::remove_dir_all(&staging);
+            format!("Failed to install theme '{dir}': {e}")
+        })?;
+
+        let record = InstalledTheme {
+            git_url: url.to_string(),
+            revision: revision.clone(),
+            latest_revision: Some(revision),
+            installed_at: Utc::now(),
+        };
+        let mut registry = self.registry();
+        registry.themes.insert(dir.clone(), record.clone());
+        registry.save_to(&self.registry_path)?;
+        log::info!("Installed catalog theme '{dir}' from {url}");
+        Ok(record)
+    }
+
+    fn installed(&self, registry: &InstallRegistry, dir: &str) -> Result<InstalledTheme, String> {
+        registry
+            .themes
+            .get(dir)
+            .cloned()
+            .ok_or_else(|| format!("Theme '{dir}' was not installed from the catalog"))
+    }
+
+    /// Fetch every catalog install and remember its upstream revision
+    pub fn check_updates(&self) -> Result<InstallRegistry, String> {
+        let mut registry = self.registry();
+        for (dir, record) in registry.themes.iter_mut() {
+            let latest = self.theme_dir(dir).and_then(|path| {
+                git(Some(&path), &["fetch", "--quiet", "origin"])?;
+                git(Some(&path), &["rev-parse", "@{upstream}"])
+            });
+            match latest {
+                Ok(latest) => record.latest_revision = Some(latest),
+                Err(e) => log::warn!("Failed to check '{dir}' for updates: {e}"),
+            }
+        }
+        registry.save_to(&self.registry_path)?;
+        Ok(registry)
+    }
+
+    /// Fast-forward a catalog install to its upstream
+    pub fn update(&self, dir: &str) -> Result<InstalledTheme, String> {
+        let mut registry = self.registry();
+        let mut record = self.installed(&registry, dir)?;
+        let path = self.theme_dir(dir)?;
+        git(Some(&path), &["pull", "--quiet", "--ff-only"])?;
+        record.revision = git(Some(&path), &["rev-parse", "HEAD"])?;
+        record.latest_revision = Some(record.revision.clone());
+        registry.themes.insert(dir.to_string(), record.clone());
+        registry.save_to(&self.registry_path)?;
+        Ok(record)
+    }
+
+    /// Remove a catalog install; the theme in use cannot be removed
+    pub fn uninstall(&self, dir: &str) -> Result<(), String> {
+        let mut registry = self.registry();
+        self.installed(&registry, dir)?;
+        let path = self.theme_dir(dir)?;
+        if path.exists() {
+            if desktop_links::is_current_theme(&path) {
+                return Err(format!("Theme '{dir}' is in use; switch themes first"));
+            }
+            fs::remove_dir_all(&path)
+                .map_err(|e| format!("Failed to remove theme '{dir}': {e}"))?;
+        }
+        registry.themes.remove(dir);
+        registry.save_to(&self.registry_path)
+    }
+}
+
+/// Reload the theme list so installs and removals show up
+async fn refresh_theme_cache(dir: &str) {
+    if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+        cache.invalidate_theme(dir).await;
+        let _ = cache.trigger_background_refresh().await;
+    }
+}
+
+/// Run git and file system work off the async runtime
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Catalog task failed: {e}"))?
+}
+
+/// Install the catalog theme titled `title`; only URLs from the catalog are ever cloned
+#[tauri::command]
+pub async fn install_catalog_theme(
+    app_handle: AppHandle,
+    title: String,
+) -> Result<InstalledTheme, String> {
+    let catalog = super::get_themes::load_catalog(&app_handle)?;
+    let theme = catalog
+        .into_iter()
+        .find(|theme| theme.title == title)
+        .ok_or_else(|| format!("Theme '{title}' is not in the catalog"))?;
+    let dir = install_dir_name(&theme)?;
+    let manager = CatalogManager::from_env()?;
+    let record = blocking(move || manager.install(&theme)).await?;
+    refresh_theme_cache(&dir).await;
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn update_catalog_theme(dir: String) -> Result<InstalledTheme, String> {
+    let (manager, name) = (CatalogManager::from_env()?, dir.clone());
+    let record = blocking(move || manager.update(&name)).await?;
+    refresh_theme_cache(&dir).await;
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn uninstall_catalog_theme(dir: String) -> Result<(), String> {
+    let (manager, name) = (CatalogManager::from_env()?, dir.clone());
+    blocking(move || manager.uninstall(&name)).await?;
+    refresh_theme_cache(&dir).await;
+    Ok(())
+}
+
+/// Fetch upstream for every catalog install and return the refreshed catalog
+#[tauri::command]
+pub async fn check_catalog_updates(app_handle: AppHandle) -> Result<Vec<CatalogEntry>, String> {
+    let catalog = super::get_themes::load_catalog(&app_handle)?;
+    let manager = CatalogManager::from_env()?;
+    blocking(move || {
+        manager.check_updates()?;
+        Ok(manager.entries(catalog))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let mut command = Command::new("git");
+        command
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false", "-C"])
+            .arg(dir)
+            .args(args);
+        let output = command.output().unwrap();
+        assert!(
+            output.status.success(),
+            "git {args:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn commit(work: &Path, content: &str) {
+        fs::write(work.join("alacritty.toml"), content).unwrap();
+        run(work, &["add", "-A"]);
+        run(work, &["commit", "--quiet", "-m", content]);
+        run(work, &["push", "--quiet", "origin", "HEAD:main"]);
+    }
+
+    /// A bare "remote" with one commit and a working clone to push more from
+    fn upstream(root: &Path) -> (PathBuf, PathBuf) {
+        let bare = root.join("omarchy-forest-theme.git");
+        let work = root.join("work");
+        run(
+            root,
+            &[
+                "init",
+                "--quiet",
+                "--bare",
+                "-b",
+                "main",
+                "omarchy-forest-theme.git",
+            ],
+        );
+        run(
+            root,
+            &["clone", "--quiet", "omarchy-forest-theme.git", "work"],
+        );
+        run(&work, &["symbolic-ref", "HEAD", "refs/heads/main"]);
+        commit(&work, "v1");
+        (bare, work)
+    }
+
+    fn entry(url: Option<&Path>) -> Theme {
+        Theme {
+            title: "Forest".into(),
+            description: "Greens".into(),
+            image_url: String::new(),
+            theme_type: vec!["dark".into()],
+            git_url: url.map(|url| url.display().to_string()),
+            dir: None,
+        }
+    }
+
+    fn manager(root: &Path) -> CatalogManager {
+        CatalogManager::new(root.join("themes"), root.join("catalog-installs.json"))
+    }
+
+    #[test]
+    fn test_install_update_uninstall() {
+        let root = TempDir::new().unwrap();
+        let (bare, work) = upstream(root.path());
+        let manager = manager(root.path());
+        let theme = entry(Some(&bare));
+
+        let entries = manager.entries(vec![theme.clone()]);
+        assert_eq!(entries[0].status, CatalogStatus::NotInstalled);
+        assert_eq!(entries[0].install_dir.as_deref(), Some("forest"));
+
+        let record = manager.install(&theme).unwrap();
+        let installed = root.path().join("themes").join("forest");
+        assert_eq!(
+            fs::read_to_string(installed.join("alacritty.toml")).unwrap(),
+            "v1"
+        );
+        let entries = manager.entries(vec![theme.clone()]);
+        assert_eq!(entries[0].status, CatalogStatus::Installed);
+        assert_eq!(entries[0].installed_revision, Some(record.revision.clone()));
+        assert!(manager.install(&theme).is_err());
+
+        commit(&work, "v2");
+        manager.check_updates().unwrap();
+        let entries = manager.entries(vec![theme.clone()]);
+        assert_eq!(entries[0].status, CatalogStatus::Outdated);
+
+        let updated = manager.update("forest").unwrap();
+        assert_ne!(updated.revision, record.revision);
+        assert_eq!(
+            fs::read_to_string(installed.join("alacritty.toml")).unwrap(),
+            "v2"
+        );
+        assert_eq!(
+            manager.entries(vec![theme.clone()])[0].status,
+            CatalogStatus::Installed
+        );
+
+        manager.uninstall("forest").unwrap();
+        assert!(!installed.exists());
+        assert!(manager.registry().themes.is_empty());
+        assert_eq!(
+            manager.entries(vec![theme])[0].status,
+            CatalogStatus::NotInstalled
+        );
+    }
+
+    #[test]
+    fn test_failed_installs_leave_nothing_behind() {
+        let root = TempDir::new().unwrap();
+        let manager = manager(root.path());
+
+        assert!(manager.install(&entry(None)).is_err());
+        let mut option = entry(None);
+        option.git_url = Some("--upload-pack=touch /tmp/pwned".into());
+        option.dir = Some("pwned".into());
+        assert!(manager.install(&option).is_err());
+
+        let missing = root.path().join("missing.git");
+        assert!(manager.install(&entry(Some(&missing))).is_err());
+        let leftovers: Vec<_> = fs::read_dir(root.path().join("themes"))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert!(leftovers.is_empty());
+        assert!(manager.registry().themes.is_empty());
+    }
+
+    #[test]
+    fn test_only_catalog_installs_are_managed() {
+        let root = TempDir::new().unwrap();
+        let manager = manager(root.path());
+        fs::create_dir_all(root.path().join("themes").join("forest")).unwrap();
+
+        assert!(manager.uninstall("forest").is_err());
+        assert!(manager.update("forest").is_err());
+        assert!(root.path().join("themes").join("forest").exists());
+        // Present on disk, so it shows as installed without a revision
+        let entries = manager.entries(vec![entry(Some(Path::new("/srv/omarchy-forest-theme")))]);
+        assert_eq!(entries[0].status, CatalogStatus::Installed);
+        assert_eq!(entries[0].installed_revision, None);
+    }
+
+    #[test]
+    fn test_install_dir_names() {
+        let named = |url: &str| {
+            let mut theme = entry(None);
+            theme.git_url = Some(url.into());
+            install_dir_name(&theme)
+        };
+        assert_eq!(
+            named("https://github.com/someone/omarchy-forest-theme.git").unwrap(),
+            "forest"
+        );
+        assert_eq!(
+            named("git@github.com:someone/Nightfox").unwrap(),
+            "nightfox"
+        );
+        assert_eq!(
+            named("https://example.com/themes/rose-pine/").unwrap(),
+            "rose-pine"
+        );
+        assert!(named("https://example.com/..").is_err());
+
+        let mut theme = entry(None);
+        theme.dir = Some("../escape".into());
+        assert!(install_dir_name(&theme).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! Unpacked Chromium/Brave theme extension written next to a custom theme's configs.
+//!
+//! `chromium.theme` only sets a single `--set-theme-color`; the extension colors the frame,
+//! toolbar, tab text and new tab page, and can show one of the theme's backgrounds there.
+
+use super::theme_variants;
+use crate::services::config::oklch::Oklch;
+use crate::services::safe_path;
+use crate::types::CustomTheme;
+use serde_json::{json, Map, Value};
+use std::{fs, path::Path};
+
+/// Directory inside the theme that users load via chrome://extensions -> "Load unpacked"
+pub const EXTENSION_DIR: &str = "chromium-theme";
+const NTP_IMAGE: &str = "images/ntp_background.png";
+
+/// Manifest color keys, as named in the schema and in Chromium's theme format
+pub const COLOR_KEYS: [&str; 7] = [
+    "frame",
+    "toolbar",
+    "tab_text",
+    "tab_background_text",
+    "bookmark_text",
+    "ntp_background",
+    "ntp_text",
+];
+
+/// Fill in every color the variant leaves empty, starting from the base `theme_color`
+fn resolve_colors(theme_color: &str, variant: &Value, light: bool) -> Map<String, Value> {
+    let set = |key: &str| {
+        variant
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let base = Oklch::from_hex(theme_color).unwrap_or(Oklch {
+        l: 0.24,
+        c: 0.0,
+        h: 0.0,
+    });
+    let shade = |l: f64, max_chroma: f64| {
+        Oklch {
+            l,
+            c: base.c.min(max_chroma),
+            h: base.h,
+        }
+        .to_hex()
+    };
+
+    // Dark themes use the base color as is; light themes use a pale tint of it
+    let frame = set("frame").unwrap_or_else(|| {
+        if light {
+            shade(0.95, 0.03)
+        } else {
+            base.to_hex()
+        }
+    });
+    let frame_lch = Oklch::from_hex(&frame).unwrap_or(base);
+    let toolbar = set("toolbar").unwrap_or_else(|| {
+        let delta = if light { -0.04 } else { 0.06 };
+        Oklch {
+            l: (frame_lch.l + delta).clamp(0.0, 1.0),
+            ..frame_lch
+        }
+        .to_hex()
+    });
+    let text = if light {
+        shade(0.25, 0.02)
+    } else {
+        shade(0.93, 0.02)
+    };
+    let muted_text = Oklch::from_hex(&text)
+        .map(|t| t.mix(frame_lch, 0.4).to_hex())
+        .unwrap_or_else(|| text.clone());
+
+    let defaults = [
+        frame.clone(),
+        toolbar,
+        text.clone(),
+        muted_text,
+        text.clone(),
+        frame,
+        text,
+    ];
+    COLOR_KEYS
+        .iter()
+        .zip(defaults)
+        .map(|(key, default)| (key.to_string(), json!(set(key).unwrap_or(default))))
+        .collect()
+}
+
+fn rgb_array(hex: &str) -> Result<Value, String> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid Chromium theme color '#{hex}'"));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or_default();
+    Ok(json!([channel(0), channel(2), channel(4)]))
+}
+
+/// Build the extension's `manifest.json`
+pub fn render_manifest(
+    theme_name: &str,
+    chromium: &Value,
+    light: bool,
+    with_ntp_image: bool,
+) -> Result<Value, String> {
+    let theme_color = chromium
+        .get("theme_color")
+        .and_then(|c| c.as_str())
+        .unwrap_or("#1e1e1e");
+    let variant_key = if light { "light" } else { "dark" };
+    let variant = chromium
+        .get("extension")
+        .and_then(|e| e.get(variant_key))
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    let mut colors = Map::new();
+    for (key, value) in resolve_colors(theme_color, &variant, light) {
+        colors.insert(key, rgb_array(value.as_str().unwrap_or_default())?);
+    }
+    // Keep unfocused windows on the theme color instead of Chromium's default tint
+    colors.insert("frame_inactive".into(), colors["frame"].clone());
+
+    let mut theme = json!({ "colors": colors });
+    if with_ntp_image {
+        theme["images"] = json!({ "theme_ntp_background": NTP_IMAGE });
+        theme["properties"] = json!({
+            "ntp_background_alignment": "center",
+            "ntp_background_repeat": "no-repeat"
+        });
+    }
+
+    Ok(json!({
+        "manifest_version": 3,
+        "name": format!("Omarchy {theme_name}"),
+        "version": "1.0",
+        "description": "Generated with Omarchist",
+        "theme": theme
+    }))
+}
+
+/// The PNG background to show on the new tab page: the configured one, else the first PNG
+fn pick_ntp_image(theme_dir: &Path, configured: &str) -> Option<std::path::PathBuf> {
+    let backgrounds = theme_dir.join("backgrounds");
+    let is_png = |path: &Path| {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+    };
+
+    if !configured.is_empty() {
+        return match safe_path::child(&backgrounds, configured) {
+            Ok(path) if is_png(&path) && path.is_file() => Some(path),
+            Ok(_) => {
+                log::warn!("Chromium themes can only show PNG images; '{configured}' is skipped");
+                None
+            },
+            Err(e) => {
+                log::warn!("Skipping new tab background '{configured}': {e}");
+                None
+            },
+        };
+    }
+
+    // Symlinks leading out of the theme are skipped like in the background list
+    let mut pngs: Vec<_> = fs::read_dir(&backgrounds)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_png(p) && p.is_file())
+        .filter(|p| safe_path::contained_in(&backgrounds, p).is_ok())
+        .collect();
+    pngs.sort();
+    pngs.into_iter().next()
+}
+
+/// Write (or remove) the extension for a theme according to its `chromium.extension` data.
+/// The light or dark variant follows the theme's `light.mode` file.
+pub fn write_extension(theme_dir: &Path, theme_name: &str, apps: &Value) -> Result<(), String> {
+    let extension_dir = theme_dir.join(EXTENSION_DIR);
+    let chromium = apps.get("chromium").cloned().unwrap_or_else(|| json!({}));
+    let extension = chromium.get("extension").cloned().unwrap_or_default();
+
+    if !extension
+        .get("enabled")
+        .and_then(|e| e.as_bool())
+        .unwrap_or(false)
+    {
+        if extension_dir.exists() {
+            fs::remove_dir_all(&extension_dir)
+                .map_err(|e| format!("Failed to remove Chromium theme extension: {e}"))?;
+        }
+        return Ok(());
+    }
+
+    let light = theme_dir.join("light.mode").exists();
+    let ntp_image = extension
+        .get("use_background")
+        .and_then(|u| u.as_bool())
+        .unwrap_or(true)
+        .then(|| {
+            let configured = extension
+                .get("background")
+                .and_then(|b| b.as_str())
+                .unwrap_or_default();
+            pick_ntp_image(theme_dir, configured.trim())
+        })
+        .flatten();
+    let manifest = render_manifest(theme_name, &chromium, light, ntp_image.is_some())?;
+
+    // Start clean so a previously copied image does not linger
+    if extension_dir.exists() {
+        fs::remove_dir_all(&extension_dir)
+            .map_err(|e| format!("Failed to replace Chromium theme extension: {e}"))?;
+    }
+    fs::create_dir_all(extension_dir.join("images"))
+        .map_err(|e| format!("Failed to create Chromium theme extension: {e}"))?;
+    if let Some(image) = ntp_image {
+        fs::copy(&image, extension_dir.join(NTP_IMAGE))
+            .map_err(|e| format!("Failed to copy {}: {e}", image.display()))?;
+    }
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize Chromium theme manifest: {e}"))?;
+    fs::write(extension_dir.join("manifest.json"), content)
+        .map_err(|e| format!("Failed to write Chromium theme manifest: {e}"))
+}
+
+/// Rewrite the extension from the theme's saved `custom_theme.json`, e.g. after light mode
+/// was toggled. Themes that are not custom themes are left alone.
+pub fn refresh_extension(theme_dir: &Path) -> Result<(), String> {
+    let Ok(content) = fs::read_to_string(theme_dir.join("custom_theme.json")) else {
+        return Ok(());
+    };
+    let theme: CustomTheme = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse theme metadata: {e}"))?;
+    let active = theme_variants::active_variant(theme_dir, &theme);
+    write_extension(
+        theme_dir,
+        &theme.name,
+        theme_variants::variant_apps(&theme, active),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_derives_missing_colors() {
+        let chromium = json!({
+            "theme_color": "#1e1e1e",
+            "extension": { "dark": { "toolbar": "#333333" } }
+        });
+        let manifest = render_manifest("Matte", &chromium, false, false).unwrap();
+        let colors = &manifest["theme"]["colors"];
+        assert_eq!(manifest["name"], "Omarchy Matte");
+        assert_eq!(colors["frame"], json!([30, 30, 30]));
+        assert_eq!(colors["toolbar"], json!([51, 51, 51]));
+        assert!(colors["tab_text"][0].as_u64().unwrap() > 200);
+        assert!(manifest["theme"].get("images").is_none());
+
+        let light = render_manifest("Matte", &chromium, true, true).unwrap();
+        assert!(light["theme"]["colors"]["frame"][0].as_u64().unwrap() > 200);
+        assert!(light["theme"]["colors"]["tab_text"][0].as_u64().unwrap() < 100);
+        assert_eq!(light["theme"]["images"]["theme_ntp_background"], NTP_IMAGE);
+
+        let invalid = json!({ "extension": { "dark": { "frame": "#12" } } });
+        assert!(render_manifest("x", &invalid, false, false).is_err());
+    }
+
+    #[test]
+    fn test_write_follows_light_mode_and_enabled() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("backgrounds")).unwrap();
+        fs::write(dir.path().join("backgrounds/1.jpg"), b"jpg").unwrap();
+        fs::write(dir.path().join("backgrounds/2.png"), b"png").unwrap();
+        fs::write(dir.path().join("light.mode"), "").unwrap();
+        let apps =
+            json!({ "chromium": { "theme_color": "#1e1e1e", "extension": { "enabled": true } } });
+
+        write_extension(dir.path(), "Test", &apps).unwrap();
+        let extension = dir.path().join(EXTENSION_DIR);
+        assert_eq!(fs::read(extension.join(NTP_IMAGE)).unwrap(), b"png");
+        let manifest: Value =
+            serde_json::from_str(&fs::read_to_string(extension.join("manifest.json")).unwrap())
+                .unwrap();
+        assert!(manifest["theme"]["colors"]["frame"][0].as_u64().unwrap() > 200);
+
+        let disabled = json!({ "chromium": { "extension": { "enabled": false } } });
+        write_extension(dir.path(), "Test", &disabled).unwrap();
+        assert!(!extension.exists());
+    }
+
+    #[test]
+    fn test_ntp_image_stays_in_backgrounds() {
+        let temp = TempDir::new().unwrap();
+        let theme_dir = temp.path().join("theme");
+        fs::create_dir_all(theme_dir.join("backgrounds")).unwrap();
+        fs::write(theme_dir.join("backgrounds/1.png"), b"png").unwrap();
+        fs::write(temp.path().join("secret.png"), b"png").unwrap();
+
+        assert_eq!(
+            pick_ntp_image(&theme_dir, "1.png"),
+            Some(theme_dir.join("backgrounds/1.png").canonicalize().unwrap())
+        );
+        assert_eq!(pick_ntp_image(&theme_dir, "../../secret.png"), None);
+        let absolute = temp.path().join("secret.png");
+        assert_eq!(pick_ntp_image(&theme_dir, absolute.to_str().unwrap()), None);
+        assert_eq!(
+            pick_ntp_image(&theme_dir, ""),
+            Some(theme_dir.join("backgrounds/1.png"))
+        );
+    }
+}
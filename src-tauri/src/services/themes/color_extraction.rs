@@ -1,8 +1,27 @@
+use crate::services::config::generators::{
+    alacritty::{parse_legacy_yaml, theme_data_from_config},
+    foot::FootGenerator,
+    ghostty::GhosttyGenerator,
+    kitty::KittyGenerator,
+    ConfigGenerator,
+};
+use crate::services::config::schema_validation::deep_merge;
 use crate::types::{PrimaryColors, TerminalColors, ThemeColors};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::fs;
-use std::path::Path;
-use toml;
+use std::path::{Path, PathBuf};
+
+/// Terminal config files that colors can be read from, in order of preference
+pub const TERMINAL_CONFIG_FILES: [&str; 5] = [
+    "alacritty.toml",
+    "alacritty.yml",
+    "kitty.conf",
+    "foot.ini",
+    "ghostty.conf",
+];
+
+/// Alacritty imports are followed at most this deep, which also breaks import cycles
+const MAX_IMPORT_DEPTH: usize = 5;
 
 /// Color extraction service for theme configurations
 pub struct ColorExtractor;
@@ -33,31 +52,101 @@ impl ColorExtractor {
         })
     }
 
-    /// Extract colors from an Alacritty TOML configuration file
+    /// Extract colors from an Alacritty configuration file.
+    ///
+    /// Handles `alacritty.toml`, the legacy YAML format and configs that pull their colors
+    /// in through `import`, in which case the imported files are merged underneath.
     pub fn extract_from_alacritty_config(config_path: &Path) -> Option<ThemeColors> {
-        let content = fs::read_to_string(config_path).ok()?;
-        let config: Value = toml::from_str(&content).ok()?;
+        let config = Self::load_alacritty_config(config_path, 0)?;
+        Self::extract_from_custom_theme(&json!({ "alacritty": theme_data_from_config(&config) }))
+    }
 
-        let colors = config.get("colors")?;
+    /// Read an Alacritty config and the files it imports into one merged value
+    fn load_alacritty_config(config_path: &Path, depth: usize) -> Option<Value> {
+        let content = fs::read_to_string(config_path).ok()?;
+        let is_yaml = config_path
+            .extension()
+            .is_some_and(|ext| ext == "yml" || ext == "yaml");
+        let config: Value = if is_yaml {
+            parse_legacy_yaml(&content).ok()?
+        } else {
+            toml::from_str(&content).ok()?
+        };
+
+        // Alacritty 0.13 moved `import` under `[general]`
+        let imports: Vec<&str> = config
+            .get("general")
+            .and_then(|g| g.get("import"))
+            .or_else(|| config.get("import"))
+            .and_then(|i| i.as_array())
+            .map(|items| items.iter().filter_map(|i| i.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut merged = json!({});
+        if depth < MAX_IMPORT_DEPTH {
+            for import in imports {
+                let import_path = Self::resolve_import(config_path, import);
+                match Self::load_alacritty_config(&import_path, depth + 1) {
+                    Some(imported) => deep_merge(&mut merged, &imported),
+                    None => log::warn!("Failed to read Alacritty import {import_path:?}"),
+                }
+            }
+        }
+        deep_merge(&mut merged, &config);
+        Some(merged)
+    }
 
-        // Extract primary colors
-        let primary = colors.get("primary")?;
-        let background = Self::normalize_color(primary.get("background")?.as_str()?)?;
-        let foreground = Self::normalize_color(primary.get("foreground")?.as_str()?)?;
+    /// Imports may be absolute, `~`-relative or relative to the importing file
+    fn resolve_import(config_path: &Path, import: &str) -> PathBuf {
+        if let Some(rest) = import.strip_prefix("~/") {
+            if let Some(home) = dirs::home_dir() {
+                return home.join(rest);
+            }
+        }
+        match config_path.parent() {
+            Some(parent) => parent.join(import),
+            None => PathBuf::from(import),
+        }
+    }
 
-        // Extract terminal colors
-        let normal = colors.get("normal");
-        let bright = colors.get("bright");
+    /// Extract colors from the first terminal config in a theme directory that yields a
+    /// complete palette, trying Alacritty's configs before the kitty, foot and ghostty files
+    pub fn extract_from_theme_dir(theme_dir: &Path) -> Option<ThemeColors> {
+        TERMINAL_CONFIG_FILES
+            .iter()
+            .map(|file_name| theme_dir.join(file_name))
+            .filter(|config_path| config_path.exists())
+            .find_map(|config_path| {
+                let colors = Self::extract_from_terminal_config(&config_path);
+                if colors.is_none() {
+                    log::warn!("Failed to extract colors from {config_path:?}");
+                }
+                colors
+            })
+    }
 
-        let terminal_colors = Self::extract_terminal_colors(normal, bright)?;
+    /// Whether a theme directory has any terminal config colors can be read from
+    pub fn has_terminal_config(theme_dir: &Path) -> bool {
+        TERMINAL_CONFIG_FILES
+            .iter()
+            .any(|file_name| theme_dir.join(file_name).exists())
+    }
 
-        Some(ThemeColors {
-            primary: PrimaryColors {
-                background,
-                foreground,
+    /// Extract colors from a kitty, foot or ghostty config using the matching generator's parser
+    pub fn extract_from_terminal_config(config_path: &Path) -> Option<ThemeColors> {
+        let generator: &dyn ConfigGenerator = match config_path.file_name()?.to_str()? {
+            "alacritty.toml" | "alacritty.yml" => {
+                return Self::extract_from_alacritty_config(config_path)
             },
-            terminal: terminal_colors,
-        })
+            "kitty.conf" => &KittyGenerator,
+            "foot.ini" => &FootGenerator,
+            "ghostty.conf" => &GhosttyGenerator,
+            _ => return None,
+        };
+
+        let content = fs::read_to_string(config_path).ok()?;
+        let parsed = generator.parse_existing_config(&content).ok()?;
+        Self::extract_from_custom_theme(&json!({ "alacritty": parsed }))
     }
 
     /// Extract terminal colors with fallback logic (normal -> bright -> defaults)
@@ -123,6 +212,13 @@ impl ColorExtractor {
 
     /// Convert color from other formats to hex (basic implementation)
     fn convert_to_hex(color: &str) -> Option<String> {
+        // Alacritty's YAML-era `0xRRGGBB`
+        if let Some(hex) = color.strip_prefix("0x") {
+            if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Some(format!("#{}", hex.to_lowercase()));
+            }
+        }
+
         // Handle 3-digit hex without #
         if color.len() == 3 && color.chars().all(|c| c.is_ascii_hexdigit()) {
             return Some(format!("#{}", color.to_lowercase()));
@@ -178,7 +274,7 @@ impl ColorExtractor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+    use tempfile::TempDir;
 
     #[test]
     fn test_normalize_valid_hex_colors() {
@@ -358,4 +454,94 @@ mod tests {
         );
         assert_eq!(ColorExtractor::validate_and_sanitize_color("invalid"), None);
     }
+
+    #[test]
+    fn test_extract_from_theme_dir_falls_back_to_other_terminals() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("foot.ini"),
+            "[colors]\nbackground=101010\nforeground=f0f0f0\nregular1=aa0000\nregular2=00aa00\n\
+             regular3=aaaa00\nregular4=0000aa\nregular5=aa00aa\nregular6=00aaaa\n",
+        )
+        .unwrap();
+
+        assert!(ColorExtractor::has_terminal_config(temp_dir.path()));
+        let colors = ColorExtractor::extract_from_theme_dir(temp_dir.path()).unwrap();
+        assert_eq!(colors.primary.background, "#101010");
+        assert_eq!(colors.terminal.red, "#aa0000");
+        assert_eq!(colors.terminal.cyan, "#00aaaa");
+    }
+
+    #[test]
+    fn test_extract_from_theme_dir_prefers_alacritty() {
+        let temp_dir = TempDir::new().unwrap();
+        let kitty = KittyGenerator
+            .generate_config(
+                &json!({ "kitty": { "colors": { "primary": { "background": "#222222" } } } }),
+            )
+            .unwrap();
+        fs::write(temp_dir.path().join("kitty.conf"), kitty).unwrap();
+
+        let colors = ColorExtractor::extract_from_theme_dir(temp_dir.path()).unwrap();
+        assert_eq!(colors.primary.background, "#222222");
+
+        fs::write(
+            temp_dir.path().join("alacritty.toml"),
+            "[colors.primary]\nbackground = \"#333333\"\nforeground = \"#eeeeee\"\n\
+             [colors.normal]\nred = \"#ff0000\"\ngreen = \"#00ff00\"\nyellow = \"#ffff00\"\n\
+             blue = \"#0000ff\"\nmagenta = \"#ff00ff\"\ncyan = \"#00ffff\"\n",
+        )
+        .unwrap();
+        let colors = ColorExtractor::extract_from_theme_dir(temp_dir.path()).unwrap();
+        assert_eq!(colors.primary.background, "#333333");
+    }
+
+    #[test]
+    fn test_extract_from_alacritty_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("themes")).unwrap();
+        fs::write(
+            temp_dir.path().join("themes/base.toml"),
+            "[colors.primary]\nbackground = \"#101010\"\nforeground = \"#eeeeee\"\n\
+             [colors.normal]\nred = \"#ff0000\"\ngreen = \"#00ff00\"\nyellow = \"#ffff00\"\n\
+             blue = \"#0000ff\"\nmagenta = \"#ff00ff\"\ncyan = \"#00ffff\"\n",
+        )
+        .unwrap();
+        let config_path = temp_dir.path().join("alacritty.toml");
+        fs::write(
+            &config_path,
+            "[general]\nimport = [\"themes/base.toml\", \"missing.toml\"]\n\n\
+             [colors.primary]\nbackground = \"#202020\"\n",
+        )
+        .unwrap();
+
+        let colors = ColorExtractor::extract_from_alacritty_config(&config_path).unwrap();
+        assert_eq!(colors.primary.background, "#202020");
+        assert_eq!(colors.primary.foreground, "#eeeeee");
+        assert_eq!(colors.terminal.cyan, "#00ffff");
+    }
+
+    #[test]
+    fn test_extract_from_legacy_yaml_config() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("alacritty.yml"),
+            "colors:\n  primary:\n    background: '0x1D1F21'\n    foreground: '0xc5c8c6'\n\
+             \x20 normal:\n    red: '0xcc6666'\n    green: '0xb5bd68'\n    yellow: '0xf0c674'\n\
+             \x20   blue: '0x81a2be'\n    magenta: '0xb294bb'\n    cyan: '0x8abeb7'\n",
+        )
+        .unwrap();
+
+        let colors = ColorExtractor::extract_from_theme_dir(temp_dir.path()).unwrap();
+        assert_eq!(colors.primary.background, "#1d1f21");
+        assert_eq!(colors.terminal.magenta, "#b294bb");
+        assert!(ColorExtractor::has_terminal_config(temp_dir.path()));
+    }
+
+    #[test]
+    fn test_extract_from_theme_dir_without_terminal_configs() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(!ColorExtractor::has_terminal_config(temp_dir.path()));
+        assert!(ColorExtractor::extract_from_theme_dir(temp_dir.path()).is_none());
+    }
 }
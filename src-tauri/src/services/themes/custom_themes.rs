@@ -1,7 +1,15 @@
+use super::background_import::{self, BackgroundImport};
 use super::color_extraction::ColorExtractor;
+use super::theme_variants::{self, VARIANTS_DIR};
+use super::{backgrounds, bulk, chromium_extension, desktop_links, manifest};
+use crate::services::config::generators::btop::{self, BundledBtopTheme};
 use crate::services::config::generators::ConfigGeneratorRegistry;
+use crate::services::config::light_mode::LightModeService;
+use crate::services::config::schema_validation::{deep_merge, SchemaValidator};
+use crate::services::safe_path;
 use crate::types::{
-    AlacrittyColors, AlacrittyConfig, AlacrittyPrimaryColors, CustomTheme, ThemeColors,
+    AlacrittyColors, AlacrittyConfig, AlacrittyPrimaryColors, BackgroundPlaylist, CustomTheme,
+    ThemeColors, ThemeManifest, ThemeVariant,
 };
 use serde_json::Value;
 use std::fs;
@@ -57,14 +65,9 @@ impl CustomThemeService {
             return Some(colors);
         }
 
-        // Fallback: try to extract from Alacritty config file
-        let alacritty_config_path = theme_dir.join("alacritty.toml");
-        if alacritty_config_path.exists() {
-            if let Some(colors) =
-                ColorExtractor::extract_from_alacritty_config(&alacritty_config_path)
-            {
-                return Some(colors);
-            }
+        // Fallback: try to extract from the terminal config files
+        if let Some(colors) = ColorExtractor::extract_from_theme_dir(theme_dir) {
+            return Some(colors);
         }
 
         // If all else fails, return fallback colors
@@ -85,6 +88,12 @@ impl CustomThemeService {
             return Err(format!("Theme '{name}' already exists"));
         }
 
+        // Enforce the generator schemas (coercion, enums, colors, defaults)
+        let theme_data = self.validate_theme_data(theme_data)?;
+
+        // Generate and validate every config before touching the filesystem
+        let app_configs = self.render_app_configs(&name, &theme_data, true)?;
+
         // Create theme directory
         fs::create_dir_all(&theme_dir)
             .map_err(|e| format!("Failed to create theme directory: {e}"))?;
@@ -106,35 +115,14 @@ impl CustomThemeService {
             modified_at: now,
             apps: theme_data.clone(),
             colors,
+            light_apps: None,
+            backgrounds: None,
         };
 
-        // Generate config files for each app using the generator registry
-        for app_name in self.generator_registry.get_all_apps() {
-            if let Some(generator) = self.generator_registry.get_generator(app_name) {
-                // Extract the specific config for this app from the theme_data
-                if let Some(app_config) = theme_data.get(app_name) {
-                    match generator.generate_config(app_config) {
-                        Ok(config_content) => {
-                            let config_path = theme_dir.join(generator.get_file_name());
-                            fs::write(&config_path, config_content)
-                                .map_err(|e| format!("Failed to write {app_name} config: {e}"))?;
-                        },
-                        Err(e) => {
-                            log::warn!("Failed to generate {app_name} config for '{name}': {e}");
-                        },
-                    }
-                } else {
-                    log::warn!("No config data found for app '{app_name}' in new theme '{name}'");
-                }
-            }
-        }
-
-        // Save theme metadata
-        let metadata_path = theme_dir.join("custom_theme.json");
-        let metadata_content = serde_json::to_string_pretty(&theme)
-            .map_err(|e| format!("Failed to serialize theme metadata: {e}"))?;
-        fs::write(&metadata_path, metadata_content)
-            .map_err(|e| format!("Failed to write theme metadata: {e}"))?;
+        Self::write_app_configs(&theme_dir, app_configs)?;
+        Self::write_chromium_extension(&theme_dir, &theme, ThemeVariant::Dark);
+        Self::write_metadata(&theme_dir, &theme)?;
+        Self::write_manifest(&theme_dir, &name, "")?;
 
         log::info!(
             "Created custom theme '{}' in directory: {}",
@@ -172,6 +160,17 @@ impl CustomThemeService {
         &self,
         name: &str,
         theme_data: Value,
+    ) -> Result<CustomTheme, String> {
+        self.update_theme_variant(name, ThemeVariant::Dark, theme_data)
+    }
+
+    /// Update one variant of a theme. `Dark` edits `apps`, which is the only variant of
+    /// themes without a light one.
+    pub fn update_theme_variant(
+        &self,
+        name: &str,
+        variant: ThemeVariant,
+        theme_data: Value,
     ) -> Result<CustomTheme, String> {
         let sanitized_name = Self::sanitize_name(name);
         let theme_dir = self.themes_dir.join(&sanitized_name);
@@ -183,68 +182,233 @@ impl CustomThemeService {
         // Load existing theme metadata
         let mut theme = self.load_theme_metadata(&sanitized_name)?;
 
+        let apps = match variant {
+            ThemeVariant::Dark => &mut theme.apps,
+            ThemeVariant::Light => theme
+                .light_apps
+                .as_mut()
+                .ok_or_else(|| format!("Theme '{name}' has no light variant"))?,
+        };
         // Deep-merge incoming app data into existing apps so we don't wipe other apps
-        let mut merged_apps = theme.apps.clone();
-        Self::deep_merge(&mut merged_apps, &theme_data);
-        theme.apps = merged_apps;
+        let mut merged_apps = apps.clone();
+        deep_merge(&mut merged_apps, &theme_data);
+        *apps = self.validate_theme_data(merged_apps)?;
         theme.modified_at = chrono::Utc::now().to_rfc3339();
 
         // Re-extract colors after update
         theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
 
-        // Regenerate config files for each app
+        // Regenerate config files for each app, validating all of them before writing any
+        let active = theme_variants::active_variant(&theme_dir, &theme);
+        let app_configs = self.render_theme_files(&theme, active)?;
+        Self::write_app_configs(&theme_dir, app_configs)?;
+        Self::write_chromium_extension(&theme_dir, &theme, active);
+        Self::write_metadata(&theme_dir, &theme)?;
+
+        log::info!("Updated custom theme '{name}'");
+
+        Ok(theme)
+    }
+
+    /// Add a light variant derived from the dark one by inverting lightness in OKLCH,
+    /// replacing any existing light variant
+    pub fn create_light_variant(&self, name: &str) -> Result<CustomTheme, String> {
+        let sanitized_name = Self::sanitize_name(name);
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+
+        let light_apps = theme_variants::derive_light_variant(&theme.apps, &self.get_app_schemas());
+        theme.light_apps = Some(self.validate_theme_data(light_apps)?);
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+
+        let active = theme_variants::active_variant(&theme_dir, &theme);
+        let app_configs = self.render_theme_files(&theme, active)?;
+        Self::write_app_configs(&theme_dir, app_configs)?;
+        Self::write_chromium_extension(&theme_dir, &theme, active);
+        Self::write_metadata(&theme_dir, &theme)?;
+
+        log::info!("Derived a light variant for custom theme '{name}'");
+
+        Ok(theme)
+    }
+
+    /// Drop the light variant, leaving a dark-only theme
+    pub fn remove_light_variant(&self, name: &str) -> Result<CustomTheme, String> {
+        let sanitized_name = Self::sanitize_name(name);
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let mut theme = self.load_theme_metadata(&sanitized_name)?;
+        if theme.light_apps.take().is_none() {
+            return Ok(theme);
+        }
+        theme.modified_at = chrono::Utc::now().to_rfc3339();
+
+        let app_configs = self.render_theme_files(&theme, ThemeVariant::Dark)?;
+        Self::write_metadata(&theme_dir, &theme)?;
+        Self::write_app_configs(&theme_dir, app_configs)?;
+        let variants_dir = theme_dir.join(VARIANTS_DIR);
+        if variants_dir.exists() {
+            fs::remove_dir_all(&variants_dir)
+                .map_err(|e| format!("Failed to remove theme variants: {e}"))?;
+        }
+        // Turns light mode off and refreshes the Chromium extension from the new metadata
+        LightModeService::new(&self.app_handle)?.disable_light_mode(name)?;
+
+        Ok(theme)
+    }
+
+    /// Make `variant` the active one: regenerate the root files from its data, toggle
+    /// `light.mode` and, when this is the current Omarchy theme, reload the desktop
+    pub fn switch_variant(&self, name: &str, variant: ThemeVariant) -> Result<CustomTheme, String> {
+        let sanitized_name = Self::sanitize_name(name);
+        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let theme = self.load_theme_metadata(&sanitized_name)?;
+
+        if variant == ThemeVariant::Light && theme.light_apps.is_none() {
+            return Err(format!("Theme '{name}' has no light variant"));
+        }
+
+        let app_configs = self.render_theme_files(&theme, variant)?;
+        Self::write_app_configs(&theme_dir, app_configs)?;
+        // Also rewrites the Chromium extension for the new variant
+        LightModeService::new(&self.app_handle)?
+            .set_light_mode(name, variant == ThemeVariant::Light)?;
+
+        if desktop_links::is_current_theme(&theme_dir) {
+            crate::commands::shell::refresh_theme_adjustments()?;
+        }
+
+        log::info!(
+            "Switched custom theme '{name}' to its {} variant",
+            variant.as_str()
+        );
+
+        Ok(theme)
+    }
+
+    /// Whether a custom theme has both a dark and a light variant
+    pub fn has_light_variant(&self, name: &str) -> bool {
+        self.get_theme(name)
+            .is_ok_and(|theme| theme.light_apps.is_some())
+    }
+
+    /// Validate theme data against the generator schemas, returning the normalized data
+    fn validate_theme_data(&self, theme_data: Value) -> Result<Value, String> {
+        let report = SchemaValidator::new(&self.generator_registry).validate_apps(&theme_data);
+        if !report.valid {
+            return Err(format!("Invalid theme data: {}", report.error_summary()));
+        }
+        for warning in &report.warnings {
+            log::warn!("{}: {}", warning.path, warning.message);
+        }
+        Ok(report.data)
+    }
+
+    /// Generate config content for each registered app and validate it.
+    /// Generation failures are logged and skipped; invalid output aborts the whole write.
+    /// When `only_present` is set, apps without data in `theme_data` are skipped.
+    fn render_app_configs(
+        &self,
+        name: &str,
+        theme_data: &Value,
+        only_present: bool,
+    ) -> Result<Vec<(String, String)>, String> {
+        let mut app_configs = Vec::new();
+
         for app_name in self.generator_registry.get_all_apps() {
-            if let Some(generator) = self.generator_registry.get_generator(app_name) {
-                match generator.generate_config(&theme.apps) {
-                    Ok(config_content) => {
-                        let config_path = theme_dir.join(generator.get_file_name());
-                        log::debug!("Writing {} config to {}", app_name, config_path.display());
-                        fs::write(&config_path, config_content)
-                            .map_err(|e| format!("Failed to write {app_name} config: {e}"))?;
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to generate {app_name} config: {e}");
-                    },
-                }
+            let Some(generator) = self.generator_registry.get_generator(app_name) else {
+                continue;
+            };
+
+            if only_present && theme_data.get(app_name).is_none() {
+                log::warn!("No config data found for app '{app_name}' in theme '{name}'");
+                continue;
+            }
+
+            match generator.generate_config(theme_data) {
+                Ok(config_content) => {
+                    generator.validate_output(&config_content).map_err(|e| {
+                        format!("Generated {app_name} config for '{name}' is invalid: {e}")
+                    })?;
+                    app_configs.push((generator.get_file_name().to_string(), config_content));
+                },
+                Err(e) => {
+                    log::warn!("Failed to generate {app_name} config for '{name}': {e}");
+                },
             }
         }
 
-        // Update the metadata file
-        let metadata_path = theme_dir.join("custom_theme.json");
-        let metadata_content = serde_json::to_string_pretty(&theme)
-            .map_err(|e| format!("Failed to serialize theme metadata: {e}"))?;
-        fs::write(&metadata_path, metadata_content)
-            .map_err(|e| format!("Failed to write theme metadata: {e}"))?;
+        Ok(app_configs)
+    }
 
-        log::info!("Updated custom theme '{name}'");
+    /// Render every file of a theme: the `active` variant's configs at the root and, for
+    /// themes with a light variant, each variant's own set under `variants/`
+    fn render_theme_files(
+        &self,
+        theme: &CustomTheme,
+        active: ThemeVariant,
+    ) -> Result<Vec<(String, String)>, String> {
+        if theme.light_apps.is_none() {
+            return self.render_app_configs(&theme.name, &theme.apps, false);
+        }
 
-        Ok(theme)
+        let mut files = Vec::new();
+        for variant in [ThemeVariant::Dark, ThemeVariant::Light] {
+            let apps = theme_variants::variant_apps(theme, variant);
+            let configs = self.render_app_configs(&theme.name, apps, false)?;
+            if variant == active {
+                files.extend(configs.iter().cloned());
+            }
+            files.extend(configs.into_iter().map(|(file_name, content)| {
+                (
+                    format!("{VARIANTS_DIR}/{}/{file_name}", variant.as_str()),
+                    content,
+                )
+            }));
+        }
+        Ok(files)
     }
 
-    /// Deep-merge JSON values: when both sides are objects, merge keys recursively.
-    /// Otherwise, overwrite target with source.
-    fn deep_merge(target: &mut Value, src: &Value) {
-        use serde_json::Value::*;
-        match (target, src) {
-            (Object(t_map), Object(s_map)) => {
-                for (k, v) in s_map {
-                    match (t_map.get_mut(k), v) {
-                        (Some(t_child), Object(_)) => {
-                            Self::deep_merge(t_child, v);
-                        },
-                        (Some(t_child), _) => {
-                            *t_child = v.clone();
-                        },
-                        (core::option::Option::None, _) => {
-                            t_map.insert(k.clone(), v.clone());
-                        },
-                    }
-                }
-            },
-            (t, s) => {
-                *t = s.clone();
-            },
+    fn write_chromium_extension(theme_dir: &Path, theme: &CustomTheme, active: ThemeVariant) {
+        let apps = theme_variants::variant_apps(theme, active);
+        if let Err(e) = chromium_extension::write_extension(theme_dir, &theme.name, apps) {
+            log::warn!(
+                "Failed to write Chromium theme extension for '{}': {e}",
+                theme.name
+            );
+        }
+    }
+
+    /// Read `custom_theme.json` from a theme directory
+    pub fn read_metadata(theme_dir: &Path) -> Result<CustomTheme, String> {
+        let content = fs::read_to_string(theme_dir.join("custom_theme.json"))
+            .map_err(|e| format!("Failed to read theme metadata: {e}"))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse theme metadata: {e}"))
+    }
+
+    pub fn write_metadata(theme_dir: &Path, theme: &CustomTheme) -> Result<(), String> {
+        let metadata_content = serde_json::to_string_pretty(theme)
+            .map_err(|e| format!("Failed to serialize theme metadata: {e}"))?;
+        fs::write(theme_dir.join("custom_theme.json"), metadata_content)
+            .map_err(|e| format!("Failed to write theme metadata: {e}"))
+    }
+
+    /// Write previously rendered and validated config files into a theme directory
+    fn write_app_configs(
+        theme_dir: &Path,
+        app_configs: Vec<(String, String)>,
+    ) -> Result<(), String> {
+        for (file_name, config_content) in app_configs {
+            let config_path = theme_dir.join(&file_name);
+            if let Some(parent) = config_path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+            }
+            log::debug!("Writing {}", config_path.display());
+            fs::write(&config_path, config_content)
+                .map_err(|e| format!("Failed to write {file_name}: {e}"))?;
         }
+
+        Ok(())
     }
 
     /// Update an existing theme (legacy method for backwards compatibility)
@@ -320,8 +484,7 @@ impl CustomThemeService {
 
     /// Delete a theme
     pub fn delete_theme(&self, name: &str) -> Result<(), String> {
-        let sanitized_name = Self::sanitize_name(name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+        let theme_dir = self.theme_dir(name)?;
 
         if !theme_dir.exists() {
             return Err(format!("Theme '{name}' not found"));
@@ -333,6 +496,77 @@ impl CustomThemeService {
         Ok(())
     }
 
+    /// Rewrite a theme's files from its stored data, e.g. after a generator changed
+    pub fn regenerate_theme(&self, name: &str) -> Result<CustomTheme, String> {
+        let theme_dir = self.existing_theme_dir(name)?;
+        let mut theme = self.load_theme_metadata(&Self::sanitize_name(name))?;
+
+        theme.apps = self.validate_theme_data(theme.apps)?;
+        if let Some(light_apps) = theme.light_apps.take() {
+            theme.light_apps = Some(self.validate_theme_data(light_apps)?);
+        }
+        theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
+
+        let active = theme_variants::active_variant(&theme_dir, &theme);
+        let app_configs = self.render_theme_files(&theme, active)?;
+        Self::write_app_configs(&theme_dir, app_configs)?;
+        Self::write_chromium_extension(&theme_dir, &theme, active);
+        Self::write_metadata(&theme_dir, &theme)?;
+
+        log::info!("Regenerated custom theme '{name}'");
+
+        Ok(theme)
+    }
+
+    /// A custom theme packed for an export bundle
+    pub fn bundle_theme(&self, name: &str) -> Result<bulk::BundledTheme, String> {
+        let theme_dir = self.existing_theme_dir(name)?;
+        let theme = self.load_theme_metadata(&Self::sanitize_name(name))?;
+        bulk::bundle_theme(&theme_dir, theme)
+    }
+
+    /// Create a custom theme from an export bundle entry, rendering its files again.
+    /// A theme of the same name is never replaced.
+    pub fn import_bundled_theme(&self, bundled: bulk::BundledTheme) -> Result<CustomTheme, String> {
+        let name = bundled.theme.name.clone();
+        let theme_dir = self.theme_dir(&name)?;
+        if theme_dir.exists() {
+            return Err(format!("Theme '{name}' already exists"));
+        }
+
+        let mut theme = bundled.theme;
+        theme.apps = self.validate_theme_data(theme.apps)?;
+        if let Some(light_apps) = theme.light_apps.take() {
+            theme.light_apps = Some(self.validate_theme_data(light_apps)?);
+        }
+        let app_configs = self.render_theme_files(&theme, ThemeVariant::Dark)?;
+
+        fs::create_dir_all(&theme_dir)
+            .map_err(|e| format!("Failed to create theme directory: {e}"))?;
+        let written = bulk::unpack_backgrounds(&theme_dir, &bundled.backgrounds)
+            .and_then(|()| {
+                theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
+                Self::write_app_configs(&theme_dir, app_configs)
+            })
+            .and_then(|()| {
+                Self::write_chromium_extension(&theme_dir, &theme, ThemeVariant::Dark);
+                Self::write_metadata(&theme_dir, &theme)
+            })
+            .and_then(|()| match bundled.manifest {
+                Some(theme_manifest) => manifest::save(&theme_dir, theme_manifest).map(|_| ()),
+                None => Self::write_manifest(&theme_dir, &name, ""),
+            });
+        // Leave no half-imported theme behind
+        if let Err(e) = written {
+            let _ = fs::remove_dir_all(&theme_dir);
+            return Err(e);
+        }
+
+        log::info!("Imported custom theme '{name}'");
+
+        Ok(theme)
+    }
+
     /// Initialize a new custom theme by copying template files
     pub fn init_theme(&self, name: String, description: String) -> Result<CustomTheme, String> {
         let sanitized_name = Self::sanitize_name(&name);
@@ -348,7 +582,8 @@ impl CustomThemeService {
             .map_err(|e| format!("Failed to create theme directory: {e}"))?;
 
         // Copy template files
-        self.copy_template_files(&theme_dir, &name, &description)?;
+        self.copy_template_files(&theme_dir, &name)?;
+        Self::write_manifest(&theme_dir, &name, &description)?;
 
         // Load the created theme metadata (this will automatically extract colors)
         let theme = self.load_theme_metadata(&sanitized_name)?;
@@ -363,12 +598,7 @@ impl CustomThemeService {
     }
 
     /// Copy all template files to the new theme directory
-    fn copy_template_files(
-        &self,
-        theme_dir: &Path,
-        name: &str,
-        description: &str,
-    ) -> Result<(), String> {
+    fn copy_template_files(&self, theme_dir: &Path, name: &str) -> Result<(), String> {
         // Get template directory path from Tauri resources
         let resource_dir = self
             .app_handle
@@ -386,19 +616,13 @@ impl CustomThemeService {
             ));
         }
 
-        self.copy_dir_recursive(&template_dir, theme_dir, name, description)?;
+        self.copy_dir_recursive(&template_dir, theme_dir, name)?;
 
         Ok(())
     }
 
     /// Recursively copy directory contents and replace placeholders in custom_theme.json
-    fn copy_dir_recursive(
-        &self,
-        src: &Path,
-        dst: &Path,
-        name: &str,
-        description: &str,
-    ) -> Result<(), String> {
+    fn copy_dir_recursive(&self, src: &Path, dst: &Path, name: &str) -> Result<(), String> {
         let entries =
             fs::read_dir(src).map_err(|e| format!("Failed to read template directory: {e}"))?;
 
@@ -411,16 +635,11 @@ impl CustomThemeService {
                 // Create directory and copy contents recursively
                 fs::create_dir_all(&dst_path)
                     .map_err(|e| format!("Failed to create directory: {e}"))?;
-                self.copy_dir_recursive(&src_path, &dst_path, name, description)?;
+                self.copy_dir_recursive(&src_path, &dst_path, name)?;
             } else {
                 // Check if this is the custom_theme.json template
                 if entry.file_name() == "custom_theme.json" {
-                    self.copy_and_process_metadata_template(
-                        &src_path,
-                        &dst_path,
-                        name,
-                        description,
-                    )?;
+                    self.copy_and_process_metadata_template(&src_path, &dst_path, name)?;
                 } else {
                     // Copy file normally
                     fs::copy(&src_path, &dst_path).map_err(|e| {
@@ -439,14 +658,13 @@ impl CustomThemeService {
         src: &Path,
         dst: &Path,
         name: &str,
-        _description: &str,
     ) -> Result<(), String> {
         let template_content = fs::read_to_string(src)
             .map_err(|e| format!("Failed to read metadata template: {e}"))?;
 
         let now = chrono::Utc::now().to_rfc3339();
 
-        // Replace placeholders (no description)
+        // Replace placeholders; the description lives in theme.toml
         let processed_content = template_content
             .replace("{{THEME_NAME}}", name)
             .replace("{{CREATED_AT}}", &now)
@@ -458,157 +676,153 @@ impl CustomThemeService {
         Ok(())
     }
 
+    /// Write the `theme.toml` of a new theme
+    fn write_manifest(theme_dir: &Path, name: &str, description: &str) -> Result<(), String> {
+        let theme_manifest = ThemeManifest {
+            title: Some(name.to_string()),
+            description: Some(description.to_string()),
+            ..Default::default()
+        };
+        manifest::save(theme_dir, theme_manifest).map(|_| ())
+    }
+
+    /// The manifest of any theme; themes without `theme.toml` get an empty one
+    pub fn get_theme_manifest(&self, theme_name: &str) -> Result<ThemeManifest, String> {
+        let theme_dir = self.theme_dir(theme_name)?;
+        if !theme_dir.is_dir() {
+            return Err(format!("Theme '{theme_name}' not found"));
+        }
+        Ok(manifest::load(&theme_dir).unwrap_or_default())
+    }
+
+    /// Replace a custom theme's manifest. Bundled themes belong to Omarchy and stay read-only.
+    pub fn update_theme_manifest(
+        &self,
+        theme_name: &str,
+        theme_manifest: ThemeManifest,
+    ) -> Result<ThemeManifest, String> {
+        manifest::save(&self.existing_theme_dir(theme_name)?, theme_manifest)
+    }
+
     /// Load theme metadata from JSON file
     fn load_theme_metadata(&self, sanitized_name: &str) -> Result<CustomTheme, String> {
         let theme_dir = self.themes_dir.join(sanitized_name);
-        let metadata_path = theme_dir.join("custom_theme.json");
-
-        let content = fs::read_to_string(&metadata_path)
-            .map_err(|e| format!("Failed to read theme metadata: {e}"))?;
-
-        let mut theme: CustomTheme = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse theme metadata: {e}"))?;
+        let mut theme = Self::read_metadata(&theme_dir)?;
 
         // If colors are missing (backwards compatibility), extract them now
         if theme.colors.is_none() {
             theme.colors = self.extract_theme_colors(&theme_dir, &theme.apps);
 
             // Save the updated metadata with colors
-            if let Ok(updated_content) = serde_json::to_string_pretty(&theme) {
-                if let Err(e) = fs::write(&metadata_path, updated_content) {
-                    log::warn!("Failed to update theme metadata with colors: {e}");
-                }
+            if let Err(e) = Self::write_metadata(&theme_dir, &theme) {
+                log::warn!("Failed to update theme metadata with colors: {e}");
             }
         }
 
         Ok(theme)
     }
-    /// Get list of background images for a theme
+    /// Get list of background images for a theme, in playlist order
     pub fn get_theme_backgrounds(&self, theme_name: &str) -> Result<Vec<String>, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
-        let backgrounds_dir = theme_dir.join("backgrounds");
-
-        if !backgrounds_dir.exists() {
-            return Ok(Vec::new());
-        }
-
-        let mut backgrounds = Vec::new();
-        let entries = fs::read_dir(&backgrounds_dir)
-            .map_err(|e| format!("Failed to read backgrounds directory: {e}"))?;
-
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
-            let path = entry.path();
-
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    let ext = extension.to_string_lossy().to_lowercase();
-                    if matches!(
-                        ext.as_str(),
-                        "jpg" | "jpeg" | "png" | "webp" | "bmp" | "gif"
-                    ) {
-                        if let Some(filename) = path.file_name() {
-                            backgrounds.push(filename.to_string_lossy().to_string());
-                        }
-                    }
-                }
-            }
-        }
-
-        backgrounds.sort();
-        Ok(backgrounds)
+        let theme_dir = self.theme_dir(theme_name)?;
+        let files = backgrounds::image_files(&theme_dir)?;
+        let theme = self
+            .load_theme_metadata(&Self::sanitize_name(theme_name))
+            .ok();
+        Ok(backgrounds::ordered(
+            theme.as_ref().and_then(|t| t.backgrounds.as_ref()),
+            files,
+        ))
     }
 
-    /// Add background images to a theme by copying files
+    /// Add background images to a theme by copying files; reports the outcome per source file
     pub fn add_theme_backgrounds(
         &self,
         theme_name: &str,
         source_paths: Vec<String>,
-    ) -> Result<Vec<String>, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
+    ) -> Result<Vec<BackgroundImport>, String> {
+        let theme_dir = self.theme_dir(theme_name)?;
 
         if !theme_dir.exists() {
             return Err(format!("Theme '{theme_name}' not found"));
         }
 
-        let backgrounds_dir = theme_dir.join("backgrounds");
+        background_import::import_backgrounds(&theme_dir, source_paths, &safe_path::import_roots())
+    }
 
-        // Create backgrounds directory if it doesn't exist
-        fs::create_dir_all(&backgrounds_dir)
-            .map_err(|e| format!("Failed to create backgrounds directory: {e}"))?;
+    /// Remove a background image from a theme
+    pub fn remove_theme_background(&self, theme_name: &str, filename: &str) -> Result<(), String> {
+        let theme_dir = self.theme_dir(theme_name)?;
+        let file_path = self.background_path(&theme_dir, filename)?;
 
-        let mut copied_files = Vec::new();
+        if !file_path.exists() {
+            return Err(format!("Background image '{filename}' not found"));
+        }
 
-        for source_path in source_paths {
-            let source = Path::new(&source_path);
+        fs::remove_file(&file_path)
+            .map_err(|e| format!("Failed to remove background image: {e}"))?;
 
-            if !source.exists() {
-                log::warn!("Source file does not exist: {source_path}");
-                continue;
-            }
+        log::debug!("Removed background image: {}", file_path.display());
+        backgrounds::forget(&theme_dir, filename)
+    }
 
-            if !source.is_file() {
-                log::warn!("Source path is not a file: {source_path}");
-                continue;
-            }
+    /// Directory of a theme; names that sanitize to nothing are refused
+    fn theme_dir(&self, theme_name: &str) -> Result<PathBuf, String> {
+        safe_path::theme_dir(&self.themes_dir, &Self::sanitize_name(theme_name))
+    }
 
-            // Validate file extension
-            if let Some(extension) = source.extension() {
-                let ext = extension.to_string_lossy().to_lowercase();
-                if !matches!(
-                    ext.as_str(),
-                    "jpg" | "jpeg" | "png" | "webp" | "bmp" | "gif"
-                ) {
-                    log::warn!("Unsupported image format: {source_path}");
-                    continue;
-                }
-            } else {
-                log::warn!("File has no extension: {source_path}");
-                continue;
-            }
+    /// A background file of a theme, refusing names and symlinks that leave `backgrounds/`
+    fn background_path(&self, theme_dir: &Path, filename: &str) -> Result<PathBuf, String> {
+        let backgrounds_dir = theme_dir.join(backgrounds::BACKGROUNDS_DIR);
+        if !backgrounds_dir.exists() {
+            return Err(format!("Background image '{filename}' not found"));
+        }
+        safe_path::child(&backgrounds_dir, filename)
+    }
 
-            // Get filename and create destination path
-            if let Some(filename) = source.file_name() {
-                let dest_path = backgrounds_dir.join(filename);
-
-                // Copy the file
-                match fs::copy(source, &dest_path) {
-                    Ok(_) => {
-                        copied_files.push(filename.to_string_lossy().to_string());
-                        log::debug!(
-                            "Copied background image: {} -> {}",
-                            source_path,
-                            dest_path.display()
-                        );
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to copy {source_path}: {e}");
-                    },
-                }
-            }
+    /// Directory of an existing custom theme
+    fn existing_theme_dir(&self, theme_name: &str) -> Result<PathBuf, String> {
+        let theme_dir = self.theme_dir(theme_name)?;
+        if !theme_dir.join("custom_theme.json").exists() {
+            return Err(format!("Theme '{theme_name}' not found"));
         }
+        Ok(theme_dir)
+    }
 
-        Ok(copied_files)
+    pub fn get_background_playlist(&self, theme_name: &str) -> Result<BackgroundPlaylist, String> {
+        backgrounds::playlist(&self.existing_theme_dir(theme_name)?)
     }
 
-    /// Remove a background image from a theme
-    pub fn remove_theme_background(&self, theme_name: &str, filename: &str) -> Result<(), String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
-        let backgrounds_dir = theme_dir.join("backgrounds");
-        let file_path = backgrounds_dir.join(filename);
+    pub fn reorder_backgrounds(
+        &self,
+        theme_name: &str,
+        order: Vec<String>,
+    ) -> Result<BackgroundPlaylist, String> {
+        backgrounds::reorder(&self.existing_theme_dir(theme_name)?, order)
+    }
 
-        if !file_path.exists() {
-            return Err(format!("Background image '{filename}' not found"));
-        }
+    pub fn set_active_background(
+        &self,
+        theme_name: &str,
+        filename: &str,
+    ) -> Result<BackgroundPlaylist, String> {
+        backgrounds::set_active(&self.existing_theme_dir(theme_name)?, filename)
+    }
 
-        fs::remove_file(&file_path)
-            .map_err(|e| format!("Failed to remove background image: {e}"))?;
+    /// Move to the next (`delta` 1) or previous (`delta` -1) background
+    pub fn step_background(
+        &self,
+        theme_name: &str,
+        delta: isize,
+    ) -> Result<BackgroundPlaylist, String> {
+        backgrounds::step_active(&self.existing_theme_dir(theme_name)?, delta)
+    }
 
-        log::debug!("Removed background image: {}", file_path.display());
-        Ok(())
+    pub fn set_background_rotation(
+        &self,
+        theme_name: &str,
+        minutes: u32,
+    ) -> Result<BackgroundPlaylist, String> {
+        backgrounds::set_rotation(&self.existing_theme_dir(theme_name)?, minutes)
     }
 
     /// Get background image data as base64 for preview
@@ -617,10 +831,8 @@ impl CustomThemeService {
         theme_name: &str,
         filename: &str,
     ) -> Result<String, String> {
-        let sanitized_name = Self::sanitize_name(theme_name);
-        let theme_dir = self.themes_dir.join(&sanitized_name);
-        let backgrounds_dir = theme_dir.join("backgrounds");
-        let file_path = backgrounds_dir.join(filename);
+        let theme_dir = self.theme_dir(theme_name)?;
+        let file_path = self.background_path(&theme_dir, filename)?;
 
         if !file_path.exists() {
             return Err(format!("Background image '{filename}' not found"));
@@ -768,9 +980,11 @@ pub async fn update_custom_theme_advanced(
     app_handle: AppHandle,
     name: String,
     theme_data: Value,
+    variant: Option<ThemeVariant>,
 ) -> Result<CustomTheme, String> {
     let service = CustomThemeService::new(&app_handle)?;
-    let result = service.update_theme_advanced(&name, theme_data);
+    let result =
+        service.update_theme_variant(&name, variant.unwrap_or(ThemeVariant::Dark), theme_data);
 
     // Invalidate cache for the updated theme
     if result.is_ok() {
@@ -784,6 +998,58 @@ pub async fn update_custom_theme_advanced(
     result
 }
 
+#[tauri::command]
+pub async fn create_light_variant(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<CustomTheme, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    let result = service.create_light_variant(&name);
+
+    if result.is_ok() {
+        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+            cache.invalidate_theme(&name).await;
+        }
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn remove_light_variant(
+    app_handle: AppHandle,
+    name: String,
+) -> Result<CustomTheme, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    let result = service.remove_light_variant(&name);
+
+    if result.is_ok() {
+        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+            cache.invalidate_theme(&name).await;
+        }
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn switch_theme_variant(
+    app_handle: AppHandle,
+    name: String,
+    variant: ThemeVariant,
+) -> Result<CustomTheme, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    let result = service.switch_variant(&name, variant);
+
+    if result.is_ok() {
+        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+            cache.invalidate_theme(&name).await;
+        }
+    }
+
+    result
+}
+
 #[tauri::command]
 pub async fn get_custom_theme(app_handle: AppHandle, name: String) -> Result<CustomTheme, String> {
     let service = CustomThemeService::new(&app_handle)?;
@@ -814,10 +1080,43 @@ pub async fn delete_custom_theme(app_handle: AppHandle, name: String) -> Result<
 }
 
 #[tauri::command]
-pub async fn init_custom_theme(app_handle: AppHandle, name: String) -> Result<CustomTheme, String> {
+pub async fn init_custom_theme(
+    app_handle: AppHandle,
+    name: String,
+    description: Option<String>,
+) -> Result<CustomTheme, String> {
     log::info!("Initializing custom theme '{name}'");
     let service = CustomThemeService::new(&app_handle)?;
-    service.init_theme(name, String::new())
+    service.init_theme(name, description.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_theme_manifest(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<ThemeManifest, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.get_theme_manifest(&theme_name)
+}
+
+#[tauri::command]
+pub async fn update_theme_manifest(
+    app_handle: AppHandle,
+    theme_name: String,
+    manifest: ThemeManifest,
+) -> Result<ThemeManifest, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    let result = service.update_theme_manifest(&theme_name, manifest);
+
+    // Title, description and preview are part of the cached theme list
+    if result.is_ok() {
+        if let Ok(cache) = crate::services::cache::cache_manager::get_theme_cache().await {
+            cache.invalidate_theme(&theme_name).await;
+            let _ = cache.trigger_background_refresh().await;
+        }
+    }
+
+    result
 }
 
 #[tauri::command]
@@ -840,7 +1139,7 @@ pub async fn add_theme_backgrounds(
     app_handle: AppHandle,
     theme_name: String,
     source_paths: Vec<String>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<BackgroundImport>, String> {
     let service = CustomThemeService::new(&app_handle)?;
     service.add_theme_backgrounds(&theme_name, source_paths)
 }
@@ -855,6 +1154,63 @@ pub async fn remove_theme_background(
     service.remove_theme_background(&theme_name, &filename)
 }
 
+#[tauri::command]
+pub async fn get_background_playlist(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<BackgroundPlaylist, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.get_background_playlist(&theme_name)
+}
+
+#[tauri::command]
+pub async fn reorder_theme_backgrounds(
+    app_handle: AppHandle,
+    theme_name: String,
+    order: Vec<String>,
+) -> Result<BackgroundPlaylist, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.reorder_backgrounds(&theme_name, order)
+}
+
+#[tauri::command]
+pub async fn set_active_background(
+    app_handle: AppHandle,
+    theme_name: String,
+    filename: String,
+) -> Result<BackgroundPlaylist, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.set_active_background(&theme_name, &filename)
+}
+
+#[tauri::command]
+pub async fn next_background(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<BackgroundPlaylist, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.step_background(&theme_name, 1)
+}
+
+#[tauri::command]
+pub async fn previous_background(
+    app_handle: AppHandle,
+    theme_name: String,
+) -> Result<BackgroundPlaylist, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.step_background(&theme_name, -1)
+}
+
+#[tauri::command]
+pub async fn set_background_rotation(
+    app_handle: AppHandle,
+    theme_name: String,
+    minutes: u32,
+) -> Result<BackgroundPlaylist, String> {
+    let service = CustomThemeService::new(&app_handle)?;
+    service.set_background_rotation(&theme_name, minutes)
+}
+
 #[tauri::command]
 pub async fn get_background_image_data(
     app_handle: AppHandle,
@@ -865,6 +1221,21 @@ pub async fn get_background_image_data(
     service.get_background_image_data(&theme_name, &filename)
 }
 
+#[tauri::command]
+pub async fn list_btop_themes() -> Result<Vec<BundledBtopTheme>, String> {
+    Ok(btop::list_bundled_themes(&btop::bundled_theme_dirs()))
+}
+
+/// Convert one of btop's own themes into custom-theme `apps` JSON for the editor to merge
+#[tauri::command]
+pub async fn convert_btop_theme(name: String) -> Result<Value, String> {
+    let theme = btop::list_bundled_themes(&btop::bundled_theme_dirs())
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("btop theme '{name}' not found"))?;
+    btop::convert_theme_file(Path::new(&theme.path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
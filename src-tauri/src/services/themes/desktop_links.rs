@@ -0,0 +1,473 @@
+use crate::services::settings::app_settings;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name used for the qt6ct color scheme and the Kvantum theme directory
+const QT_THEME_NAME: &str = "Omarchy";
+/// Suffix for user files moved aside when a link replaced them; later backups are numbered
+const BACKUP_SUFFIX: &str = "omarchist-backup";
+/// The user's qt6ct/Kvantum values from before a theme overrode them, under `config_home`
+const SAVED_SETTINGS: &str = "omarchist/desktop-settings.json";
+
+/// Previous values keyed by config file, then by key; `None` means the key was not set
+type SavedSettings = BTreeMap<String, BTreeMap<String, Option<String>>>;
+
+const QT6CT_CONFIG: &str = "qt6ct/qt6ct.conf";
+const KVANTUM_CONFIG: &str = "Kvantum/kvantum.kvconfig";
+
+/// A generated theme file and the config locations (relative to `$XDG_CONFIG_HOME`)
+/// where toolkits look for it
+struct DesktopLink {
+    source: &'static str,
+    targets: &'static [&'static str],
+}
+
+const DESKTOP_LINKS: [DesktopLink; 3] = [
+    DesktopLink {
+        source: "gtk.css",
+        targets: &["gtk-3.0/gtk.css", "gtk-4.0/gtk.css"],
+    },
+    DesktopLink {
+        source: "qt6ct.conf",
+        targets: &["qt6ct/colors/Omarchy.conf"],
+    },
+    DesktopLink {
+        source: "kvantum.kvconfig",
+        targets: &["Kvantum/Omarchy/Omarchy.kvconfig"],
+    },
+];
+
+/// The theme directory `omarchy-theme-set` points at the active theme
+pub fn current_theme_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/omarchy/current/theme"))
+}
+
+/// Whether `theme_dir` is the theme `current/theme` points at
+pub fn is_current_theme(theme_dir: &Path) -> bool {
+    let Some(current) = current_theme_dir().and_then(|dir| fs::canonicalize(dir).ok()) else {
+        return false;
+    };
+    fs::canonicalize(theme_dir).is_ok_and(|dir| dir == current)
+}
+
+/// Best-effort link of the active theme's GTK and Qt files into the user's config dir
+pub fn sync_current_theme() {
+    let (Some(theme_dir), Some(config_home)) = (current_theme_dir(), app_settings::config_home())
+    else {
+        log::warn!("Could not resolve config directories for GTK/Qt theme files");
+        return;
+    };
+
+    if let Err(e) = sync_desktop_links(&theme_dir, &config_home) {
+        log::warn!("Failed to link GTK/Qt theme files: {e}");
+    }
+}
+
+/// Symlink every GTK/Qt file in `theme_dir` into its toolkit location under `config_home`.
+///
+/// Links point at `theme_dir` itself, so when it is the `current/theme` symlink later theme
+/// switches are picked up without relinking. Existing user files are moved to a backup and
+/// never deleted; when one already exists the new backup gets a number.
+/// Links whose source is missing from the theme are removed and the newest backup is put
+/// back, and the qt6ct/Kvantum settings return to the user's own values.
+pub fn sync_desktop_links(theme_dir: &Path, config_home: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut linked = Vec::new();
+
+    for link in &DESKTOP_LINKS {
+        let source = theme_dir.join(link.source);
+        for target in link.targets.iter().map(|t| config_home.join(t)) {
+            if source.exists() {
+                link_file(&source, &target)?;
+                linked.push(target);
+            } else if is_link_into(&target, theme_dir) {
+                fs::remove_file(&target)
+                    .map_err(|e| format!("Failed to remove {}: {e}", target.display()))?;
+                restore_backup(&target)?;
+            }
+        }
+    }
+
+    if theme_dir.join("qt6ct.conf").exists() {
+        let scheme_path = config_home.join(DESKTOP_LINKS[1].targets[0]);
+        override_settings(
+            config_home,
+            QT6CT_CONFIG,
+            "Appearance",
+            &[
+                ("color_scheme_path", &scheme_path.to_string_lossy()),
+                ("custom_palette", "true"),
+            ],
+        )?;
+    } else {
+        restore_settings(config_home, QT6CT_CONFIG, "Appearance")?;
+    }
+
+    if theme_dir.join("kvantum.kvconfig").exists() {
+        override_settings(
+            config_home,
+            KVANTUM_CONFIG,
+            "General",
+            &[("theme", QT_THEME_NAME)],
+        )?;
+    } else {
+        restore_settings(config_home, KVANTUM_CONFIG, "General")?;
+    }
+
+    Ok(linked)
+}
+
+fn load_saved_settings(config_home: &Path) -> SavedSettings {
+    fs::read_to_string(config_home.join(SAVED_SETTINGS))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_saved_settings(config_home: &Path, saved: &SavedSettings) -> Result<(), String> {
+    let path = config_home.join(SAVED_SETTINGS);
+    if saved.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {e}", path.display()))
+            },
+            _ => Ok(()),
+        };
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let content = serde_json::to_string_pretty(saved)
+        .map_err(|e| format!("Failed to serialize saved settings: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Write the theme's values into `file`, first recording the user's own values unless an
+/// earlier theme already did
+fn override_settings(
+    config_home: &Path,
+    file: &str,
+    section: &str,
+    values: &[(&str, &str)],
+) -> Result<(), String> {
+    let path = config_home.join(file);
+    let mut saved = load_saved_settings(config_home);
+    if !saved.contains_key(file) {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let previous = values
+            .iter()
+            .map(|(key, _)| (key.to_string(), get_ini_value(&content, section, key)))
+            .collect();
+        saved.insert(file.to_string(), previous);
+        save_saved_settings(config_home, &saved)?;
+    }
+    update_ini_file(&path, section, values)
+}
+
+/// Put back the values recorded by [`override_settings`], if any
+fn restore_settings(config_home: &Path, file: &str, section: &str) -> Result<(), String> {
+    let mut saved = load_saved_settings(config_home);
+    let Some(previous) = saved.remove(file) else {
+        return Ok(());
+    };
+    let path = config_home.join(file);
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let restored = previous
+        .iter()
+        .fold(content, |acc, (key, value)| match value {
+            Some(value) => set_ini_value(&acc, section, key, value),
+            None => remove_ini_value(&acc, section, key),
+        });
+    fs::write(&path, restored).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    save_saved_settings(config_home, &saved)
+}
+
+fn link_file(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+
+    match fs::symlink_metadata(target) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            if fs::read_link(target).is_ok_and(|existing| existing == source) {
+                return Ok(());
+            }
+            fs::remove_file(target)
+                .map_err(|e| format!("Failed to replace {}: {e}", target.display()))?;
+        },
+        Ok(_) => {
+            let backup = backup_path(target);
+            log::info!("Backing up {} to {}", target.display(), backup.display());
+            fs::rename(target, &backup)
+                .map_err(|e| format!("Failed to back up {}: {e}", target.display()))?;
+        },
+        Err(_) => {},
+    }
+
+    std::os::unix::fs::symlink(source, target)
+        .map_err(|e| format!("Failed to link {}: {e}", target.display()))
+}
+
+/// `<target>.omarchist-backup`, or the first free `<target>.omarchist-backup.N`
+fn backup_path(target: &Path) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{BACKUP_SUFFIX}"));
+    let backup = target.with_file_name(&name);
+    if fs::symlink_metadata(&backup).is_err() {
+        return backup;
+    }
+    (1..)
+        .map(|n| {
+            let mut numbered = name.clone();
+            numbered.push(format!(".{n}"));
+            target.with_file_name(numbered)
+        })
+        .find(|path| fs::symlink_metadata(path).is_err())
+        .expect("some backup number is free")
+}
+
+/// Move the newest backup of `target` back in place, if there is one
+fn restore_backup(target: &Path) -> Result<(), String> {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{BACKUP_SUFFIX}"));
+    let first = target.with_file_name(&name);
+    let newest = (1..)
+        .map(|n| {
+            let mut numbered = name.clone();
+            numbered.push(format!(".{n}"));
+            target.with_file_name(numbered)
+        })
+        .take_while(|path| fs::symlink_metadata(path).is_ok())
+        .last()
+        .or_else(|| fs::symlink_metadata(&first).is_ok().then_some(first));
+    let Some(backup) = newest else {
+        return Ok(());
+    };
+    log::info!("Restoring {} from {}", target.display(), backup.display());
+    fs::rename(&backup, target).map_err(|e| format!("Failed to restore {}: {e}", target.display()))
+}
+
+fn is_link_into(target: &Path, theme_dir: &Path) -> bool {
+    fs::symlink_metadata(target).is_ok_and(|m| m.file_type().is_symlink())
+        && fs::read_link(target).is_ok_and(|dest| dest.starts_with(theme_dir))
+}
+
+fn update_ini_file(path: &Path, section: &str, values: &[(&str, &str)]) -> Result<(), String> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let updated = values.iter().fold(content, |acc, (key, value)| {
+        set_ini_value(&acc, section, key, value)
+    });
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    fs::write(path, updated).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Range of the lines after `[section]` up to the next section header
+fn section_lines(lines: &[String], section: &str) -> Option<(usize, usize)> {
+    let header = format!("[{section}]");
+    let start = lines.iter().position(|l| l.trim() == header)? + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| start + i);
+    Some((start, end))
+}
+
+fn key_line(lines: &[String], start: usize, end: usize, key: &str) -> Option<usize> {
+    lines[start..end]
+        .iter()
+        .position(|l| l.split_once('=').is_some_and(|(k, _)| k.trim() == key))
+        .map(|i| start + i)
+}
+
+fn get_ini_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let (start, end) = section_lines(&lines, section)?;
+    let line = &lines[key_line(&lines, start, end, key)?];
+    line.split_once('=')
+        .map(|(_, value)| value.trim().to_string())
+}
+
+/// Drop `key` from `[section]`, keeping every other line as it is
+fn remove_ini_value(content: &str, section: &str, key: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let Some((start, end)) = section_lines(&lines, section) else {
+        return content.to_string();
+    };
+    let Some(line) = key_line(&lines, start, end, key) else {
+        return content.to_string();
+    };
+    lines.remove(line);
+    lines.join("\n") + "\n"
+}
+
+/// Set `key=value` inside `[section]`, keeping every other line as it is
+pub fn set_ini_value(content: &str, section: &str, key: &str, value: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let entry = format!("{key}={value}");
+
+    let Some((start, end)) = section_lines(&lines, section) else {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(format!("[{section}]"));
+        lines.push(entry);
+        return lines.join("\n") + "\n";
+    };
+
+    match key_line(&lines, start, end, key) {
+        Some(i) => lines[i] = entry,
+        None => {
+            // Insert after the last non-blank line of the section
+            let insert_at = lines[start..end]
+                .iter()
+                .rposition(|l| !l.trim().is_empty())
+                .map_or(start, |i| start + 1 + i);
+            lines.insert(insert_at, entry);
+        },
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_ini_value() {
+        let content = "[Appearance]\nstyle=Fusion\n\n[Fonts]\ngeneral=Sans\n";
+        let updated = set_ini_value(content, "Appearance", "custom_palette", "true");
+        assert_eq!(
+            updated,
+            "[Appearance]\nstyle=Fusion\ncustom_palette=true\n\n[Fonts]\ngeneral=Sans\n"
+        );
+
+        let updated = set_ini_value(&updated, "Appearance", "style", "kvantum");
+        assert!(updated.contains("style=kvantum\n"));
+        assert!(!updated.contains("Fusion"));
+
+        assert_eq!(
+            set_ini_value("", "General", "theme", "Omarchy"),
+            "[General]\ntheme=Omarchy\n"
+        );
+    }
+
+    #[test]
+    fn test_sync_links_and_backs_up_user_files() {
+        let temp = TempDir::new().unwrap();
+        let theme_dir = temp.path().join("theme");
+        let config_home = temp.path().join("config");
+        fs::create_dir_all(&theme_dir).unwrap();
+        fs::create_dir_all(config_home.join("gtk-4.0")).unwrap();
+        fs::write(
+            theme_dir.join("gtk.css"),
+            "@define-color window_bg_color #000000;",
+        )
+        .unwrap();
+        fs::write(theme_dir.join("kvantum.kvconfig"), "[GeneralColors]\n").unwrap();
+        fs::write(config_home.join("gtk-4.0/gtk.css"), "/* mine */").unwrap();
+
+        let linked = sync_desktop_links(&theme_dir, &config_home).unwrap();
+        assert_eq!(linked.len(), 3);
+
+        let gtk4 = config_home.join("gtk-4.0/gtk.css");
+        assert_eq!(fs::read_link(&gtk4).unwrap(), theme_dir.join("gtk.css"));
+        assert_eq!(
+            fs::read_to_string(config_home.join("gtk-4.0/gtk.css.omarchist-backup")).unwrap(),
+            "/* mine */"
+        );
+        assert!(!config_home.join("qt6ct/colors/Omarchy.conf").exists());
+        assert_eq!(
+            fs::read_to_string(config_home.join("Kvantum/kvantum.kvconfig")).unwrap(),
+            "[General]\ntheme=Omarchy\n"
+        );
+
+        // Syncing again is a no-op
+        sync_desktop_links(&theme_dir, &config_home).unwrap();
+        assert_eq!(fs::read_link(&gtk4).unwrap(), theme_dir.join("gtk.css"));
+
+        // A user file written over the link later gets its own backup next to the first
+        fs::remove_file(&gtk4).unwrap();
+        fs::write(&gtk4, "/* mine, edited */").unwrap();
+        sync_desktop_links(&theme_dir, &config_home).unwrap();
+        assert_eq!(
+            fs::read_to_string(config_home.join("gtk-4.0/gtk.css.omarchist-backup")).unwrap(),
+            "/* mine */"
+        );
+        assert_eq!(
+            fs::read_to_string(config_home.join("gtk-4.0/gtk.css.omarchist-backup.1")).unwrap(),
+            "/* mine, edited */"
+        );
+    }
+
+    #[test]
+    fn test_switching_away_restores_user_files_and_settings() {
+        let temp = TempDir::new().unwrap();
+        let custom = temp.path().join("custom");
+        let stock = temp.path().join("stock");
+        let current = temp.path().join("current");
+        let config_home = temp.path().join("config");
+        fs::create_dir_all(&custom).unwrap();
+        fs::create_dir_all(&stock).unwrap();
+        fs::create_dir_all(config_home.join("gtk-3.0")).unwrap();
+        fs::create_dir_all(config_home.join("qt6ct")).unwrap();
+        fs::write(custom.join("gtk.css"), "").unwrap();
+        fs::write(custom.join("qt6ct.conf"), "[ColorScheme]\n").unwrap();
+        fs::write(custom.join("kvantum.kvconfig"), "[GeneralColors]\n").unwrap();
+        fs::write(config_home.join("gtk-3.0/gtk.css"), "/* mine */").unwrap();
+        let qt6ct =
+            "[Appearance]\ncolor_scheme_path=/usr/share/qt6ct/colors/darker.conf\nstyle=Fusion\n";
+        fs::write(config_home.join(QT6CT_CONFIG), qt6ct).unwrap();
+
+        // `current` stands in for `current/theme`, which Omarchy repoints on every switch
+        std::os::unix::fs::symlink(&custom, &current).unwrap();
+        sync_desktop_links(&current, &config_home).unwrap();
+        let applied = fs::read_to_string(config_home.join(QT6CT_CONFIG)).unwrap();
+        assert!(applied.contains("custom_palette=true"));
+        assert!(config_home.join(SAVED_SETTINGS).exists());
+
+        fs::remove_file(&current).unwrap();
+        std::os::unix::fs::symlink(&stock, &current).unwrap();
+        sync_desktop_links(&current, &config_home).unwrap();
+
+        let gtk3 = config_home.join("gtk-3.0/gtk.css");
+        assert!(!fs::symlink_metadata(&gtk3)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&gtk3).unwrap(), "/* mine */");
+        assert!(fs::symlink_metadata(config_home.join("gtk-4.0/gtk.css")).is_err());
+        assert_eq!(
+            fs::read_to_string(config_home.join(QT6CT_CONFIG)).unwrap(),
+            qt6ct
+        );
+        assert_eq!(
+            fs::read_to_string(config_home.join(KVANTUM_CONFIG)).unwrap(),
+            "[General]\n"
+        );
+        assert!(!config_home.join(SAVED_SETTINGS).exists());
+    }
+
+    #[test]
+    fn test_sync_removes_links_for_missing_files() {
+        let temp = TempDir::new().unwrap();
+        let theme_dir = temp.path().join("theme");
+        let config_home = temp.path().join("config");
+        fs::create_dir_all(&theme_dir).unwrap();
+        fs::write(theme_dir.join("gtk.css"), "").unwrap();
+
+        sync_desktop_links(&theme_dir, &config_home).unwrap();
+        assert!(config_home.join("gtk-3.0/gtk.css").exists());
+
+        fs::remove_file(theme_dir.join("gtk.css")).unwrap();
+        sync_desktop_links(&theme_dir, &config_home).unwrap();
+        assert!(fs::symlink_metadata(config_home.join("gtk-3.0/gtk.css")).is_err());
+    }
+}
@@ -1,11 +1,18 @@
 use regex::Regex;
 use serde::Serialize;
-use std::{fs, io, path::PathBuf};
+use std::{collections::HashMap, fs, io, path::PathBuf};
 
 #[derive(Debug, Serialize)]
 pub struct SystemColors {
     pub foreground: String,
     pub background: String,
+    /// Extended palette from Omarchist-generated themes; `None` for themes that only
+    /// define background and foreground
+    pub accent: Option<String>,
+    pub warning: Option<String>,
+    pub critical: Option<String>,
+    pub muted: Option<String>,
+    pub module_background: Option<String>,
 }
 
 #[tauri::command]
@@ -24,17 +31,64 @@ pub fn get_system_theme_colors() -> Result<Option<SystemColors>, String> {
         },
     };
 
-    let re_bg = Regex::new(r"(?mi)@define-color\s+background\s+([^;]+);").unwrap();
-    let re_fg = Regex::new(r"(?mi)@define-color\s+foreground\s+([^;]+);").unwrap();
+    Ok(parse_system_colors(&content))
+}
+
+/// Read the `@define-color` variables of a waybar.css, following `@name` references
+pub fn parse_system_colors(content: &str) -> Option<SystemColors> {
+    let re = Regex::new(r"(?mi)@define-color\s+([\w-]+)\s+([^;]+);").unwrap();
+    let defined: HashMap<String, String> = re
+        .captures_iter(content)
+        .map(|c| (c[1].to_lowercase(), c[2].trim().to_string()))
+        .collect();
+
+    let resolve = |name: &str| {
+        let mut value = defined.get(name)?;
+        // Bounded so a reference cycle cannot loop forever
+        for _ in 0..defined.len() {
+            match value.strip_prefix('@') {
+                Some(reference) => value = defined.get(&reference.to_lowercase())?,
+                None => return Some(value.clone()),
+            }
+        }
+        None
+    };
+
+    Some(SystemColors {
+        foreground: resolve("foreground")?,
+        background: resolve("background")?,
+        accent: resolve("accent"),
+        warning: resolve("warning"),
+        critical: resolve("critical"),
+        muted: resolve("muted"),
+        module_background: resolve("module_background"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_system_colors() {
+        let basic = parse_system_colors(
+            "@define-color foreground #cdd6f4;\n@define-color background #181824;\n",
+        )
+        .unwrap();
+        assert_eq!(basic.foreground, "#cdd6f4");
+        assert_eq!(basic.background, "#181824");
+        assert_eq!(basic.accent, None);
 
-    let background = re_bg.captures(&content).map(|c| c[1].trim().to_string());
-    let foreground = re_fg.captures(&content).map(|c| c[1].trim().to_string());
+        let extended = parse_system_colors(
+            "@define-color background #1e1e1e;\n@define-color foreground #8a8a8d;\n\
+             @define-color accent #f59e0b;\n@define-color module_background @background;\n\
+             @define-color muted @loop;\n@define-color loop @muted;\n",
+        )
+        .unwrap();
+        assert_eq!(extended.accent.as_deref(), Some("#f59e0b"));
+        assert_eq!(extended.module_background.as_deref(), Some("#1e1e1e"));
+        assert_eq!(extended.muted, None);
 
-    match (background, foreground) {
-        (Some(background), Some(foreground)) => Ok(Some(SystemColors {
-            foreground,
-            background,
-        })),
-        _ => Ok(None),
+        assert!(parse_system_colors("@define-color background #000;").is_none());
     }
 }
@@ -1,8 +1,9 @@
 use super::color_extraction::ColorExtractor;
+use super::manifest;
 use super::optimized_theme_loader::{OptimizedThemeLoader, ThemeMetadata};
 use crate::services::cache::cache_manager::get_theme_cache;
-use crate::types::ThemeColors;
-use dirs;
+use crate::services::safe_path;
+use crate::types::{ThemeColors, ThemeManifest};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -17,6 +18,9 @@ pub struct SysTheme {
     pub is_system: bool,             // Indicates if this is a system theme
     pub is_custom: bool,             // Indicates if this is a custom theme
     pub colors: Option<ThemeColors>, // Extracted color palette from theme configuration
+    /// Contents of the theme's `theme.toml`, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<ThemeManifest>,
 }
 
 /// Global instance of the optimized theme loader
@@ -109,17 +113,9 @@ fn extract_theme_colors(theme_dir: &Path, is_custom: bool) -> Option<ThemeColors
         }
     }
 
-    // For system themes or fallback, try to extract from alacritty.toml
-    let alacritty_config_path = theme_dir.join("alacritty.toml");
-    if alacritty_config_path.exists() {
-        match ColorExtractor::extract_from_alacritty_config(&alacritty_config_path) {
-            Some(colors) => return Some(colors),
-            None => {
-                log::warn!(
-                    "Failed to extract colors from Alacritty config at {alacritty_config_path:?}"
-                );
-            },
-        }
+    // For system themes or fallback, try to extract from the terminal configs
+    if let Some(colors) = ColorExtractor::extract_from_theme_dir(theme_dir) {
+        return Some(colors);
     }
 
     // No extractable colors found - this is handled gracefully by returning None
@@ -133,9 +129,11 @@ fn generate_theme_from_directory(theme_dir: &Path) -> Result<SysTheme, String> {
         .and_then(|name| name.to_str())
         .ok_or_else(|| "Invalid directory name".to_string())?;
 
+    let manifest = manifest::load(theme_dir);
+
     // Convert directory name to a nice title (replace hyphens/underscores with spaces and capitalize)
     // Optimized version that avoids multiple string allocations
-    let title = {
+    let derived_title = {
         let mut title = String::with_capacity(dir_name.len() + 10); // Pre-allocate with estimated capacity
         let mut capitalize_next = true;
 
@@ -157,11 +155,26 @@ fn generate_theme_from_directory(theme_dir: &Path) -> Result<SysTheme, String> {
         title
     };
 
-    // Look for any image files with common extensions
-    let mut image_path = String::new();
-
-    // Read all files in the theme directory and look for image files
-    if let Ok(entries) = fs::read_dir(theme_dir) {
+    let title = manifest
+        .as_ref()
+        .and_then(|m| m.title.clone())
+        .unwrap_or(derived_title);
+
+    // The manifest's preview image wins over the first image found in the directory
+    let mut image_path = manifest
+        .as_ref()
+        .and_then(|m| m.preview.as_deref())
+        .and_then(|preview| manifest::preview_path(theme_dir, preview).ok())
+        .and_then(|path| convert_image_to_data_url(&path).ok())
+        .unwrap_or_default();
+
+    // Otherwise read all files in the theme directory and look for image files
+    let entries = if image_path.is_empty() {
+        fs::read_dir(theme_dir).ok()
+    } else {
+        None
+    };
+    if let Some(entries) = entries {
         for entry in entries.flatten() {
             let file_path = entry.path();
             // Check if it's a file (not a directory) and has an image extension
@@ -204,11 +217,15 @@ fn generate_theme_from_directory(theme_dir: &Path) -> Result<SysTheme, String> {
     Ok(SysTheme {
         dir: dir_name.to_string(),
         title,
-        description: format!("Auto-generated theme from {dir_name}"),
+        description: manifest
+            .as_ref()
+            .and_then(|m| m.description.clone())
+            .unwrap_or_default(),
         image: image_path,
         is_system,
         is_custom,
         colors,
+        manifest,
     })
 }
 
@@ -274,8 +291,7 @@ fn base64_encode(data: &[u8]) -> String {
 /// Get a specific system theme by folder name
 #[tauri::command]
 pub async fn get_sys_theme_by_name(theme_name: String) -> Result<Option<SysTheme>, String> {
-    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
-    let theme_path = home_dir.join(".config/omarchy/themes").join(&theme_name);
+    let theme_path = safe_path::theme_dir(&safe_path::themes_root()?, &theme_name)?;
 
     if !theme_path.exists() || !theme_path.is_dir() {
         return Ok(None);
@@ -1,5 +1,6 @@
 // This file contains the functions to get the themes from the data/themes.toml file
 
+use super::catalog::{CatalogEntry, CatalogManager};
 use crate::types::{Theme, ThemeData};
 use std::path::PathBuf;
 use tauri::AppHandle;
@@ -13,9 +14,9 @@ fn get_data_dir(app_handle: &AppHandle) -> Result<PathBuf, Box<dyn std::error::E
     Ok(resource_dir.join("data"))
 }
 
-#[tauri::command]
-pub async fn get_themes(app_handle: AppHandle) -> Result<Vec<Theme>, String> {
-    let data_dir = get_data_dir(&app_handle).map_err(|e| e.to_string())?;
+/// Entries of the bundled `themes.toml` catalog
+pub fn load_catalog(app_handle: &AppHandle) -> Result<Vec<Theme>, String> {
+    let data_dir = get_data_dir(app_handle).map_err(|e| e.to_string())?;
     let themes_path = data_dir.join("themes.toml");
 
     let content = std::fs::read_to_string(&themes_path)
@@ -26,3 +27,10 @@ pub async fn get_themes(app_handle: AppHandle) -> Result<Vec<Theme>, String> {
 
     Ok(theme_data.theme)
 }
+
+/// The catalog with each entry marked installed, outdated or not installed
+#[tauri::command]
+pub async fn get_themes(app_handle: AppHandle) -> Result<Vec<CatalogEntry>, String> {
+    let catalog = load_catalog(&app_handle)?;
+    Ok(CatalogManager::from_env()?.entries(catalog))
+}
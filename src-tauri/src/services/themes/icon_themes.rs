@@ -0,0 +1,202 @@
+use serde::Serialize;
+use std::sync::{OnceLock, PoisonError, RwLock};
+use std::{fs, path::PathBuf};
+
+/// Used when nothing is configured, matching Omarchy's own fallback
+pub const FALLBACK_ICON_THEME: &str = "Yaru-blue";
+
+/// The last scan of [`icon_theme_dirs`], replaced by [`refresh_icon_themes`]
+static INSTALLED_ICON_THEMES: OnceLock<RwLock<Vec<IconTheme>>> = OnceLock::new();
+
+/// An installed icon theme, read from its `index.theme`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct IconTheme {
+    /// Directory name; this is what `gsettings` and `icons.theme` refer to
+    pub id: String,
+    /// Display name from `Name=`
+    pub name: String,
+    pub inherits: Vec<String>,
+    pub directories: Vec<String>,
+    pub path: String,
+}
+
+/// Icon theme locations, highest precedence first: `~/.icons`, `$XDG_DATA_HOME/icons`
+/// (usually `~/.local/share/icons`) and `/usr/share/icons`
+pub fn icon_theme_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".icons"));
+    }
+    let data_dir = match std::env::var("XDG_DATA_HOME") {
+        Ok(xdg_data_home) => Some(PathBuf::from(xdg_data_home)),
+        Err(_) => dirs::data_dir(),
+    };
+    if let Some(data_dir) = data_dir {
+        dirs.push(data_dir.join("icons"));
+    }
+    dirs.push(PathBuf::from("/usr/share/icons"));
+    dirs
+}
+
+/// Parse the `[Icon Theme]` group of an `index.theme`. Cursor-only and hidden themes have
+/// no icons to offer, so they yield `None`.
+pub fn parse_index_theme(id: &str, content: &str) -> Option<IconTheme> {
+    let mut in_group = false;
+    let mut name = None;
+    let mut inherits = Vec::new();
+    let mut directories = Vec::new();
+    let mut hidden = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_group = line == "[Icon Theme]";
+            continue;
+        }
+        if !in_group || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let list = || -> Vec<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Inherits" => inherits = list(),
+            "Directories" => directories = list(),
+            "Hidden" => hidden = value.trim().eq_ignore_ascii_case("true"),
+            _ => {},
+        }
+    }
+
+    if hidden || directories.is_empty() {
+        return None;
+    }
+    Some(IconTheme {
+        id: id.to_string(),
+        name: name.unwrap_or_else(|| id.to_string()),
+        inherits,
+        directories,
+        path: String::new(),
+    })
+}
+
+/// Every icon theme in `dirs`, sorted by id. Earlier directories win on id clashes.
+pub fn scan_icon_themes(dirs: &[PathBuf]) -> Vec<IconTheme> {
+    let mut themes: Vec<IconTheme> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            let Some(id) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if themes.iter().any(|t| t.id == id) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path.join("index.theme")) else {
+                continue;
+            };
+            if let Some(mut theme) = parse_index_theme(id, &content) {
+                theme.path = path.to_string_lossy().to_string();
+                themes.push(theme);
+            }
+        }
+    }
+    themes.sort_by(|a, b| a.id.cmp(&b.id));
+    themes
+}
+
+fn installed() -> &'static RwLock<Vec<IconTheme>> {
+    INSTALLED_ICON_THEMES.get_or_init(|| RwLock::new(scan_icon_themes(&icon_theme_dirs())))
+}
+
+/// Installed icon themes, scanned on first use
+pub fn installed_icon_themes() -> Vec<IconTheme> {
+    installed()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Scan the icon directories again, picking up themes installed since the last scan
+pub fn refresh_icon_themes() -> Vec<IconTheme> {
+    let themes = scan_icon_themes(&icon_theme_dirs());
+    *installed().write().unwrap_or_else(PoisonError::into_inner) = themes.clone();
+    themes
+}
+
+pub fn is_installed(id: &str) -> bool {
+    installed_icon_themes().iter().any(|t| t.id == id)
+}
+
+/// Installed icon themes; `refresh` rescans the icon directories first
+#[tauri::command]
+pub fn list_icon_themes(refresh: Option<bool>) -> Vec<IconTheme> {
+    if refresh.unwrap_or(false) {
+        refresh_icon_themes()
+    } else {
+        installed_icon_themes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_theme(dir: &std::path::Path, id: &str, index: &str) {
+        fs::create_dir_all(dir.join(id)).unwrap();
+        fs::write(dir.join(id).join("index.theme"), index).unwrap();
+    }
+
+    #[test]
+    fn test_parse_index_theme() {
+        let theme = parse_index_theme(
+            "Yaru-blue",
+            "[Icon Theme]\nName=Yaru Blue\nInherits=Yaru, hicolor\nDirectories=16x16/apps,scalable/apps\n\n[16x16/apps]\nSize=16\n",
+        )
+        .unwrap();
+        assert_eq!(theme.name, "Yaru Blue");
+        assert_eq!(theme.inherits, vec!["Yaru", "hicolor"]);
+        assert_eq!(theme.directories, vec!["16x16/apps", "scalable/apps"]);
+
+        // Cursor themes have no Directories
+        assert!(parse_index_theme("default", "[Icon Theme]\nInherits=Adwaita\n").is_none());
+        assert!(parse_index_theme("x", "[Icon Theme]\nHidden=true\nDirectories=apps\n").is_none());
+    }
+
+    #[test]
+    fn test_scan_prefers_earlier_dirs() {
+        let user = TempDir::new().unwrap();
+        let system = TempDir::new().unwrap();
+        write_theme(
+            user.path(),
+            "Papirus",
+            "[Icon Theme]\nName=Mine\nDirectories=apps\n",
+        );
+        write_theme(
+            system.path(),
+            "Papirus",
+            "[Icon Theme]\nName=Papirus\nDirectories=apps\n",
+        );
+        write_theme(
+            system.path(),
+            "Adwaita",
+            "[Icon Theme]\nName=Adwaita\nDirectories=apps\n",
+        );
+        fs::create_dir_all(system.path().join("no-index")).unwrap();
+
+        let themes = scan_icon_themes(&[user.path().to_path_buf(), system.path().to_path_buf()]);
+        let ids: Vec<&str> = themes.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["Adwaita", "Papirus"]);
+        assert_eq!(themes[1].name, "Mine");
+    }
+}
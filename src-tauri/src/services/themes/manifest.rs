@@ -0,0 +1,145 @@
+//! `theme.toml`: a theme's title, description, author, homepage, license, version, tags and
+//! preview image. Both theme loaders read it; custom themes get one when they are created.
+
+use super::theme_library::normalize_tags;
+use crate::services::safe_path;
+use crate::types::ThemeManifest;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "theme.toml";
+
+const PREVIEW_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "gif", "svg"];
+
+/// The theme's manifest; `None` when it has none or it cannot be parsed
+pub fn load(theme_dir: &Path) -> Option<ThemeManifest> {
+    let path = theme_dir.join(MANIFEST_FILE);
+    let content = fs::read_to_string(&path).ok()?;
+    toml::from_str(&content)
+        .map_err(|e| log::warn!("Failed to parse {}: {e}", path.display()))
+        .ok()
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Trim fields, normalize tags and check the homepage and preview image
+pub fn normalize(theme_dir: &Path, manifest: ThemeManifest) -> Result<ThemeManifest, String> {
+    let homepage = clean(manifest.homepage);
+    if let Some(url) = &homepage {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!("Homepage '{url}' must be an http(s) URL"));
+        }
+    }
+
+    let preview = clean(manifest.preview);
+    if let Some(preview) = &preview {
+        preview_path(theme_dir, preview)?;
+    }
+
+    Ok(ThemeManifest {
+        title: clean(manifest.title),
+        description: clean(manifest.description),
+        author: clean(manifest.author),
+        homepage,
+        license: clean(manifest.license),
+        version: clean(manifest.version),
+        tags: normalize_tags(manifest.tags),
+        preview,
+    })
+}
+
+/// The preview image, which must be an existing image file inside the theme directory
+pub fn preview_path(theme_dir: &Path, preview: &str) -> Result<PathBuf, String> {
+    if Path::new(preview).is_absolute() {
+        return Err(format!("Preview '{preview}' must be relative to the theme"));
+    }
+    let path = safe_path::contained_in(theme_dir, &theme_dir.join(preview))?;
+    let is_image = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| PREVIEW_EXTENSIONS.contains(&ext.as_str()));
+    if !is_image || !path.is_file() {
+        return Err(format!("Preview '{preview}' is not an image in the theme"));
+    }
+    Ok(path)
+}
+
+/// Normalize and write the manifest, returning what was written
+pub fn save(theme_dir: &Path, manifest: ThemeManifest) -> Result<ThemeManifest, String> {
+    let manifest = normalize(theme_dir, manifest)?;
+    let content = toml::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize theme manifest: {e}"))?;
+    fs::write(theme_dir.join(MANIFEST_FILE), content)
+        .map_err(|e| format!("Failed to write theme manifest: {e}"))?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("backgrounds")).unwrap();
+        fs::write(dir.path().join("backgrounds").join("1.png"), b"img").unwrap();
+
+        let saved = save(
+            dir.path(),
+            ThemeManifest {
+                title: Some(" Forest ".into()),
+                description: Some("Greens and browns".into()),
+                author: Some("".into()),
+                homepage: Some("https://example.com/forest".into()),
+                version: Some("1.0.0".into()),
+                tags: vec!["Green".into(), "warm".into()],
+                preview: Some("backgrounds/1.png".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(saved.title.as_deref(), Some("Forest"));
+        assert_eq!(saved.author, None);
+        assert_eq!(saved.tags, vec!["green", "warm"]);
+        assert_eq!(load(dir.path()), Some(saved));
+    }
+
+    #[test]
+    fn test_missing_or_broken_manifest() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(load(dir.path()), None);
+
+        fs::write(dir.path().join(MANIFEST_FILE), "title = [").unwrap();
+        assert_eq!(load(dir.path()), None);
+    }
+
+    #[test]
+    fn test_invalid_homepage_and_preview() {
+        let theme = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.png"), b"img").unwrap();
+        fs::write(theme.path().join("notes.txt"), b"text").unwrap();
+
+        let with = |homepage: Option<&str>, preview: Option<String>| ThemeManifest {
+            homepage: homepage.map(str::to_string),
+            preview,
+            ..Default::default()
+        };
+        assert!(normalize(theme.path(), with(Some("javascript:alert(1)"), None)).is_err());
+        let escape = format!(
+            "../{}/secret.png",
+            outside.path().file_name().unwrap().to_string_lossy()
+        );
+        assert!(normalize(theme.path(), with(None, Some(escape))).is_err());
+        let absolute = outside.path().join("secret.png").display().to_string();
+        assert!(normalize(theme.path(), with(None, Some(absolute))).is_err());
+        assert!(normalize(theme.path(), with(None, Some("notes.txt".into()))).is_err());
+        assert!(normalize(theme.path(), with(None, Some("missing.png".into()))).is_err());
+    }
+}
@@ -1,11 +1,24 @@
 // Theme-related services
+pub mod background_import;
+pub mod backgrounds;
+pub mod bulk;
+pub mod catalog;
+pub mod chromium_extension;
 pub mod color_extraction;
 pub mod custom_themes;
+pub mod desktop_links;
 pub mod get_current_theme;
 pub mod get_sys_themes;
 pub mod get_themes;
+pub mod icon_themes;
+pub mod manifest;
 pub mod optimized_theme_loader;
+pub mod schedule;
 pub mod theme_cache;
+pub mod theme_compare;
+pub mod theme_library;
+pub mod theme_query;
+pub mod theme_variants;
 
 // Re-export commonly used types
 pub use color_extraction::ColorExtractor;
@@ -1,5 +1,6 @@
 use super::color_extraction::ColorExtractor;
 use super::get_sys_themes::SysTheme;
+use super::manifest;
 use crate::types::ThemeColors;
 use dirs;
 use serde::{Deserialize, Serialize};
@@ -233,8 +234,12 @@ impl OptimizedThemeLoader {
             .and_then(|name| name.to_str())
             .ok_or_else(|| "Invalid directory name".to_string())?;
 
-        // Convert directory name to a nice title (optimized)
-        let title = Self::dir_name_to_title(dir_name);
+        // The manifest's title, else the directory name as a nice title (optimized)
+        let theme_manifest = manifest::load(theme_dir);
+        let title = theme_manifest
+            .as_ref()
+            .and_then(|m| m.title.clone())
+            .unwrap_or_else(|| Self::dir_name_to_title(dir_name));
 
         let is_custom = theme_dir.join("custom_theme.json").is_file();
 
@@ -249,7 +254,7 @@ impl OptimizedThemeLoader {
 
         // Check if theme has color configuration files
         let has_colors = theme_dir.join("custom_theme.json").exists()
-            || theme_dir.join("alacritty.toml").exists();
+            || ColorExtractor::has_terminal_config(theme_dir);
 
         // Check if theme has image files
         let has_image = Self::has_image_files(theme_dir);
@@ -295,8 +300,12 @@ impl OptimizedThemeLoader {
             .and_then(|name| name.to_str())
             .ok_or_else(|| "Invalid directory name".to_string())?;
 
-        // Convert directory name to a nice title (optimized)
-        let title = Self::dir_name_to_title(dir_name);
+        // The manifest's title, else the directory name as a nice title (optimized)
+        let theme_manifest = manifest::load(theme_dir);
+        let title = theme_manifest
+            .as_ref()
+            .and_then(|m| m.title.clone())
+            .unwrap_or_else(|| Self::dir_name_to_title(dir_name));
 
         let is_custom = theme_dir.join("custom_theme.json").is_file();
 
@@ -313,16 +322,21 @@ impl OptimizedThemeLoader {
         let colors = Self::extract_theme_colors_cached(theme_dir, is_custom, &color_cache).await;
 
         // Load image asynchronously
-        let image_path = Self::load_theme_image_async(theme_dir).await;
+        let preview = theme_manifest.as_ref().and_then(|m| m.preview.clone());
+        let image_path = Self::load_theme_image_async(theme_dir, preview).await;
 
         Ok(SysTheme {
             dir: dir_name.to_string(),
             title,
-            description: format!("Auto-generated theme from {dir_name}"),
+            description: theme_manifest
+                .as_ref()
+                .and_then(|m| m.description.clone())
+                .unwrap_or_default(),
             image: image_path,
             is_system,
             is_custom,
             colors,
+            manifest: theme_manifest,
         })
     }
 
@@ -380,27 +394,20 @@ impl OptimizedThemeLoader {
             }
         }
 
-        // For system themes or fallback, try to extract from alacritty.toml
-        let alacritty_config_path = theme_dir.join("alacritty.toml");
-        if alacritty_config_path.exists() {
-            if let Some(colors) =
-                ColorExtractor::extract_from_alacritty_config(&alacritty_config_path)
-            {
-                return Some(colors);
-            }
-        }
-
-        None
+        // For system themes or fallback, try to extract from the terminal configs
+        ColorExtractor::extract_from_theme_dir(theme_dir)
     }
 
     /// Load theme image asynchronously
-    async fn load_theme_image_async(theme_dir: &Path) -> String {
+    async fn load_theme_image_async(theme_dir: &Path, preview: Option<String>) -> String {
         // This is I/O bound, so we can spawn it as a blocking task
         let theme_dir_path = theme_dir.to_path_buf();
         let theme_dir_display = theme_dir.display().to_string();
 
-        match tokio::task::spawn_blocking(move || Self::find_and_convert_image(&theme_dir_path))
-            .await
+        match tokio::task::spawn_blocking(move || {
+            Self::find_and_convert_image(&theme_dir_path, preview.as_deref())
+        })
+        .await
         {
             Ok(Ok(image_path)) => image_path,
             Ok(Err(e)) => {
@@ -414,8 +421,14 @@ impl OptimizedThemeLoader {
         }
     }
 
-    /// Find and convert image to data URL (blocking operation)
-    fn find_and_convert_image(theme_dir: &Path) -> Result<String, String> {
+    /// Find and convert image to data URL (blocking operation); the manifest's preview wins
+    fn find_and_convert_image(theme_dir: &Path, preview: Option<&str>) -> Result<String, String> {
+        if let Some(preview) = preview {
+            match manifest::preview_path(theme_dir, preview) {
+                Ok(path) => return Self::convert_image_to_data_url(&path),
+                Err(e) => log::warn!("Ignoring theme preview: {e}"),
+            }
+        }
         if let Ok(entries) = fs::read_dir(theme_dir) {
             for entry in entries.flatten() {
                 let file_path = entry.path();
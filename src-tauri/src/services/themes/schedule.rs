@@ -0,0 +1,614 @@
+//! Scheduled theme switching: apply themes at fixed local times or at sunrise/sunset.
+//!
+//! The schedule lives in the app settings. The running instance evaluates it in a
+//! background task; `omarchist schedule status` prints the same evaluation without
+//! starting the UI.
+
+use crate::services::config::light_mode::set_theme_light_mode;
+use crate::services::settings::app_settings;
+use crate::types::{ScheduleRule, ThemeSchedule, ThemeVariant};
+use chrono::{DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// How often the schedule is re-read, so edits and clock changes are picked up
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// When a rule fires
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScheduleTime {
+    Clock(NaiveTime),
+    /// Minutes after (or before, when negative) sunrise
+    Sunrise(i64),
+    Sunset(i64),
+}
+
+impl ScheduleTime {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        for (name, make) in [
+            ("sunrise", ScheduleTime::Sunrise as fn(i64) -> Self),
+            ("sunset", ScheduleTime::Sunset),
+        ] {
+            if let Some(offset) = value.strip_prefix(name) {
+                let offset = offset.trim();
+                if offset.is_empty() {
+                    return Ok(make(0));
+                }
+                let minutes = offset
+                    .strip_prefix('+')
+                    .unwrap_or(offset)
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid offset in '{value}', expected e.g. {name}-30"))?;
+                return Ok(make(minutes));
+            }
+        }
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .map(ScheduleTime::Clock)
+            .map_err(|_| format!("Invalid time '{value}', expected HH:MM, sunrise or sunset"))
+    }
+
+    fn is_solar(self) -> bool {
+        !matches!(self, ScheduleTime::Clock(_))
+    }
+}
+
+/// Check a schedule before it is saved
+pub fn validate_schedule(schedule: &ThemeSchedule) -> Result<(), String> {
+    let mut needs_location = false;
+    for rule in &schedule.rules {
+        if rule.theme.trim().is_empty() {
+            return Err(format!("Schedule rule at '{}' has no theme", rule.at));
+        }
+        needs_location |= ScheduleTime::parse(&rule.at)?.is_solar();
+    }
+
+    match (schedule.latitude, schedule.longitude) {
+        (Some(lat), Some(lon)) => {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                return Err(format!("Invalid location {lat}, {lon}"));
+            }
+        },
+        (None, None) if !needs_location => {},
+        _ => return Err("Sunrise and sunset rules need a latitude and longitude".to_string()),
+    }
+    Ok(())
+}
+
+/// Sunrise and sunset of one day
+pub type SunTimes = (DateTime<Utc>, DateTime<Utc>);
+
+/// Sunrise and sunset in UTC for a date, using the NOAA sunrise equation. `None` during
+/// polar day or night.
+pub fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> Option<SunTimes> {
+    let days_since_epoch = date
+        .signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1)?)
+        .num_days() as f64;
+    // Julian day at noon UTC, relative to J2000
+    let n = days_since_epoch + 2_440_588.0 - 2_451_545.0 + 0.0008;
+    let mean_solar_noon = n - longitude / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let center =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = 2_451_545.0 + mean_solar_noon + 0.0053 * anomaly.sin()
+        - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let declination = (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = ((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees() / 360.0;
+
+    let to_utc = |julian: f64| {
+        let seconds = ((julian - 2_440_587.5) * 86_400.0).round() as i64;
+        Utc.timestamp_opt(seconds, 0).single()
+    };
+    Some((to_utc(transit - hour_angle)?, to_utc(transit + hour_angle)?))
+}
+
+/// When `time` fires on `date` in `tz`, with that day's own UTC offset. A clock time skipped
+/// by a DST change fires an hour later; one that happens twice fires the first time.
+fn occurrence<Tz: TimeZone>(
+    schedule: &ThemeSchedule,
+    time: ScheduleTime,
+    date: NaiveDate,
+    tz: &Tz,
+) -> Option<DateTime<FixedOffset>> {
+    let solar = |offset_minutes: i64, pick: fn(SunTimes) -> DateTime<Utc>| {
+        let times = sun_times(date, schedule.latitude?, schedule.longitude?)?;
+        let at = pick(times) + Duration::minutes(offset_minutes);
+        Some(at.with_timezone(tz).fixed_offset())
+    };
+    match time {
+        ScheduleTime::Clock(clock) => {
+            let local = date.and_time(clock);
+            let at = match tz.from_local_datetime(&local) {
+                LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => at,
+                LocalResult::None => tz
+                    .from_local_datetime(&(local + Duration::hours(1)))
+                    .earliest()?,
+            };
+            Some(at.fixed_offset())
+        },
+        ScheduleTime::Sunrise(minutes) => solar(minutes, |(rise, _)| rise),
+        ScheduleTime::Sunset(minutes) => solar(minutes, |(_, set)| set),
+    }
+}
+
+/// Every firing of the schedule's rules from the day before `now` to the day after
+fn occurrences<'a, Tz: TimeZone>(
+    schedule: &'a ThemeSchedule,
+    now: &DateTime<Tz>,
+) -> Vec<(&'a ScheduleRule, DateTime<FixedOffset>)> {
+    let (today, tz) = (now.date_naive(), now.timezone());
+    let mut result = Vec::new();
+    for rule in &schedule.rules {
+        let Ok(time) = ScheduleTime::parse(&rule.at) else {
+            continue;
+        };
+        for days in -1..=1 {
+            if let Some(at) = occurrence(schedule, time, today + Duration::days(days), &tz) {
+                result.push((rule, at));
+            }
+        }
+    }
+    result.sort_by_key(|(_, at)| *at);
+    result
+}
+
+/// The rule in effect at `now` (the last one to fire) and when it fired
+pub fn active_rule<'a, Tz: TimeZone>(
+    schedule: &'a ThemeSchedule,
+    now: &DateTime<Tz>,
+) -> Option<(&'a ScheduleRule, DateTime<FixedOffset>)> {
+    occurrences(schedule, now)
+        .into_iter()
+        .rfind(|(_, at)| at <= now)
+}
+
+/// The next rule to fire after `now`
+pub fn next_rule<'a, Tz: TimeZone>(
+    schedule: &'a ThemeSchedule,
+    now: &DateTime<Tz>,
+) -> Option<(&'a ScheduleRule, DateTime<FixedOffset>)> {
+    occurrences(schedule, now)
+        .into_iter()
+        .find(|(_, at)| at > now)
+}
+
+/// A rule together with when it fires
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScheduledTheme {
+    pub theme: String,
+    pub variant: Option<ThemeVariant>,
+    pub at: DateTime<FixedOffset>,
+}
+
+impl ScheduledTheme {
+    fn new((rule, at): (&ScheduleRule, DateTime<FixedOffset>)) -> Self {
+        Self {
+            theme: rule.theme.clone(),
+            variant: rule.variant,
+            at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScheduleStatus {
+    pub enabled: bool,
+    pub active: Option<ScheduledTheme>,
+    pub next: Option<ScheduledTheme>,
+    pub sunrise: Option<DateTime<FixedOffset>>,
+    pub sunset: Option<DateTime<FixedOffset>>,
+}
+
+pub fn status<Tz: TimeZone>(schedule: &ThemeSchedule, now: &DateTime<Tz>) -> ScheduleStatus {
+    let sun = schedule
+        .latitude
+        .zip(schedule.longitude)
+        .and_then(|(lat, lon)| sun_times(now.date_naive(), lat, lon));
+    ScheduleStatus {
+        enabled: schedule.enabled,
+        active: active_rule(schedule, now).map(ScheduledTheme::new),
+        next: next_rule(schedule, now).map(ScheduledTheme::new),
+        sunrise: sun.map(|(rise, _)| rise.with_timezone(&now.timezone()).fixed_offset()),
+        sunset: sun.map(|(_, set)| set.with_timezone(&now.timezone()).fixed_offset()),
+    }
+}
+
+/// Human-readable status for the CLI
+pub fn format_status(status: &ScheduleStatus) -> String {
+    let describe = |scheduled: &ScheduledTheme| {
+        let variant = scheduled
+            .variant
+            .map(|v| format!(" ({})", v.as_str()))
+            .unwrap_or_default();
+        format!(
+            "{}{variant} at {}",
+            scheduled.theme,
+            scheduled.at.format("%Y-%m-%d %H:%M")
+        )
+    };
+
+    let mut lines = vec![format!(
+        "Schedule: {}",
+        if status.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    )];
+    lines.push(match &status.active {
+        Some(active) => format!("Active:   {}", describe(active)),
+        None => "Active:   none".to_string(),
+    });
+    lines.push(match &status.next {
+        Some(next) => format!("Next:     {}", describe(next)),
+        None => "Next:     none".to_string(),
+    });
+    if let (Some(sunrise), Some(sunset)) = (status.sunrise, status.sunset) {
+        lines.push(format!(
+            "Sun:      rises {}, sets {}",
+            sunrise.format("%H:%M"),
+            sunset.format("%H:%M")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Handle `omarchist schedule <subcommand>` before Tauri starts. Returns the exit code, or
+/// `None` when the arguments are not a schedule command.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    if args.get(1).map(String::as_str) != Some("schedule") {
+        return None;
+    }
+    match args.get(2).map(String::as_str) {
+        Some("status") | None => {
+            let schedule = app_settings::read_saved_settings()
+                .and_then(|settings| settings.schedule)
+                .unwrap_or_default();
+            println!(
+                "{}",
+                format_status(&status(&schedule, &chrono::Local::now()))
+            );
+            Some(0)
+        },
+        Some(other) => {
+            eprintln!("Unknown schedule subcommand '{other}'. Usage: omarchist schedule status");
+            Some(2)
+        },
+    }
+}
+
+/// Which firing was last applied, so restarts don't re-apply it over a manual change
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SchedulerState {
+    last_applied: Option<DateTime<FixedOffset>>,
+}
+
+fn state_path() -> Option<PathBuf> {
+    app_settings::app_config_dir().map(|dir| dir.join("schedule-state.json"))
+}
+
+fn load_state() -> SchedulerState {
+    state_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &SchedulerState) {
+    let Some(path) = state_path() else {
+        return;
+    };
+    let result = serde_json::to_string_pretty(state)
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save scheduler state to {}: {e}", path.display());
+    }
+}
+
+async fn apply_rule(app_handle: &AppHandle, rule: &ScheduleRule) -> Result<(), String> {
+    if let Some(variant) = rule.variant {
+        set_theme_light_mode(
+            app_handle.clone(),
+            rule.theme.clone(),
+            variant == ThemeVariant::Light,
+        )
+        .await?;
+    }
+    crate::commands::shell::apply_theme(rule.theme.clone()).await
+}
+
+/// Apply the active rule if it has not been applied yet; returns how long to wait
+async fn tick(app_handle: &AppHandle, state: &mut SchedulerState) -> std::time::Duration {
+    let schedule = match app_settings::load_settings(app_handle).await {
+        Ok(settings) => settings.schedule.unwrap_or_default(),
+        Err(e) => {
+            log::warn!("Failed to load theme schedule: {e}");
+            return POLL_INTERVAL;
+        },
+    };
+    if !schedule.enabled {
+        return POLL_INTERVAL;
+    }
+
+    let now = chrono::Local::now();
+    if let Some((rule, at)) = active_rule(&schedule, &now) {
+        if state.last_applied != Some(at) {
+            log::info!("Schedule applying '{}' (due {at})", rule.theme);
+            match apply_rule(app_handle, rule).await {
+                Ok(()) => {
+                    state.last_applied = Some(at);
+                    save_state(state);
+                },
+                Err(e) => log::warn!("Scheduled switch to '{}' failed: {e}", rule.theme),
+            }
+        }
+    }
+
+    next_rule(&schedule, &now)
+        .and_then(|(_, at)| (at - now.fixed_offset()).to_std().ok())
+        .map_or(POLL_INTERVAL, |until_next| until_next.min(POLL_INTERVAL))
+}
+
+/// Start the scheduler in the running instance
+pub fn start(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut state = load_state();
+        loop {
+            let wait = tick(&app_handle, &mut state).await;
+            tokio::time::sleep(wait).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_schedule_status(app_handle: AppHandle) -> Result<ScheduleStatus, String> {
+    let settings = app_settings::load_settings(&app_handle)
+        .await
+        .map_err(|e| format!("Unable to load settings: {e}"))?;
+    let schedule = settings.schedule.unwrap_or_default();
+    Ok(status(&schedule, &chrono::Local::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn rule(at: &str, theme: &str) -> ScheduleRule {
+        ScheduleRule {
+            at: at.to_string(),
+            theme: theme.to_string(),
+            variant: None,
+        }
+    }
+
+    fn local(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_schedule_time() {
+        assert_eq!(
+            ScheduleTime::parse("07:30").unwrap(),
+            ScheduleTime::Clock(NaiveTime::from_hms_opt(7, 30, 0).unwrap())
+        );
+        assert_eq!(
+            ScheduleTime::parse("sunrise").unwrap(),
+            ScheduleTime::Sunrise(0)
+        );
+        assert_eq!(
+            ScheduleTime::parse("sunset-30").unwrap(),
+            ScheduleTime::Sunset(-30)
+        );
+        assert_eq!(
+            ScheduleTime::parse(" sunset +15 ").unwrap(),
+            ScheduleTime::Sunset(15)
+        );
+        assert!(ScheduleTime::parse("25:00").is_err());
+        assert!(ScheduleTime::parse("sunset-soon").is_err());
+    }
+
+    #[test]
+    fn test_sun_times_london_midsummer() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let (rise, set) = sun_times(date, 51.5074, -0.1278).unwrap();
+        // 03:43 and 20:21 UTC according to timeanddate.com
+        let expected_rise = Utc.with_ymd_and_hms(2024, 6, 21, 3, 43, 0).unwrap();
+        let expected_set = Utc.with_ymd_and_hms(2024, 6, 21, 20, 21, 0).unwrap();
+        assert!((rise - expected_rise).num_minutes().abs() <= 3);
+        assert!((set - expected_set).num_minutes().abs() <= 3);
+
+        // Midnight sun in Tromsø
+        assert!(sun_times(date, 69.65, 18.96).is_none());
+    }
+
+    #[test]
+    fn test_active_and_next_rule_wrap_around_midnight() {
+        let schedule = ThemeSchedule {
+            enabled: true,
+            latitude: None,
+            longitude: None,
+            rules: vec![rule("07:30", "day"), rule("19:00", "night")],
+        };
+
+        let morning = local("2024-03-10T06:00:00+01:00");
+        let (active, at) = active_rule(&schedule, &morning).unwrap();
+        assert_eq!(active.theme, "night");
+        assert_eq!(at, local("2024-03-09T19:00:00+01:00"));
+        assert_eq!(next_rule(&schedule, &morning).unwrap().0.theme, "day");
+
+        let noon = local("2024-03-10T12:00:00+01:00");
+        assert_eq!(active_rule(&schedule, &noon).unwrap().0.theme, "day");
+        let (next, at) = next_rule(&schedule, &noon).unwrap();
+        assert_eq!(next.theme, "night");
+        assert_eq!(at, local("2024-03-10T19:00:00+01:00"));
+    }
+
+    /// Central European time: UTC+1, UTC+2 from the last Sunday of March 2024 at 02:00
+    /// until the last Sunday of October at 03:00
+    #[derive(Clone)]
+    struct Cet;
+
+    impl Cet {
+        fn offset_at_utc(utc: &NaiveDateTime) -> FixedOffset {
+            let summer = local("2024-03-31T01:00:00Z").naive_utc()
+                ..local("2024-10-27T01:00:00Z").naive_utc();
+            FixedOffset::east_opt(if summer.contains(utc) { 7200 } else { 3600 }).unwrap()
+        }
+    }
+
+    impl TimeZone for Cet {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(&self, date: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let mut offsets = [3600, 7200]
+                .map(|secs| FixedOffset::east_opt(secs).unwrap())
+                .into_iter()
+                .filter(|offset| Self::offset_at_utc(&(*local - *offset)) == *offset);
+            match (offsets.next(), offsets.next()) {
+                (Some(a), Some(b)) => LocalResult::Ambiguous(b, a),
+                (Some(offset), None) => LocalResult::Single(offset),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, date: &NaiveDate) -> FixedOffset {
+            Self::offset_at_utc(&date.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset_at_utc(utc)
+        }
+    }
+
+    #[test]
+    fn test_occurrences_use_each_days_offset() {
+        let schedule = ThemeSchedule {
+            enabled: true,
+            latitude: None,
+            longitude: None,
+            rules: vec![rule("07:30", "day"), rule("19:00", "night")],
+        };
+
+        // Yesterday's 19:00 was still winter time
+        let morning = Cet
+            .from_local_datetime(&local("2024-03-31T06:00:00Z").naive_utc())
+            .unwrap();
+        let (active, at) = active_rule(&schedule, &morning).unwrap();
+        assert_eq!(active.theme, "night");
+        assert_eq!(at, local("2024-03-30T19:00:00+01:00"));
+        assert_eq!(at.offset().local_minus_utc(), 3600);
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let skipped = ScheduleTime::parse("02:30").unwrap();
+        assert_eq!(
+            occurrence(&schedule, skipped, date, &Cet),
+            Some(local("2024-03-31T03:30:00+02:00"))
+        );
+
+        let date = NaiveDate::from_ymd_opt(2024, 10, 27).unwrap();
+        let repeated = ScheduleTime::parse("02:30").unwrap();
+        assert_eq!(
+            occurrence(&schedule, repeated, date, &Cet),
+            Some(local("2024-10-27T02:30:00+02:00"))
+        );
+    }
+
+    #[test]
+    fn test_solar_rules_and_status() {
+        let schedule = ThemeSchedule {
+            enabled: true,
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            rules: vec![rule("sunrise", "day"), rule("sunset-30", "night")],
+        };
+        let now = local("2024-06-21T21:00:00+01:00");
+        let status = status(&schedule, &now);
+        let active = status.active.unwrap();
+        assert_eq!(active.theme, "night");
+        // Sunset is 21:21 BST, so the rule fired around 20:51
+        assert_eq!(active.at.date_naive(), now.date_naive());
+        assert!(
+            (active.at - local("2024-06-21T20:51:00+01:00"))
+                .num_minutes()
+                .abs()
+                <= 3
+        );
+        assert_eq!(status.next.unwrap().theme, "day");
+
+        let text = format_status(&status_for_format());
+        assert!(text.contains("Schedule: enabled"));
+        assert!(text.contains("Active:   night (light) at 2024-06-21 20:51"));
+        assert!(text.contains("Next:     none"));
+    }
+
+    fn status_for_format() -> ScheduleStatus {
+        ScheduleStatus {
+            enabled: true,
+            active: Some(ScheduledTheme {
+                theme: "night".to_string(),
+                variant: Some(ThemeVariant::Light),
+                at: local("2024-06-21T20:51:00+01:00"),
+            }),
+            next: None,
+            sunrise: None,
+            sunset: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        let mut schedule = ThemeSchedule {
+            enabled: true,
+            latitude: None,
+            longitude: None,
+            rules: vec![rule("07:30", "day")],
+        };
+        assert!(validate_schedule(&schedule).is_ok());
+
+        schedule.rules.push(rule("sunset", "night"));
+        assert!(validate_schedule(&schedule).is_err());
+        schedule.latitude = Some(40.7);
+        schedule.longitude = Some(-74.0);
+        assert!(validate_schedule(&schedule).is_ok());
+        schedule.latitude = Some(120.0);
+        assert!(validate_schedule(&schedule).is_err());
+
+        schedule.latitude = Some(40.7);
+        schedule.rules.push(rule("12:00", " "));
+        assert!(validate_schedule(&schedule).is_err());
+    }
+
+    #[test]
+    fn test_run_cli_ignores_other_commands() {
+        assert_eq!(run_cli(&["omarchist".into(), "refresh".into()]), None);
+        assert_eq!(
+            run_cli(&["omarchist".into(), "schedule".into(), "bogus".into()]),
+            Some(2)
+        );
+    }
+}
@@ -354,6 +354,7 @@ mod tests {
             is_system: false,
             is_custom: false,
             colors: None,
+            manifest: None,
         }
     }
 
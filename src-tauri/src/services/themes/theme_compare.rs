@@ -0,0 +1,438 @@
+//! Semantic comparison of two themes: each app's file is parsed by its generator and the
+//! resulting settings are diffed key by key, with a perceptual distance for changed colors.
+
+use super::background_import::sha256_file;
+use super::backgrounds;
+use super::theme_variants;
+use crate::services::config::generators::ConfigGeneratorRegistry;
+use crate::services::config::oklch::Oklch;
+use crate::services::safe_path;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// One setting that differs. `from` is missing for keys only `b` has, `to` for keys only `a` has.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KeyDiff {
+    pub key: String,
+    pub from: Option<Value>,
+    pub to: Option<Value>,
+    /// OKLab distance when both values are colors; about 0.02 is just noticeable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_e: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AppDiff {
+    pub app: String,
+    pub file: String,
+    pub changes: Vec<KeyDiff>,
+    /// Set when a file could not be parsed and its lines were compared instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Background images compared by content, so renamed copies still match
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct BackgroundDiff {
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ThemeComparison {
+    pub a: String,
+    pub b: String,
+    /// Apps whose files differ; apps with identical settings are left out
+    pub apps: Vec<AppDiff>,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+    pub backgrounds: BackgroundDiff,
+}
+
+impl ThemeComparison {
+    pub fn is_identical(&self) -> bool {
+        self.apps.is_empty()
+            && self.only_in_a.is_empty()
+            && self.only_in_b.is_empty()
+            && self.backgrounds == BackgroundDiff::default()
+    }
+}
+
+/// Leaf values of `value` keyed by their dotted path
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, child, out);
+            }
+        },
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        },
+    }
+}
+
+/// Colors in any stored format; alpha does not take part in the distance
+fn as_color(value: &Value) -> Option<Oklch> {
+    Oklch::from_hex(&theme_variants::color_hex(value.as_str()?)?)
+}
+
+fn diff_values(a: &Value, b: &Value) -> Vec<KeyDiff> {
+    let (mut left, mut right) = (BTreeMap::new(), BTreeMap::new());
+    flatten("", a, &mut left);
+    flatten("", b, &mut right);
+    let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (from, to) = (left.get(key), right.get(key));
+            if from == to {
+                return None;
+            }
+            let delta_e = from
+                .and_then(as_color)
+                .zip(to.and_then(as_color))
+                .map(|(from, to)| from.distance(to));
+            Some(KeyDiff {
+                key: key.clone(),
+                from: from.cloned(),
+                to: to.cloned(),
+                delta_e,
+            })
+        })
+        .collect()
+}
+
+/// Differing lines of two files that could not be parsed, keyed `line N`
+fn diff_lines(a: &str, b: &str) -> Vec<KeyDiff> {
+    let (a, b): (Vec<&str>, Vec<&str>) = (a.lines().collect(), b.lines().collect());
+    (0..a.len().max(b.len()))
+        .filter_map(|i| {
+            let (from, to) = (a.get(i), b.get(i));
+            (from != to).then(|| KeyDiff {
+                key: format!("line {}", i + 1),
+                from: from.map(|line| Value::from(*line)),
+                to: to.map(|line| Value::from(*line)),
+                delta_e: None,
+            })
+        })
+        .collect()
+}
+
+/// Names of the regular files directly inside `dir`
+fn file_names(dir: &Path) -> BTreeSet<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Background file names of a theme keyed by content hash
+fn background_hashes(theme_dir: &Path) -> BTreeMap<String, String> {
+    let dir = theme_dir.join(backgrounds::BACKGROUNDS_DIR);
+    backgrounds::image_files(theme_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| Some((sha256_file(&dir.join(&name)).ok()?, name)))
+        .collect()
+}
+
+fn compare_backgrounds(a_dir: &Path, b_dir: &Path) -> BackgroundDiff {
+    let (a, b) = (background_hashes(a_dir), background_hashes(b_dir));
+    let only = |left: &BTreeMap<String, String>, right: &BTreeMap<String, String>| {
+        let mut names: Vec<String> = left
+            .iter()
+            .filter(|(hash, _)| !right.contains_key(*hash))
+            .map(|(_, name)| name.clone())
+            .collect();
+        names.sort();
+        names
+    };
+    BackgroundDiff {
+        only_in_a: only(&a, &b),
+        only_in_b: only(&b, &a),
+    }
+}
+
+fn compare_app(registry: &ConfigGeneratorRegistry, app: &str, a: &str, b: &str) -> Option<AppDiff> {
+    if a == b {
+        return None;
+    }
+    let generator = registry.get_generator(app)?;
+    let (changes, note) = match (
+        generator.parse_existing_config(a),
+        generator.parse_existing_config(b),
+    ) {
+        (Ok(a), Ok(b)) => (diff_values(&a, &b), None),
+        (Err(e), _) | (_, Err(e)) => (
+            diff_lines(a, b),
+            Some(format!("Compared line by line: {e}")),
+        ),
+    };
+    // Formatting-only differences parse to the same settings
+    if changes.is_empty() && note.is_none() {
+        return None;
+    }
+    Some(AppDiff {
+        app: app.to_string(),
+        file: generator.get_file_name().to_string(),
+        changes,
+        note,
+    })
+}
+
+/// Compare two theme directories; `a` and `b` are the names shown in the result
+pub fn compare_dirs(
+    registry: &ConfigGeneratorRegistry,
+    a: &str,
+    a_dir: &Path,
+    b: &str,
+    b_dir: &Path,
+) -> ThemeComparison {
+    let (a_files, b_files) = (file_names(a_dir), file_names(b_dir));
+
+    let mut apps: Vec<&str> = registry.get_all_apps();
+    apps.sort_unstable();
+    let apps = apps
+        .into_iter()
+        .filter_map(|app| {
+            let file = registry.get_generator(app)?.get_file_name();
+            let a_content = fs::read_to_string(a_dir.join(file)).ok()?;
+            let b_content = fs::read_to_string(b_dir.join(file)).ok()?;
+            compare_app(registry, app, &a_content, &b_content)
+        })
+        .collect();
+
+    ThemeComparison {
+        a: a.to_string(),
+        b: b.to_string(),
+        apps,
+        only_in_a: a_files.difference(&b_files).cloned().collect(),
+        only_in_b: b_files.difference(&a_files).cloned().collect(),
+        backgrounds: compare_backgrounds(a_dir, b_dir),
+    }
+}
+
+/// Compare two installed themes by directory name
+pub fn compare(a: &str, b: &str) -> Result<ThemeComparison, String> {
+    let themes_root = safe_path::themes_root()?;
+    let dir = |name: &str| {
+        let dir = safe_path::theme_dir(&themes_root, name)?;
+        if dir.is_dir() {
+            Ok(dir)
+        } else {
+            Err(format!("Theme '{name}' not found"))
+        }
+    };
+    let (a_dir, b_dir) = (dir(a)?, dir(b)?);
+    Ok(compare_dirs(
+        &ConfigGeneratorRegistry::new(),
+        a,
+        &a_dir,
+        b,
+        &b_dir,
+    ))
+}
+
+fn show(value: &Option<Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+        None => "(none)".to_string(),
+    }
+}
+
+/// Human-readable report for the CLI
+pub fn format_comparison(comparison: &ThemeComparison) -> String {
+    let (a, b) = (&comparison.a, &comparison.b);
+    if comparison.is_identical() {
+        return format!("{a} and {b} are identical");
+    }
+    let mut lines = vec![format!("{a} → {b}")];
+    for app in &comparison.apps {
+        lines.push(format!("{} ({})", app.app, app.file));
+        if let Some(note) = &app.note {
+            lines.push(format!("  {note}"));
+        }
+        for change in &app.changes {
+            let delta = change
+                .delta_e
+                .map(|d| format!(" (ΔE {d:.3})"))
+                .unwrap_or_default();
+            lines.push(format!(
+                "  {}: {} → {}{delta}",
+                change.key,
+                show(&change.from),
+                show(&change.to)
+            ));
+        }
+    }
+    for (label, names) in [
+        (format!("Only in {a}"), &comparison.only_in_a),
+        (format!("Only in {b}"), &comparison.only_in_b),
+        (
+            format!("Backgrounds only in {a}"),
+            &comparison.backgrounds.only_in_a,
+        ),
+        (
+            format!("Backgrounds only in {b}"),
+            &comparison.backgrounds.only_in_b,
+        ),
+    ] {
+        if !names.is_empty() {
+            lines.push(format!("{label}: {}", names.join(", ")));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Handle `omarchist compare <a> <b> [--json]` before Tauri starts. Returns the exit code, or
+/// `None` when the arguments are not a compare command.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    if args.get(1).map(String::as_str) != Some("compare") {
+        return None;
+    }
+    let json = args.iter().any(|arg| arg == "--json");
+    let names: Vec<&String> = args[2..].iter().filter(|arg| *arg != "--json").collect();
+    let [a, b] = names[..] else {
+        eprintln!("Usage: omarchist compare <theme-a> <theme-b> [--json]");
+        return Some(2);
+    };
+    match compare(a, b) {
+        Ok(comparison) if json => match serde_json::to_string_pretty(&comparison) {
+            Ok(output) => {
+                println!("{output}");
+                Some(0)
+            },
+            Err(e) => {
+                eprintln!("{e}");
+                Some(1)
+            },
+        },
+        Ok(comparison) => {
+            println!("{}", format_comparison(&comparison));
+            Some(0)
+        },
+        Err(e) => {
+            eprintln!("{e}");
+            Some(1)
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn compare_themes(a: String, b: String) -> Result<ThemeComparison, String> {
+    compare(&a, &b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::generators::alacritty::AlacrittyGenerator;
+    use crate::services::config::generators::ConfigGenerator;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn theme(root: &Path, name: &str, background: &str) -> std::path::PathBuf {
+        let dir = root.join(name);
+        fs::create_dir_all(dir.join("backgrounds")).unwrap();
+        let config = AlacrittyGenerator
+            .generate_config(&json!({
+                "alacritty": { "colors": { "primary": { "background": background } } }
+            }))
+            .unwrap();
+        fs::write(dir.join("alacritty.toml"), config).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_diff_values_reports_keys_and_color_distance() {
+        let changes = diff_values(
+            &json!({ "colors": { "bg": "#000000", "fg": "#ffffff" }, "size": 1 }),
+            &json!({ "colors": { "bg": "#000000", "fg": "#fffffe" }, "font": "mono" }),
+        );
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, ["colors.fg", "font", "size"]);
+        assert!(changes[0].delta_e.unwrap() < 0.01);
+        assert_eq!(changes[1].from, None);
+        assert_eq!(changes[2].to, None);
+        assert_eq!(changes[2].delta_e, None);
+    }
+
+    #[test]
+    fn test_colors_in_every_stored_format() {
+        let changes = diff_values(
+            &json!({ "hypr": "33CCFFEE", "lock": "18,18,18,0.9", "walker": "EAEAEA88" }),
+            &json!({ "hypr": "rgba(33ccffaa)", "lock": "20,20,20,1.0", "walker": "#000000" }),
+        );
+        assert_eq!(changes[0].delta_e, Some(0.0));
+        assert!(changes[1].delta_e.unwrap() < 0.02);
+        assert!(changes[2].delta_e.unwrap() > 0.5);
+    }
+
+    #[test]
+    fn test_unparsable_files_get_a_line_diff() {
+        let registry = ConfigGeneratorRegistry::builtin();
+        let diff = compare_app(&registry, "alacritty", "a = 1\n[broken", "a = 2\n[broken").unwrap();
+        assert!(diff.note.is_some());
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].key, "line 1");
+        assert_eq!(diff.changes[0].to, Some(json!("a = 2")));
+    }
+
+    #[test]
+    fn test_compare_dirs() {
+        let root = TempDir::new().unwrap();
+        let a = theme(root.path(), "a", "#1a1b26");
+        let b = theme(root.path(), "b", "#2e3440");
+        fs::write(a.join("neovim.lua"), "return {}").unwrap();
+        fs::write(a.join("backgrounds").join("1.png"), b"same").unwrap();
+        fs::write(b.join("backgrounds").join("renamed.png"), b"same").unwrap();
+        fs::write(b.join("backgrounds").join("2.png"), b"other").unwrap();
+
+        let comparison = compare_dirs(&ConfigGeneratorRegistry::builtin(), "a", &a, "b", &b);
+        assert_eq!(comparison.apps.len(), 1);
+        let alacritty = &comparison.apps[0];
+        assert_eq!(alacritty.app, "alacritty");
+        let change = &alacritty.changes[0];
+        assert_eq!(change.key, "colors.primary.background");
+        assert_eq!(change.from, Some(json!("#1a1b26")));
+        assert_eq!(change.to, Some(json!("#2e3440")));
+        assert!(change.delta_e.unwrap() > 0.02);
+
+        assert_eq!(comparison.only_in_a, ["neovim.lua"]);
+        assert!(comparison.only_in_b.is_empty());
+        assert!(comparison.backgrounds.only_in_a.is_empty());
+        assert_eq!(comparison.backgrounds.only_in_b, ["2.png"]);
+        assert!(format_comparison(&comparison).contains("colors.primary.background"));
+    }
+
+    #[test]
+    fn test_formatting_only_changes_are_identical() {
+        let root = TempDir::new().unwrap();
+        let a = theme(root.path(), "a", "#1a1b26");
+        let b = theme(root.path(), "b", "#1a1b26");
+        let config = fs::read_to_string(b.join("alacritty.toml")).unwrap();
+        fs::write(
+            b.join("alacritty.toml"),
+            format!("# reformatted\n{config}\n"),
+        )
+        .unwrap();
+
+        let comparison = compare_dirs(&ConfigGeneratorRegistry::builtin(), "a", &a, "b", &b);
+        assert!(comparison.is_identical());
+        assert_eq!(format_comparison(&comparison), "a and b are identical");
+    }
+}
@@ -0,0 +1,178 @@
+//! User data about themes that is not part of the themes themselves: tags, favorites and
+//! when each theme was last applied.
+//!
+//! Bundled themes are links into Omarchy's install, so this lives in the app's config
+//! directory rather than in the theme folders.
+
+use crate::services::settings::app_settings;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LIBRARY_FILE: &str = "theme-library.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ThemeUserData {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_applied: Option<DateTime<Utc>>,
+}
+
+/// User data keyed by theme directory name
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ThemeLibrary {
+    #[serde(default)]
+    pub themes: BTreeMap<String, ThemeUserData>,
+}
+
+/// Tags are trimmed, lowercased and deduplicated so filtering is predictable
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    tags.into_iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+impl ThemeLibrary {
+    pub fn get(&self, dir: &str) -> ThemeUserData {
+        self.themes.get(dir).cloned().unwrap_or_default()
+    }
+
+    /// Change one theme's entry, dropping it once it holds nothing
+    fn update(&mut self, dir: &str, change: impl FnOnce(&mut ThemeUserData)) {
+        let entry = self.themes.entry(dir.to_string()).or_default();
+        change(entry);
+        if *entry == ThemeUserData::default() {
+            self.themes.remove(dir);
+        }
+    }
+
+    pub fn set_tags(&mut self, dir: &str, tags: Vec<String>) {
+        self.update(dir, |data| data.tags = normalize_tags(tags));
+    }
+
+    pub fn set_favorite(&mut self, dir: &str, favorite: bool) {
+        self.update(dir, |data| data.favorite = favorite);
+    }
+
+    pub fn record_applied(&mut self, dir: &str, at: DateTime<Utc>) {
+        self.update(dir, |data| data.last_applied = Some(at));
+    }
+
+    /// Every tag in use, sorted
+    pub fn all_tags(&self) -> Vec<String> {
+        self.themes
+            .values()
+            .flat_map(|data| data.tags.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize theme library: {e}"))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write theme library: {e}"))
+    }
+}
+
+fn library_path() -> Result<PathBuf, String> {
+    app_settings::app_config_dir()
+        .map(|dir| dir.join(LIBRARY_FILE))
+        .ok_or_else(|| "Failed to get config directory".to_string())
+}
+
+pub fn load() -> ThemeLibrary {
+    library_path()
+        .map(|path| ThemeLibrary::load_from(&path))
+        .unwrap_or_default()
+}
+
+/// Load, change and save the library
+pub fn modify(change: impl FnOnce(&mut ThemeLibrary)) -> Result<ThemeLibrary, String> {
+    let path = library_path()?;
+    let mut library = ThemeLibrary::load_from(&path);
+    change(&mut library);
+    library.save_to(&path)?;
+    Ok(library)
+}
+
+/// Remember that a theme was applied, for "recently applied" ordering
+pub fn record_applied(dir: &str) {
+    if let Err(e) = modify(|library| library.record_applied(dir, Utc::now())) {
+        log::warn!("Failed to record that '{dir}' was applied: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn set_theme_tags(dir: String, tags: Vec<String>) -> Result<ThemeUserData, String> {
+    modify(|library| library.set_tags(&dir, tags)).map(|library| library.get(&dir))
+}
+
+#[tauri::command]
+pub async fn set_theme_favorite(dir: String, favorite: bool) -> Result<ThemeUserData, String> {
+    modify(|library| library.set_favorite(&dir, favorite)).map(|library| library.get(&dir))
+}
+
+#[tauri::command]
+pub async fn list_theme_tags() -> Result<Vec<String>, String> {
+    Ok(load().all_tags())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tags_are_normalized() {
+        assert_eq!(
+            normalize_tags(vec![
+                " Dark ".into(),
+                "dark".into(),
+                "".into(),
+                "Warm".into()
+            ]),
+            vec!["dark", "warm"]
+        );
+    }
+
+    #[test]
+    fn test_empty_entries_are_dropped_and_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("omarchist").join(LIBRARY_FILE);
+
+        let mut library = ThemeLibrary::default();
+        library.set_tags("nord", vec!["cold".into(), "Blue".into()]);
+        library.set_favorite("nord", true);
+        library.set_favorite("gruvbox", true);
+        library.set_favorite("gruvbox", false);
+        assert!(!library.themes.contains_key("gruvbox"));
+        assert_eq!(library.all_tags(), vec!["blue", "cold"]);
+
+        library.save_to(&path).unwrap();
+        assert_eq!(ThemeLibrary::load_from(&path), library);
+        assert_eq!(
+            ThemeLibrary::load_from(&dir.path().join("missing.json")),
+            ThemeLibrary::default()
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! Searching, filtering, sorting and paging the theme list in the backend.
+
+use super::get_sys_themes::{get_sys_themes, SysTheme};
+use super::theme_library::{self, ThemeLibrary, ThemeUserData};
+use crate::services::config::oklch::Oklch;
+use crate::services::safe_path;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+/// OKLab distance within which a palette color counts as matching the requested color
+pub const DEFAULT_COLOR_DISTANCE: f64 = 0.12;
+
+/// Colors that can be asked for by name instead of hex
+const NAMED_COLORS: [(&str, &str); 9] = [
+    ("red", "#e53935"),
+    ("orange", "#fb8c00"),
+    ("yellow", "#fdd835"),
+    ("green", "#43a047"),
+    ("cyan", "#00acc1"),
+    ("blue", "#1e88e5"),
+    ("purple", "#8e24aa"),
+    ("magenta", "#d81b60"),
+    ("pink", "#f06292"),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeSort {
+    #[default]
+    Title,
+    /// Most recently applied first; never-applied themes last
+    RecentlyApplied,
+    /// Closest to `color` first
+    ColorSimilarity,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeQuery {
+    /// Words that must all appear in the title or description
+    pub text: Option<String>,
+    pub is_custom: Option<bool>,
+    pub is_system: Option<bool>,
+    pub light: Option<bool>,
+    /// Themes must carry every one of these tags, set by the user or in `theme.toml`
+    pub tags: Vec<String>,
+    pub favorites_only: bool,
+    /// Hex color or a name such as "blue"; keeps themes whose palette has a close color
+    pub color: Option<String>,
+    pub max_color_distance: Option<f64>,
+    pub sort: ThemeSort,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// A theme with the user data and match details the list shows
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeListing {
+    #[serde(flatten)]
+    pub theme: SysTheme,
+    #[serde(flatten)]
+    pub user: ThemeUserData,
+    pub is_light: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_distance: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeQueryResult {
+    pub items: Vec<ThemeListing>,
+    /// Matches before paging
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+pub fn parse_color(value: &str) -> Result<Oklch, String> {
+    let value = value.trim();
+    let hex = NAMED_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map_or(value, |(_, hex)| hex);
+    Oklch::from_hex(hex).ok_or_else(|| format!("Unknown color '{value}'"))
+}
+
+/// Distance from `target` to the closest accent in the theme's terminal palette
+fn palette_distance(theme: &SysTheme, target: Oklch) -> Option<f64> {
+    let terminal = &theme.colors.as_ref()?.terminal;
+    [
+        &terminal.red,
+        &terminal.green,
+        &terminal.yellow,
+        &terminal.blue,
+        &terminal.magenta,
+        &terminal.cyan,
+    ]
+    .into_iter()
+    .filter_map(|hex| Oklch::from_hex(hex))
+    .map(|color| color.distance(target))
+    .min_by(f64::total_cmp)
+}
+
+fn matches_text(theme: &SysTheme, text: &str) -> bool {
+    let haystack = format!("{} {}", theme.title, theme.description).to_lowercase();
+    text.split_whitespace()
+        .all(|word| haystack.contains(&word.to_lowercase()))
+}
+
+/// Filter, sort and page `themes`. `is_light` tells whether a theme directory is in light mode.
+pub fn run_query(
+    themes: Vec<SysTheme>,
+    library: &ThemeLibrary,
+    is_light: impl Fn(&str) -> bool,
+    query: &ThemeQuery,
+) -> Result<ThemeQueryResult, String> {
+    let target = query.color.as_deref().map(parse_color).transpose()?;
+    let max_distance = query.max_color_distance.unwrap_or(DEFAULT_COLOR_DISTANCE);
+    let tags = theme_library::normalize_tags(query.tags.clone());
+
+    let mut matches: Vec<ThemeListing> = themes
+        .into_iter()
+        .filter(|theme| query.is_custom.map_or(true, |v| theme.is_custom == v))
+        .filter(|theme| query.is_system.map_or(true, |v| theme.is_system == v))
+        .filter(|theme| {
+            query
+                .text
+                .as_deref()
+                .map_or(true, |t| matches_text(theme, t))
+        })
+        .filter_map(|theme| {
+            let user = library.get(&theme.dir);
+            if query.favorites_only && !user.favorite {
+                return None;
+            }
+            let theme_tags = theme.manifest.as_ref().map(|m| &m.tags[..]).unwrap_or(&[]);
+            if !tags
+                .iter()
+                .all(|tag| user.tags.contains(tag) || theme_tags.contains(tag))
+            {
+                return None;
+            }
+            let light = is_light(&theme.dir);
+            if query.light.is_some_and(|v| v != light) {
+                return None;
+            }
+            let color_distance = match target {
+                Some(target) => {
+                    Some(palette_distance(&theme, target).filter(|d| *d <= max_distance)?)
+                },
+                None => None,
+            };
+            Some(ThemeListing {
+                theme,
+                user,
+                is_light: light,
+                color_distance,
+            })
+        })
+        .collect();
+
+    let by_title = |a: &ThemeListing, b: &ThemeListing| {
+        a.theme
+            .title
+            .to_lowercase()
+            .cmp(&b.theme.title.to_lowercase())
+            .then_with(|| a.theme.dir.cmp(&b.theme.dir))
+    };
+    matches.sort_by(|a, b| {
+        let primary = match query.sort {
+            ThemeSort::Title => Ordering::Equal,
+            // `None` sorts before `Some`, so reversing puts never-applied themes last
+            ThemeSort::RecentlyApplied => b.user.last_applied.cmp(&a.user.last_applied),
+            ThemeSort::ColorSimilarity => match (a.color_distance, b.color_distance) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                _ => Ordering::Equal,
+            },
+        };
+        primary.then_with(|| by_title(a, b))
+    });
+
+    let total = matches.len();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let items = matches.into_iter().skip(query.offset).take(limit).collect();
+    Ok(ThemeQueryResult {
+        items,
+        total,
+        offset: query.offset,
+        limit,
+    })
+}
+
+#[tauri::command]
+pub async fn query_themes(query: ThemeQuery) -> Result<ThemeQueryResult, String> {
+    let themes = get_sys_themes().await?;
+    let themes_root = safe_path::themes_root()?;
+    run_query(
+        themes,
+        &theme_library::load(),
+        |dir| themes_root.join(dir).join("light.mode").exists(),
+        &query,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PrimaryColors, TerminalColors, ThemeColors, ThemeManifest};
+    use chrono::{TimeZone, Utc};
+
+    fn theme(dir: &str, title: &str, blue: &str, is_custom: bool) -> SysTheme {
+        SysTheme {
+            dir: dir.into(),
+            title: title.into(),
+            description: format!("{title} color scheme"),
+            image: String::new(),
+            is_system: !is_custom,
+            is_custom,
+            colors: Some(ThemeColors {
+                primary: PrimaryColors {
+                    background: "#000000".into(),
+                    foreground: "#ffffff".into(),
+                },
+                terminal: TerminalColors {
+                    red: "#cc3333".into(),
+                    green: "#33cc33".into(),
+                    yellow: "#cccc33".into(),
+                    blue: blue.into(),
+                    magenta: "#cc33cc".into(),
+                    cyan: "#33cccc".into(),
+                },
+            }),
+            manifest: None,
+        }
+    }
+
+    fn themes() -> Vec<SysTheme> {
+        vec![
+            theme("tokyo-night", "Tokyo Night", "#7aa2f7", false),
+            theme("nord", "Nord", "#81a1c1", false),
+            theme("mine", "Mine", "#1e88e5", true),
+            theme("catppuccin", "Catppuccin", "#89b4fa", false),
+        ]
+    }
+
+    fn dirs(result: &ThemeQueryResult) -> Vec<&str> {
+        result.items.iter().map(|l| l.theme.dir.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filters_and_stable_paging() {
+        let library = ThemeLibrary::default();
+        let light = |dir: &str| dir == "catppuccin";
+
+        let all = run_query(themes(), &library, light, &ThemeQuery::default()).unwrap();
+        assert_eq!(dirs(&all), ["catppuccin", "mine", "nord", "tokyo-night"]);
+
+        let page = ThemeQuery {
+            offset: 1,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let result = run_query(themes(), &library, light, &page).unwrap();
+        assert_eq!(dirs(&result), ["mine", "nord"]);
+        assert_eq!(result.total, 4);
+
+        let query = ThemeQuery {
+            text: Some("night SCHEME".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, light, &query).unwrap()),
+            ["tokyo-night"]
+        );
+
+        let query = ThemeQuery {
+            is_custom: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, light, &query).unwrap()),
+            ["mine"]
+        );
+
+        let query = ThemeQuery {
+            light: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, light, &query).unwrap()),
+            ["catppuccin"]
+        );
+    }
+
+    #[test]
+    fn test_tags_favorites_and_recent() {
+        let mut library = ThemeLibrary::default();
+        library.set_tags("nord", vec!["Cold".into(), "calm".into()]);
+        library.set_tags("tokyo-night", vec!["cold".into()]);
+        library.set_favorite("mine", true);
+        library.record_applied("nord", Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        library.record_applied("mine", Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap());
+        let dark = |_: &str| false;
+
+        let query = ThemeQuery {
+            tags: vec!["cold".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, dark, &query).unwrap()),
+            ["nord", "tokyo-night"]
+        );
+
+        // Tags from a theme's manifest count too
+        let mut tagged = themes();
+        tagged[3].manifest = Some(ThemeManifest {
+            tags: vec!["cold".into()],
+            ..Default::default()
+        });
+        assert_eq!(
+            dirs(&run_query(tagged, &library, dark, &query).unwrap()),
+            ["catppuccin", "nord", "tokyo-night"]
+        );
+
+        let query = ThemeQuery {
+            favorites_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, dark, &query).unwrap()),
+            ["mine"]
+        );
+
+        let query = ThemeQuery {
+            sort: ThemeSort::RecentlyApplied,
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, dark, &query).unwrap()),
+            ["mine", "nord", "catppuccin", "tokyo-night"]
+        );
+    }
+
+    #[test]
+    fn test_color_similarity() {
+        let library = ThemeLibrary::default();
+        let query = ThemeQuery {
+            color: Some("blue".into()),
+            sort: ThemeSort::ColorSimilarity,
+            ..Default::default()
+        };
+        let result = run_query(themes(), &library, |_| false, &query).unwrap();
+        // "mine" uses the exact reference blue; the muted Nord blue is too far off
+        assert_eq!(dirs(&result)[0], "mine");
+        assert!(result.items[0].color_distance.unwrap() < 0.01);
+        assert!(!dirs(&result).contains(&"nord"));
+
+        let query = ThemeQuery {
+            color: Some("#81a1c1".into()),
+            max_color_distance: Some(0.01),
+            ..Default::default()
+        };
+        assert_eq!(
+            dirs(&run_query(themes(), &library, |_| false, &query).unwrap()),
+            ["nord"]
+        );
+
+        let query = ThemeQuery {
+            color: Some("not-a-color".into()),
+            ..Default::default()
+        };
+        assert!(run_query(themes(), &library, |_| false, &query).is_err());
+    }
+}
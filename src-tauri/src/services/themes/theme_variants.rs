@@ -0,0 +1,239 @@
+//! Dark/light variant pairs inside one custom theme.
+//!
+//! A theme with a light variant keeps each variant's generated files under
+//! `variants/<dark|light>/`. The theme root holds the active variant's files, chosen by
+//! `light.mode` like any other Omarchy theme, so `omarchy-theme-set` needs nothing new.
+
+use crate::services::config::generators::hypr_conf;
+use crate::services::config::oklch::Oklch;
+use crate::services::config::schema_validation::is_color_field;
+use crate::types::{CustomTheme, ThemeVariant};
+use serde_json::Value;
+use std::path::Path;
+
+pub const VARIANTS_DIR: &str = "variants";
+
+/// Schema of values no generator describes
+static NO_SCHEMA: Value = Value::Null;
+
+/// The variant whose files belong at the theme root
+pub fn active_variant(theme_dir: &Path, theme: &CustomTheme) -> ThemeVariant {
+    if theme.light_apps.is_some() && theme_dir.join("light.mode").exists() {
+        ThemeVariant::Light
+    } else {
+        ThemeVariant::Dark
+    }
+}
+
+/// App data of a variant; themes without a light variant use `apps` for both
+pub fn variant_apps(theme: &CustomTheme, variant: ThemeVariant) -> &Value {
+    match (variant, &theme.light_apps) {
+        (ThemeVariant::Light, Some(light_apps)) => light_apps,
+        _ => &theme.apps,
+    }
+}
+
+fn invert_hex(hex: &str) -> Option<String> {
+    let inverted = Oklch::from_hex(&hex[..6])?.invert_lightness().to_hex();
+    Some(format!("{}{}", &inverted[1..], &hex[6..]))
+}
+
+/// `rrggbb` of an `r,g,b[,a]` tuple
+fn tuple_hex(value: &str) -> Option<String> {
+    let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+    if !matches!(parts.len(), 3 | 4) {
+        return None;
+    }
+    let rgb: Vec<u8> = parts[..3]
+        .iter()
+        .map(|p| p.parse::<u8>().ok())
+        .collect::<Option<_>>()?;
+    Some(format!("{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2]))
+}
+
+/// The `rrggbb` part of a stored color in any of the formats [`invert_color`] accepts, or a
+/// hyprlang `rgb(..)`/`rgba(..)`. Alpha is dropped.
+pub fn color_hex(value: &str) -> Option<String> {
+    let value = value.trim();
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(hex[..6].to_ascii_lowercase());
+    }
+    let hex = hypr_conf::parse_color(value).or_else(|| tuple_hex(value))?;
+    Some(hex[..6].to_ascii_lowercase())
+}
+
+/// Invert the lightness of one stored color, keeping its format: `#rrggbb`, `rrggbb`, either
+/// with an alpha suffix, or `r,g,b[,a]`. Anything else is not a color and yields `None`.
+pub fn invert_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    let (hash, hex) = match value.strip_prefix('#') {
+        Some(hex) => ("#", hex),
+        None => ("", value),
+    };
+    if matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return invert_hex(hex).map(|inverted| format!("{hash}{inverted}"));
+    }
+
+    let inverted = invert_hex(&tuple_hex(value)?)?;
+    let channel = |i: usize| u8::from_str_radix(&inverted[i..i + 2], 16).unwrap_or_default();
+    let mut out = format!("{},{},{}", channel(0), channel(2), channel(4));
+    if let Some(alpha) = value.split(',').nth(3).map(str::trim) {
+        out.push(',');
+        out.push_str(alpha);
+    }
+    Some(out)
+}
+
+/// A starting light variant: every color in `apps` with its OKLCH lightness inverted.
+/// Hue and chroma are kept, so accents stay recognizable while backgrounds turn light.
+/// Only fields `schemas` (app name to generator schema) marks as colors are touched.
+pub fn derive_light_variant(apps: &Value, schemas: &Value) -> Value {
+    match apps {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(app, data)| {
+                    let schema = schemas.get(app).unwrap_or(&NO_SCHEMA);
+                    (app.clone(), invert_colors(data, schema))
+                })
+                .collect(),
+        ),
+        _ => apps.clone(),
+    }
+}
+
+fn invert_colors(value: &Value, schema: &Value) -> Value {
+    match value {
+        Value::String(s) if is_color_field(schema) => {
+            invert_color(s).map_or_else(|| value.clone(), Value::String)
+        },
+        Value::Array(items) => {
+            let item_schema = schema.get("items").unwrap_or(&NO_SCHEMA);
+            Value::Array(
+                items
+                    .iter()
+                    .map(|item| invert_colors(item, item_schema))
+                    .collect(),
+            )
+        },
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, child)| {
+                    let child_schema = schema
+                        .get("properties")
+                        .and_then(|properties| properties.get(key))
+                        .unwrap_or(&NO_SCHEMA);
+                    (key.clone(), invert_colors(child, child_schema))
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::generators::ConfigGeneratorRegistry;
+    use serde_json::json;
+
+    fn schemas() -> Value {
+        let registry = ConfigGeneratorRegistry::new();
+        registry
+            .get_all_apps()
+            .into_iter()
+            .filter_map(|app| Some((app.to_string(), registry.get_schema_for_app(app)?)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    fn lightness(hex: &str) -> f64 {
+        Oklch::from_hex(hex).unwrap().l
+    }
+
+    #[test]
+    fn test_invert_color_keeps_format() {
+        let light = invert_color("#1e1e1e").unwrap();
+        assert!(light.starts_with('#'));
+        assert!(lightness(&light) > 0.7);
+
+        let bare = invert_color("EAEAEA88").unwrap();
+        assert_eq!(bare.len(), 8);
+        assert!(bare.ends_with("88"));
+        assert!(lightness(&bare[..6]) < 0.3);
+
+        let rgba = invert_color("18, 18, 18, 0.9").unwrap();
+        assert!(rgba.ends_with(",0.9"));
+        assert!(rgba.split(',').next().unwrap().parse::<u8>().unwrap() > 150);
+
+        assert!(invert_color("JetBrains Mono").is_none());
+        assert!(invert_color("%H:%M").is_none());
+        assert!(invert_color("1,2").is_none());
+    }
+
+    #[test]
+    fn test_invert_keeps_hue() {
+        let accent = Oklch::from_hex("#7aa2f7").unwrap();
+        let inverted = Oklch::from_hex(&invert_color("#7aa2f7").unwrap()).unwrap();
+        assert!((accent.h - inverted.h).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_derive_light_variant() {
+        let apps = json!({
+            "alacritty": {
+                "colors": { "primary": { "background": "#121212", "foreground": "#eaeaea" } },
+                "font": { "size": 12 }
+            },
+            "hyprland": { "general": { "gaps_in": 5, "layout": "dwindle" } }
+        });
+        let light = derive_light_variant(&apps, &schemas());
+        let primary = &light["alacritty"]["colors"]["primary"];
+        assert!(lightness(primary["background"].as_str().unwrap()) > 0.8);
+        assert!(lightness(primary["foreground"].as_str().unwrap()) < 0.2);
+        assert_eq!(light["alacritty"]["font"], apps["alacritty"]["font"]);
+        assert_eq!(light["hyprland"], apps["hyprland"]);
+    }
+
+    #[test]
+    fn test_derive_light_variant_follows_schema() {
+        let apps = json!({
+            "mako": {
+                "colors": { "normal": { "background_color": "#121212" } },
+                "style": { "padding": "10,20,10,20", "outer_margin": "255,0,0" }
+            },
+            "unknown": { "background": "#121212" }
+        });
+        let light = derive_light_variant(&apps, &schemas());
+        assert_eq!(light["mako"]["style"], apps["mako"]["style"]);
+        assert_eq!(light["unknown"], apps["unknown"]);
+        assert_ne!(light["mako"]["colors"], apps["mako"]["colors"]);
+    }
+
+    #[test]
+    fn test_active_variant_needs_light_apps() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("light.mode"), "").unwrap();
+        let mut theme = CustomTheme {
+            name: "Test".into(),
+            created_at: String::new(),
+            modified_at: String::new(),
+            apps: json!({ "a": 1 }),
+            colors: None,
+            light_apps: None,
+            backgrounds: None,
+        };
+        assert_eq!(active_variant(dir.path(), &theme), ThemeVariant::Dark);
+        assert_eq!(
+            variant_apps(&theme, ThemeVariant::Light),
+            &json!({ "a": 1 })
+        );
+
+        theme.light_apps = Some(json!({ "a": 2 }));
+        assert_eq!(active_variant(dir.path(), &theme), ThemeVariant::Light);
+        assert_eq!(
+            variant_apps(&theme, ThemeVariant::Light),
+            &json!({ "a": 2 })
+        );
+    }
+}
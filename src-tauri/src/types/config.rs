@@ -2,21 +2,53 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::ThemeVariant;
+
 /// Application settings structure
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppSettings {
     /// Whether to automatically apply themes when entering edit mode
     pub auto_apply_theme: bool,
+    /// Time-of-day theme switching. `None` when a client leaves it out, in which case the
+    /// saved schedule is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ThemeSchedule>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             auto_apply_theme: true,
+            schedule: None,
         }
     }
 }
 
+/// Rules for applying themes automatically through the day
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ThemeSchedule {
+    pub enabled: bool,
+    /// Location for sunrise/sunset rules, in degrees (north and east positive)
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub rules: Vec<ScheduleRule>,
+}
+
+/// Apply `theme` at `at`: a local `HH:MM`, or `sunrise`/`sunset` with an optional minute
+/// offset such as `sunset-30`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduleRule {
+    pub at: String,
+    /// Theme directory name, as passed to `omarchy-theme-set`
+    pub theme: String,
+    /// Light or dark variant to switch to before applying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<ThemeVariant>,
+}
+
 /// Settings file structure with version and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsFile {
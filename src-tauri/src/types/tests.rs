@@ -12,6 +12,7 @@ mod tests {
     fn test_app_settings_serialization() {
         let settings = AppSettings {
             auto_apply_theme: false,
+            schedule: None,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -85,11 +86,14 @@ mod tests {
             modified_at: "2023-01-01T00:00:00Z".to_string(),
             apps: serde_json::json!({}),
             colors: None,
+            light_apps: None,
+            backgrounds: None,
         };
 
         let json = serde_json::to_string(&theme).unwrap();
         let deserialized: CustomTheme = serde_json::from_str(&json).unwrap();
 
         assert_eq!(theme.name, deserialized.name);
+        assert!(!json.contains("light_apps"));
     }
 }
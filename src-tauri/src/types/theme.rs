@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// Theme definition from themes.toml
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Theme {
     pub title: String,
     pub description: String,
@@ -11,6 +11,12 @@ pub struct Theme {
     pub image_url: String,
     #[serde(rename = "type")]
     pub theme_type: Vec<String>,
+    /// Repository to clone when installing the theme
+    #[serde(rename = "gitUrl", default, skip_serializing_if = "Option::is_none")]
+    pub git_url: Option<String>,
+    /// Directory name to install into; derived from the repository name when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
 }
 
 /// Container for theme data from themes.toml
@@ -27,6 +33,64 @@ pub struct CustomTheme {
     pub modified_at: String,
     pub apps: Value,                 // Dynamic structure for all app configurations
     pub colors: Option<ThemeColors>, // Extracted color palette
+    /// App configurations of the light variant; `apps` is then the dark one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub light_apps: Option<Value>,
+    /// Order, active entry and rotation of the images in `backgrounds/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backgrounds: Option<BackgroundPlaylist>,
+}
+
+/// Per-theme wallpaper playlist over the files in `backgrounds/`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BackgroundPlaylist {
+    /// File names in playlist order; files missing here follow alphabetically
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub active: Option<String>,
+    /// Minutes between automatic switches to the next background; 0 disables rotation
+    #[serde(default)]
+    pub rotate_minutes: u32,
+}
+
+/// Descriptive metadata from a theme's `theme.toml`; every field is optional
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ThemeManifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Preview image file name inside the theme directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+/// One half of a dark/light theme pair
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeVariant {
+    Dark,
+    Light,
+}
+
+impl ThemeVariant {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThemeVariant::Dark => "dark",
+            ThemeVariant::Light => "light",
+        }
+    }
 }
 
 /// Complete color palette extracted from a theme